@@ -0,0 +1,19 @@
+-- Make timestamp columns NOT NULL
+-- 모든 행은 DEFAULT CURRENT_TIMESTAMP로 값이 채워지므로, 기존 NULL 값만 보정한 뒤 제약 조건을 추가합니다.
+UPDATE posts SET created_at = CURRENT_TIMESTAMP WHERE created_at IS NULL;
+UPDATE posts SET updated_at = created_at WHERE updated_at IS NULL;
+ALTER TABLE posts
+    ALTER COLUMN created_at SET NOT NULL,
+    ALTER COLUMN updated_at SET NOT NULL;
+
+UPDATE comments SET created_at = CURRENT_TIMESTAMP WHERE created_at IS NULL;
+UPDATE comments SET updated_at = created_at WHERE updated_at IS NULL;
+ALTER TABLE comments
+    ALTER COLUMN created_at SET NOT NULL,
+    ALTER COLUMN updated_at SET NOT NULL;
+
+UPDATE users SET created_at = CURRENT_TIMESTAMP WHERE created_at IS NULL;
+UPDATE users SET updated_at = created_at WHERE updated_at IS NULL;
+ALTER TABLE users
+    ALTER COLUMN created_at SET NOT NULL,
+    ALTER COLUMN updated_at SET NOT NULL;
@@ -0,0 +1,21 @@
+-- Add nesting depth to comments
+-- 최상위 댓글의 깊이는 1이며, 대댓글은 부모 댓글의 깊이 + 1입니다.
+-- 작성 시점에 계산하여 저장하므로 최대 깊이 검사에 재귀 조회가 필요하지 않습니다.
+ALTER TABLE comments ADD COLUMN IF NOT EXISTS depth INTEGER NOT NULL DEFAULT 1;
+
+-- 기존 댓글의 깊이를 계산합니다.
+WITH RECURSIVE tree AS (
+    SELECT id, 1 AS depth
+    FROM comments
+    WHERE parent_id IS NULL
+    UNION ALL
+    SELECT c.id, tree.depth + 1
+    FROM comments c
+    JOIN tree ON c.parent_id = tree.id
+)
+UPDATE comments
+SET depth = tree.depth
+FROM tree
+WHERE comments.id = tree.id;
+
+ALTER TABLE comments ADD CONSTRAINT comments_depth_positive CHECK (depth >= 1);
@@ -0,0 +1,16 @@
+-- Add server-side access token revocation
+-- token_version은 JWT의 ver 클레임과 비교하며, 값을 올리면 그 사용자의 기존 액세스 토큰이 모두 무효가 됩니다 (logout-all).
+ALTER TABLE users ADD COLUMN IF NOT EXISTS token_version INTEGER NOT NULL DEFAULT 0;
+
+-- 개별 액세스 토큰의 폐기 목록입니다 (logout).
+-- 토큰이 만료되면 더 이상 필요하지 않으므로 expires_at 이후에 정리합니다.
+CREATE TABLE IF NOT EXISTS revoked_tokens (
+    jti UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    revoked_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create indexes
+CREATE INDEX IF NOT EXISTS idx_revoked_tokens_user_id ON revoked_tokens(user_id);
+CREATE INDEX IF NOT EXISTS idx_revoked_tokens_expires_at ON revoked_tokens(expires_at);
@@ -0,0 +1,21 @@
+-- Add user roles and the moderator action log
+-- 역할은 선언 순서대로 권한이 커집니다 (user < moderator < admin).
+CREATE TYPE user_role AS ENUM ('user', 'moderator', 'admin');
+ALTER TABLE users ADD COLUMN IF NOT EXISTS role user_role NOT NULL DEFAULT 'user';
+
+-- 모더레이터가 다른 사용자의 게시글이나 댓글을 수정, 삭제한 기록입니다.
+-- 대상이 삭제된 뒤에도 기록이 남도록 target_id에는 외래 키를 걸지 않습니다.
+CREATE TYPE moderator_action AS ENUM ('update_post', 'delete_post', 'update_comment', 'delete_comment');
+
+CREATE TABLE IF NOT EXISTS moderator_actions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    moderator_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    action moderator_action NOT NULL,
+    target_id UUID NOT NULL,
+    target_author_id UUID NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create indexes
+CREATE INDEX IF NOT EXISTS idx_moderator_actions_moderator_id ON moderator_actions(moderator_id);
+CREATE INDEX IF NOT EXISTS idx_moderator_actions_created_at ON moderator_actions(created_at DESC, id DESC);
@@ -0,0 +1,21 @@
+-- Add Email Verification
+-- 이메일 인증을 마친 사용자만 게시글을 작성할 수 있습니다.
+-- 이 기능 이전에 가입한 사용자는 인증된 것으로 간주합니다.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS email_verified_at TIMESTAMP WITH TIME ZONE;
+UPDATE users SET email_verified_at = created_at WHERE email_verified_at IS NULL;
+
+-- 인증 토큰은 원문 대신 SHA-256 해시만 저장하며, 한 번 사용하면 used_at이 기록됩니다.
+-- 토큰을 발급할 때의 이메일을 함께 저장하여, 이메일이 바뀐 뒤에는 이전 토큰으로 인증할 수 없게 합니다.
+CREATE TABLE IF NOT EXISTS email_verification_tokens (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    email VARCHAR(255) NOT NULL,
+    token_hash VARCHAR(64) NOT NULL,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    used_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create indexes
+CREATE UNIQUE INDEX IF NOT EXISTS idx_email_verification_tokens_token_hash ON email_verification_tokens(token_hash);
+CREATE INDEX IF NOT EXISTS idx_email_verification_tokens_user_id ON email_verification_tokens(user_id);
@@ -0,0 +1,38 @@
+-- Enforce Unique Usernames
+-- 사용자 이름은 대소문자를 구분하지 않고 유일해야 합니다.
+-- 이미 중복된 이름은 먼저 가입한 사용자만 그대로 두고, 나머지는 ID 앞부분을 붙여 구분합니다.
+-- 붙인 이름도 이미 사용 중이면 사용하지 않는 이름이 나올 때까지 ID를 더 길게 붙입니다.
+DO $$
+DECLARE
+    dup RECORD;
+    suffix TEXT;
+    candidate TEXT;
+    attempt INT;
+BEGIN
+    FOR dup IN
+        SELECT u.id, u.username FROM users u
+        WHERE EXISTS (
+            SELECT 1 FROM users o
+            WHERE LOWER(o.username) = LOWER(u.username)
+              AND (o.created_at, o.id) < (u.created_at, u.id)
+        )
+        ORDER BY u.created_at, u.id
+    LOOP
+        attempt := 0;
+        LOOP
+            suffix := '_' || LEFT(REPLACE(dup.id::TEXT, '-', ''), LEAST(8 + attempt * 4, 32));
+            -- ID 32자를 모두 붙여도 겹치면 순번을 더 붙입니다.
+            IF attempt > 6 THEN
+                suffix := suffix || '_' || (attempt - 6);
+            END IF;
+            candidate := LEFT(dup.username, 255 - LENGTH(suffix)) || suffix;
+            EXIT WHEN NOT EXISTS (SELECT 1 FROM users WHERE LOWER(username) = LOWER(candidate));
+            attempt := attempt + 1;
+        END LOOP;
+
+        UPDATE users SET username = candidate WHERE id = dup.id;
+    END LOOP;
+END $$;
+
+DROP INDEX IF EXISTS idx_users_username;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_users_username_lower ON users(LOWER(username));
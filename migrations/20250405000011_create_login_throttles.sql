@@ -0,0 +1,27 @@
+-- Create Login Throttling Tables
+-- 로그인 실패 횟수를 계정(정규화한 이메일)과 클라이언트 IP별로 기록합니다.
+-- 가입하지 않은 이메일도 같은 방식으로 기록하여, 제한 여부로 가입 여부가 드러나지 않게 합니다.
+CREATE TABLE IF NOT EXISTS login_throttles (
+    scope VARCHAR(16) NOT NULL,               -- 'account' 또는 'ip'
+    subject VARCHAR(255) NOT NULL,            -- 소문자로 바꾼 이메일 또는 IP 주소
+    failed_count INTEGER NOT NULL DEFAULT 0,  -- 마지막 실패 이후 실패 기록 유지 시간 안에 연속으로 실패한 횟수
+    last_failed_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    locked_until TIMESTAMP WITH TIME ZONE,    -- 이 시각까지 로그인 시도를 거부합니다
+    PRIMARY KEY (scope, subject)
+);
+
+-- Create Auth Audit Log Table
+-- 계정 잠금 같은 보안 관련 사건을 기록합니다.
+CREATE TABLE IF NOT EXISTS auth_audit_log (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    event VARCHAR(50) NOT NULL,
+    user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    email VARCHAR(255),
+    ip_address VARCHAR(45),
+    details TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create indexes
+CREATE INDEX IF NOT EXISTS idx_auth_audit_log_user_id ON auth_audit_log(user_id);
+CREATE INDEX IF NOT EXISTS idx_auth_audit_log_created_at ON auth_audit_log(created_at);
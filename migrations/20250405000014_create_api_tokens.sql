@@ -0,0 +1,20 @@
+-- Create API Tokens Table
+-- 스크립트나 봇이 사용자 대신 API를 호출할 때 사용하는 개인 API 토큰입니다.
+-- 토큰 원문 대신 SHA-256 해시만 저장하며, 목록에서 토큰을 구분할 수 있도록 앞부분(token_prefix)만 따로 저장합니다.
+-- scopes에는 토큰으로 허용한 권한(posts:read, comments:write 등)을 저장합니다.
+CREATE TABLE IF NOT EXISTS api_tokens (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    token_prefix VARCHAR(16) NOT NULL,
+    token_hash VARCHAR(64) NOT NULL,
+    scopes TEXT[] NOT NULL,
+    expires_at TIMESTAMP WITH TIME ZONE,
+    last_used_at TIMESTAMP WITH TIME ZONE,
+    revoked_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create indexes
+CREATE UNIQUE INDEX IF NOT EXISTS idx_api_tokens_token_hash ON api_tokens(token_hash);
+CREATE INDEX IF NOT EXISTS idx_api_tokens_user_id ON api_tokens(user_id);
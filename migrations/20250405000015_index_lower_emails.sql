@@ -0,0 +1,4 @@
+-- Index Emails Case-Insensitively
+-- 로그인, 비밀번호 재설정 등은 대소문자를 구분하지 않고(LOWER(email)) 사용자를 찾습니다.
+-- 새로 저장하는 이메일은 소문자로 정규화하지만, 이전에 대소문자만 다르게 가입한 계정이 있을 수 있으므로 unique 인덱스는 만들지 않습니다.
+CREATE INDEX IF NOT EXISTS idx_users_email_lower ON users(LOWER(email));
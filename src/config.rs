@@ -0,0 +1,862 @@
+// config.rs
+// 애플리케이션 설정을 정의하고 로드합니다.
+// 설정 값은 다음 우선순위로 결정됩니다 (아래로 갈수록 우선순위가 높습니다).
+//   1. 코드에 정의된 기본값
+//   2. TOML 설정 파일 (CONFIG_FILE 환경 변수로 지정, 기본값은 ./config.toml이며 없으면 건너뜁니다)
+//   3. 환경 변수 (HOST, PORT, DATABASE_URL, JWT_SECRET 등)
+// 로드된 설정은 validate()로 검증한 뒤 web::Data로 애플리케이션에 주입됩니다.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use jsonwebtoken::Algorithm;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::{env, fmt, fs, path::Path, str::FromStr};
+
+// 설정 파일 경로를 지정하는 환경 변수 이름과 기본 경로입니다.
+const CONFIG_FILE_ENV: &str = "CONFIG_FILE";
+const DEFAULT_CONFIG_FILE: &str = "config.toml";
+
+// 비밀번호의 최대 길이입니다. bcrypt는 72바이트 이후의 입력을 무시합니다.
+pub const MAX_PASSWORD_LENGTH: usize = 72;
+
+// 애플리케이션 전체 설정입니다.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub server: ServerConfig,          // HTTP 서버 설정
+    pub database: DatabaseConfig,      // 데이터베이스 연결 풀 설정
+    pub jwt: JwtConfig,                // JWT 발급 설정
+    pub security: SecurityConfig,      // 비밀번호 해싱 등 보안 설정
+    pub pagination: PaginationConfig,  // 페이지네이션 설정
+    pub comments: CommentsConfig,      // 댓글 작성 및 트리 조회 설정
+    pub board: BoardConfig,            // 게시판 권한 규칙 설정
+    pub mail: MailConfig,              // 메일 발송 설정
+    pub mfa: MfaConfig,                // 2단계 인증(TOTP) 설정
+    pub oidc: OidcConfig,              // 외부 ID 공급자(OIDC) 로그인 설정
+}
+
+// HTTP 서버 설정입니다.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    pub host: String,                     // 바인딩할 호스트
+    pub port: u16,                        // 바인딩할 포트
+    pub workers: usize,                   // 워커 스레드 수 (0이면 CPU 코어 수)
+    pub client_request_timeout_secs: u64, // 요청 헤더 수신 제한 시간
+}
+
+// 데이터베이스 연결 풀 설정입니다.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseConfig {
+    pub url: String,                // PostgreSQL 연결 문자열 (필수)
+    pub max_connections: u32,       // 최대 연결 수
+    pub min_connections: u32,       // 유지할 최소 연결 수
+    pub acquire_timeout_secs: u64,  // 연결 획득 대기 시간
+    pub idle_timeout_secs: u64,     // 유휴 연결 유지 시간
+}
+
+// JWT 발급 설정입니다.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct JwtConfig {
+    pub secret: String,         // HS256 서명 키 (keys가 비어 있을 때 필수)
+    pub issuer: String,         // 토큰 발급자 (iss 클레임)
+    pub audience: String,       // 토큰 수신자 (aud 클레임)
+    pub leeway_secs: u64,       // exp, nbf, iat 검증 시 허용하는 시계 오차
+    pub ttl_secs: i64,          // 액세스 토큰 유효 시간
+    pub refresh_ttl_secs: i64,  // 리프레시 토큰 유효 시간
+    pub revocation_cache_ttl_secs: u64,  // 토큰 폐기 상태 캐시 유지 시간 (0이면 캐시하지 않음)
+    pub signing_kid: String,    // 새 토큰 서명에 사용할 키의 kid
+    pub keys: Vec<JwtKeyConfig>,  // 비대칭 서명 키 목록 (비어 있으면 secret으로 HS256 서명)
+}
+
+// 비대칭 JWT 서명 키 설정입니다.
+// 키를 교체할 때는 새 키를 추가하고 signing_kid를 바꾼 뒤, 이전 키는 private_key_file 없이
+// 기존 토큰이 모두 만료될 때까지 남겨 둡니다.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JwtKeyConfig {
+    pub kid: String,                       // 토큰 헤더와 JWKS에 노출되는 키 ID
+    pub algorithm: Algorithm,              // RS256, ES256, EdDSA 중 하나
+    pub public_key_file: String,           // 공개 키 PEM 파일 경로
+    #[serde(default)]
+    pub private_key_file: Option<String>,  // 개인 키 PEM 파일 경로 (검증 전용 키는 생략)
+}
+
+// 보안 관련 설정입니다.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SecurityConfig {
+    pub password_hash_algorithm: PasswordAlgorithm, // 새로 저장하는 비밀번호 해시 알고리즘 (bcrypt, argon2id)
+    pub bcrypt_cost: u32,                 // bcrypt 해싱 비용
+    pub argon2_memory_kib: u32,           // Argon2id 메모리 사용량 (KiB)
+    pub argon2_iterations: u32,           // Argon2id 반복 횟수
+    pub argon2_parallelism: u32,          // Argon2id 병렬 처리 수
+    pub password_min_length: usize,       // 비밀번호 최소 길이 (문자 수)
+    pub password_require_letter: bool,    // 영문자 포함 필수 여부
+    pub password_require_digit: bool,     // 숫자 포함 필수 여부
+    pub password_require_uppercase: bool, // 대문자 포함 필수 여부
+    pub password_require_symbol: bool,    // 특수문자 포함 필수 여부
+    pub email_verification_ttl_secs: i64,       // 이메일 인증 토큰 유효 시간
+    pub email_verification_resend_secs: i64,    // 인증 메일 재발송 최소 간격
+    pub password_reset_ttl_secs: i64,           // 비밀번호 재설정 토큰 유효 시간
+    pub password_reset_resend_secs: i64,        // 재설정 메일 재발송 최소 간격
+    pub login_failure_window_secs: i64,         // 마지막 실패 후 이 시간이 지나면 실패 횟수를 초기화
+    pub login_account_free_attempts: i32,       // 계정별로 대기 없이 허용하는 연속 실패 횟수
+    pub login_account_lockout_threshold: i32,   // 계정을 잠그는 연속 실패 횟수
+    pub login_ip_free_attempts: i32,            // IP별로 대기 없이 허용하는 연속 실패 횟수
+    pub login_ip_lockout_threshold: i32,        // IP를 잠그는 연속 실패 횟수
+    pub login_backoff_base_secs: i64,           // 첫 대기 시간 (실패할 때마다 두 배로 늘어남)
+    pub login_backoff_max_secs: i64,            // 최대 대기 시간
+    pub login_lockout_secs: i64,                // 잠금 유지 시간
+}
+
+// 페이지네이션 설정입니다.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PaginationConfig {
+    pub default_per_page: i64,  // per_page가 없을 때 사용할 값
+    pub max_per_page: i64,      // per_page의 최댓값
+}
+
+// 댓글 작성 및 트리 조회 설정입니다.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CommentsConfig {
+    pub max_depth: i32,           // 대댓글 최대 중첩 깊이 (최상위 댓글이 1)
+    pub tree_default_depth: i32,  // depth가 없을 때 펼칠 깊이
+    pub tree_max_depth: i32,      // depth의 최댓값
+    pub tree_default_limit: i64,  // limit이 없을 때 단계별로 보여줄 댓글 수
+    pub tree_max_limit: i64,      // limit의 최댓값
+}
+
+// 게시판 권한 규칙 설정입니다. 실제 판단은 policy 모듈에서 이루어집니다.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BoardConfig {
+    pub post_author_can_delete_comments: bool,  // 게시글 작성자가 자기 게시글의 댓글을 삭제할 수 있는지 여부
+}
+
+// 메일 발송 설정입니다.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MailConfig {
+    pub transport: MailTransport,  // 발송 방식 (log, file, smtp)
+    pub from: String,              // 보내는 사람 (From 헤더)
+    pub file_dir: String,          // file 방식에서 메일을 저장할 디렉터리
+    pub verify_email_url: String,  // 인증 메일에 넣을 링크 (?token=이 붙습니다)
+    pub reset_password_url: String,  // 비밀번호 재설정 메일에 넣을 링크 (?token=이 붙습니다)
+    pub smtp: SmtpConfig,          // smtp 방식의 서버 설정
+}
+
+// 2단계 인증(TOTP) 설정입니다.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MfaConfig {
+    pub encryption_key: String,       // TOTP 비밀 키 암호화 키 (base64로 인코딩한 32바이트, 비어 있으면 등록 불가)
+    pub issuer: String,               // 인증 앱에 표시할 서비스 이름
+    pub challenge_ttl_secs: i64,      // 로그인 후 인증 코드를 입력해야 하는 시간
+    pub challenge_max_attempts: i32,  // 하나의 MFA 토큰으로 시도할 수 있는 최대 횟수
+    pub recovery_code_count: usize,   // 발급할 복구 코드 수
+    pub totp_skew_steps: u32,         // 허용할 시계 오차 (30초 단위, 앞뒤)
+}
+
+// 외부 ID 공급자(OIDC) 로그인 설정입니다.
+// discovery_url이 비어 있으면 OIDC 로그인을 사용하지 않습니다.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OidcConfig {
+    pub provider: String,         // user_identities에 기록할 공급자 이름 (예: google)
+    pub discovery_url: String,    // 공급자의 discovery 문서 주소 (.../.well-known/openid-configuration)
+    pub client_id: String,        // 공급자에 등록한 클라이언트 ID
+    pub client_secret: String,    // 클라이언트 시크릿 (비어 있으면 공개 클라이언트로 PKCE만 사용)
+    pub redirect_uri: String,     // 공급자에 등록한 redirect URI (code와 state를 받을 프런트엔드 주소)
+    pub scopes: String,           // 요청할 scope (공백으로 구분, openid 포함)
+    pub state_ttl_secs: i64,      // 공급자로 이동한 뒤 로그인을 마쳐야 하는 시간
+    pub http_timeout_secs: u64,   // 공급자 요청 타임아웃
+}
+
+// 메일 발송 방식입니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MailTransport {
+    Log,   // 메일 내용을 로그로 출력합니다 (로컬 개발용)
+    File,  // 메일을 .eml 파일로 저장합니다 (로컬 개발 및 테스트용)
+    Smtp,  // SMTP 서버로 발송합니다
+}
+
+// SMTP 서버 설정입니다.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SmtpConfig {
+    pub host: String,            // SMTP 서버 호스트
+    pub port: u16,               // SMTP 서버 포트
+    pub security: SmtpSecurity,  // 연결 암호화 방식
+    pub username: String,        // 인증 사용자 이름 (비어 있으면 인증하지 않음)
+    pub password: String,        // 인증 비밀번호
+    pub timeout_secs: u64,       // 연결 및 응답 대기 시간
+}
+
+// 비밀번호 해시 알고리즘입니다.
+// 저장된 해시는 접두사로 알고리즘을 구분하므로, 설정을 바꿔도 기존 해시로 로그인할 수 있습니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PasswordAlgorithm {
+    Bcrypt,    // bcrypt ($2b$...)
+    Argon2id,  // Argon2id ($argon2id$...)
+}
+
+// SMTP 연결 암호화 방식입니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpSecurity {
+    None,      // 암호화하지 않음 (로컬 테스트 서버용)
+    StartTls,  // 평문으로 연결한 뒤 STARTTLS로 전환 (보통 587 포트)
+    Tls,       // 처음부터 TLS로 연결 (보통 465 포트)
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            host: "127.0.0.1".to_string(),
+            port: 8080,
+            workers: 0,
+            client_request_timeout_secs: 5,
+        }
+    }
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            max_connections: 5,
+            min_connections: 0,
+            acquire_timeout_secs: 30,
+            idle_timeout_secs: 600,
+        }
+    }
+}
+
+impl Default for JwtConfig {
+    fn default() -> Self {
+        Self {
+            secret: String::new(),
+            issuer: "rust_study".to_string(),
+            audience: "rust_study_api".to_string(),
+            leeway_secs: 30,
+            ttl_secs: 15 * 60,
+            refresh_ttl_secs: 30 * 24 * 60 * 60,
+            revocation_cache_ttl_secs: 30,
+            signing_kid: String::new(),
+            keys: Vec::new(),
+        }
+    }
+}
+
+impl JwtConfig {
+    // 비대칭 서명 키 설정의 문제를 찾습니다. 키 파일의 내용은 JwtKeys를 만들 때 검사합니다.
+    fn key_problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        let mut kids = HashSet::new();
+
+        for key in &self.keys {
+            if key.kid.trim().is_empty() {
+                problems.push("jwt.keys[].kid must not be empty".to_string());
+            } else if !kids.insert(key.kid.as_str()) {
+                problems.push(format!("jwt.keys kid '{}' is duplicated", key.kid));
+            }
+            if !matches!(key.algorithm, Algorithm::RS256 | Algorithm::ES256 | Algorithm::EdDSA) {
+                problems.push(format!("jwt.keys '{}' algorithm must be RS256, ES256 or EdDSA", key.kid));
+            }
+        }
+
+        match self.keys.iter().find(|key| key.kid == self.signing_kid) {
+            None => problems.push("jwt.signing_kid (JWT_SIGNING_KID) must match one of jwt.keys".to_string()),
+            Some(key) if key.private_key_file.is_none() => {
+                problems.push(format!("jwt.keys '{}' is used for signing but has no private_key_file", key.kid));
+            }
+            Some(_) => {}
+        }
+        problems
+    }
+}
+
+impl Default for SecurityConfig {
+    fn default() -> Self {
+        Self {
+            password_hash_algorithm: PasswordAlgorithm::Argon2id,
+            bcrypt_cost: bcrypt::DEFAULT_COST,
+            // OWASP 권장값 (19 MiB, 2회, 병렬 1)
+            argon2_memory_kib: 19 * 1024,
+            argon2_iterations: 2,
+            argon2_parallelism: 1,
+            password_min_length: 8,
+            password_require_letter: true,
+            password_require_digit: true,
+            password_require_uppercase: false,
+            password_require_symbol: false,
+            email_verification_ttl_secs: 60 * 60 * 24,
+            email_verification_resend_secs: 60,
+            password_reset_ttl_secs: 60 * 60,
+            password_reset_resend_secs: 60,
+            login_failure_window_secs: 15 * 60,
+            login_account_free_attempts: 3,
+            login_account_lockout_threshold: 10,
+            login_ip_free_attempts: 10,
+            login_ip_lockout_threshold: 50,
+            login_backoff_base_secs: 1,
+            login_backoff_max_secs: 60,
+            login_lockout_secs: 15 * 60,
+        }
+    }
+}
+
+impl Default for PaginationConfig {
+    fn default() -> Self {
+        Self {
+            default_per_page: 10,
+            max_per_page: 100,
+        }
+    }
+}
+
+impl Default for CommentsConfig {
+    fn default() -> Self {
+        Self {
+            max_depth: 10,
+            tree_default_depth: 3,
+            tree_max_depth: 10,
+            tree_default_limit: 10,
+            tree_max_limit: 50,
+        }
+    }
+}
+
+impl Default for BoardConfig {
+    fn default() -> Self {
+        Self {
+            post_author_can_delete_comments: true,
+        }
+    }
+}
+
+impl Default for MailConfig {
+    fn default() -> Self {
+        Self {
+            transport: MailTransport::Log,
+            from: "Rust Study Board <no-reply@localhost>".to_string(),
+            file_dir: "mail".to_string(),
+            verify_email_url: "http://localhost:8080/verify-email".to_string(),
+            reset_password_url: "http://localhost:8080/reset-password".to_string(),
+            smtp: SmtpConfig::default(),
+        }
+    }
+}
+
+impl Default for MfaConfig {
+    fn default() -> Self {
+        Self {
+            encryption_key: String::new(),
+            issuer: "Rust Study Board".to_string(),
+            challenge_ttl_secs: 5 * 60,
+            challenge_max_attempts: 5,
+            recovery_code_count: 10,
+            totp_skew_steps: 1,
+        }
+    }
+}
+
+impl Default for OidcConfig {
+    fn default() -> Self {
+        Self {
+            provider: "oidc".to_string(),
+            discovery_url: String::new(),
+            client_id: String::new(),
+            client_secret: String::new(),
+            redirect_uri: String::new(),
+            scopes: "openid email profile".to_string(),
+            state_ttl_secs: 10 * 60,
+            http_timeout_secs: 10,
+        }
+    }
+}
+
+impl Default for SmtpConfig {
+    fn default() -> Self {
+        Self {
+            host: String::new(),
+            port: 587,
+            security: SmtpSecurity::StartTls,
+            username: String::new(),
+            password: String::new(),
+            timeout_secs: 10,
+        }
+    }
+}
+
+// 환경 변수(MAIL_TRANSPORT)로 발송 방식을 지정할 수 있도록 문자열을 파싱합니다.
+impl FromStr for MailTransport {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "log" => Ok(MailTransport::Log),
+            "file" => Ok(MailTransport::File),
+            "smtp" => Ok(MailTransport::Smtp),
+            _ => Err(format!("expected one of log, file, smtp but got '{}'", s)),
+        }
+    }
+}
+
+// 환경 변수(PASSWORD_HASH_ALGORITHM)로 해시 알고리즘을 지정할 수 있도록 문자열을 파싱합니다.
+impl FromStr for PasswordAlgorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bcrypt" => Ok(PasswordAlgorithm::Bcrypt),
+            "argon2id" => Ok(PasswordAlgorithm::Argon2id),
+            _ => Err(format!("expected one of bcrypt, argon2id but got '{}'", s)),
+        }
+    }
+}
+
+// 환경 변수(SMTP_SECURITY)로 암호화 방식을 지정할 수 있도록 문자열을 파싱합니다.
+impl FromStr for SmtpSecurity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(SmtpSecurity::None),
+            "starttls" => Ok(SmtpSecurity::StartTls),
+            "tls" => Ok(SmtpSecurity::Tls),
+            _ => Err(format!("expected one of none, starttls, tls but got '{}'", s)),
+        }
+    }
+}
+
+// 설정 로드 및 검증 과정에서 발생하는 에러입니다.
+#[derive(Debug)]
+pub enum ConfigError {
+    File { path: String, message: String },      // 설정 파일을 읽거나 파싱할 수 없음
+    Env { key: &'static str, message: String },  // 환경 변수 값이 올바르지 않음
+    Invalid(Vec<String>),                        // 검증 실패 항목 목록
+    Key { kid: String, message: String },        // JWT 서명 키를 읽거나 해석할 수 없음
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::File { path, message } => {
+                write!(f, "failed to load config file '{}': {}", path, message)
+            }
+            ConfigError::Env { key, message } => {
+                write!(f, "invalid value for environment variable {}: {}", key, message)
+            }
+            ConfigError::Key { kid, message } => {
+                write!(f, "failed to load JWT key '{}': {}", kid, message)
+            }
+            ConfigError::Invalid(problems) => {
+                write!(f, "invalid configuration:")?;
+                for problem in problems {
+                    write!(f, "\n  - {}", problem)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Config {
+    // 기본값, 설정 파일, 환경 변수 순서로 설정을 로드하고 검증합니다.
+    pub fn load() -> Result<Self, ConfigError> {
+        let mut config = match env::var(CONFIG_FILE_ENV) {
+            // 명시적으로 지정된 설정 파일은 반드시 존재해야 합니다.
+            Ok(path) => Self::from_file(&path)?,
+            Err(_) if Path::new(DEFAULT_CONFIG_FILE).exists() => Self::from_file(DEFAULT_CONFIG_FILE)?,
+            Err(_) => Self::default(),
+        };
+        config.apply_env()?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    // TOML 설정 파일을 읽습니다. 파일에 없는 값은 기본값으로 채워집니다.
+    pub fn from_file(path: &str) -> Result<Self, ConfigError> {
+        let contents = fs::read_to_string(path).map_err(|e| ConfigError::File {
+            path: path.to_string(),
+            message: e.to_string(),
+        })?;
+        toml::from_str(&contents).map_err(|e| ConfigError::File {
+            path: path.to_string(),
+            message: e.to_string(),
+        })
+    }
+
+    // 환경 변수로 설정 값을 덮어씁니다.
+    pub fn apply_env(&mut self) -> Result<(), ConfigError> {
+        override_from_env("HOST", &mut self.server.host)?;
+        override_from_env("PORT", &mut self.server.port)?;
+        override_from_env("SERVER_WORKERS", &mut self.server.workers)?;
+        override_from_env("SERVER_CLIENT_REQUEST_TIMEOUT_SECS", &mut self.server.client_request_timeout_secs)?;
+
+        override_from_env("DATABASE_URL", &mut self.database.url)?;
+        override_from_env("DATABASE_MAX_CONNECTIONS", &mut self.database.max_connections)?;
+        override_from_env("DATABASE_MIN_CONNECTIONS", &mut self.database.min_connections)?;
+        override_from_env("DATABASE_ACQUIRE_TIMEOUT_SECS", &mut self.database.acquire_timeout_secs)?;
+        override_from_env("DATABASE_IDLE_TIMEOUT_SECS", &mut self.database.idle_timeout_secs)?;
+
+        override_from_env("JWT_SECRET", &mut self.jwt.secret)?;
+        override_from_env("JWT_ISSUER", &mut self.jwt.issuer)?;
+        override_from_env("JWT_AUDIENCE", &mut self.jwt.audience)?;
+        override_from_env("JWT_LEEWAY_SECS", &mut self.jwt.leeway_secs)?;
+        override_from_env("JWT_TTL_SECS", &mut self.jwt.ttl_secs)?;
+        override_from_env("JWT_REFRESH_TTL_SECS", &mut self.jwt.refresh_ttl_secs)?;
+        override_from_env("JWT_REVOCATION_CACHE_TTL_SECS", &mut self.jwt.revocation_cache_ttl_secs)?;
+        override_from_env("JWT_SIGNING_KID", &mut self.jwt.signing_kid)?;
+
+        override_from_env("PASSWORD_HASH_ALGORITHM", &mut self.security.password_hash_algorithm)?;
+        override_from_env("BCRYPT_COST", &mut self.security.bcrypt_cost)?;
+        override_from_env("ARGON2_MEMORY_KIB", &mut self.security.argon2_memory_kib)?;
+        override_from_env("ARGON2_ITERATIONS", &mut self.security.argon2_iterations)?;
+        override_from_env("ARGON2_PARALLELISM", &mut self.security.argon2_parallelism)?;
+        override_from_env("PASSWORD_MIN_LENGTH", &mut self.security.password_min_length)?;
+        override_from_env("PASSWORD_REQUIRE_LETTER", &mut self.security.password_require_letter)?;
+        override_from_env("PASSWORD_REQUIRE_DIGIT", &mut self.security.password_require_digit)?;
+        override_from_env("PASSWORD_REQUIRE_UPPERCASE", &mut self.security.password_require_uppercase)?;
+        override_from_env("PASSWORD_REQUIRE_SYMBOL", &mut self.security.password_require_symbol)?;
+
+        override_from_env("PAGINATION_DEFAULT_PER_PAGE", &mut self.pagination.default_per_page)?;
+        override_from_env("PAGINATION_MAX_PER_PAGE", &mut self.pagination.max_per_page)?;
+
+        override_from_env("COMMENTS_MAX_DEPTH", &mut self.comments.max_depth)?;
+        override_from_env("COMMENTS_TREE_DEFAULT_DEPTH", &mut self.comments.tree_default_depth)?;
+        override_from_env("COMMENTS_TREE_MAX_DEPTH", &mut self.comments.tree_max_depth)?;
+        override_from_env("COMMENTS_TREE_DEFAULT_LIMIT", &mut self.comments.tree_default_limit)?;
+        override_from_env("COMMENTS_TREE_MAX_LIMIT", &mut self.comments.tree_max_limit)?;
+
+        override_from_env("BOARD_POST_AUTHOR_CAN_DELETE_COMMENTS", &mut self.board.post_author_can_delete_comments)?;
+
+        override_from_env("EMAIL_VERIFICATION_TTL_SECS", &mut self.security.email_verification_ttl_secs)?;
+        override_from_env("EMAIL_VERIFICATION_RESEND_SECS", &mut self.security.email_verification_resend_secs)?;
+        override_from_env("PASSWORD_RESET_TTL_SECS", &mut self.security.password_reset_ttl_secs)?;
+        override_from_env("PASSWORD_RESET_RESEND_SECS", &mut self.security.password_reset_resend_secs)?;
+        override_from_env("LOGIN_FAILURE_WINDOW_SECS", &mut self.security.login_failure_window_secs)?;
+        override_from_env("LOGIN_ACCOUNT_FREE_ATTEMPTS", &mut self.security.login_account_free_attempts)?;
+        override_from_env("LOGIN_ACCOUNT_LOCKOUT_THRESHOLD", &mut self.security.login_account_lockout_threshold)?;
+        override_from_env("LOGIN_IP_FREE_ATTEMPTS", &mut self.security.login_ip_free_attempts)?;
+        override_from_env("LOGIN_IP_LOCKOUT_THRESHOLD", &mut self.security.login_ip_lockout_threshold)?;
+        override_from_env("LOGIN_BACKOFF_BASE_SECS", &mut self.security.login_backoff_base_secs)?;
+        override_from_env("LOGIN_BACKOFF_MAX_SECS", &mut self.security.login_backoff_max_secs)?;
+        override_from_env("LOGIN_LOCKOUT_SECS", &mut self.security.login_lockout_secs)?;
+
+        override_from_env("MAIL_TRANSPORT", &mut self.mail.transport)?;
+        override_from_env("MAIL_FROM", &mut self.mail.from)?;
+        override_from_env("MAIL_FILE_DIR", &mut self.mail.file_dir)?;
+        override_from_env("MAIL_VERIFY_EMAIL_URL", &mut self.mail.verify_email_url)?;
+        override_from_env("MAIL_RESET_PASSWORD_URL", &mut self.mail.reset_password_url)?;
+        override_from_env("SMTP_HOST", &mut self.mail.smtp.host)?;
+        override_from_env("SMTP_PORT", &mut self.mail.smtp.port)?;
+        override_from_env("SMTP_SECURITY", &mut self.mail.smtp.security)?;
+        override_from_env("SMTP_USERNAME", &mut self.mail.smtp.username)?;
+        override_from_env("SMTP_PASSWORD", &mut self.mail.smtp.password)?;
+        override_from_env("SMTP_TIMEOUT_SECS", &mut self.mail.smtp.timeout_secs)?;
+
+        override_from_env("MFA_ENCRYPTION_KEY", &mut self.mfa.encryption_key)?;
+        override_from_env("MFA_ISSUER", &mut self.mfa.issuer)?;
+        override_from_env("MFA_CHALLENGE_TTL_SECS", &mut self.mfa.challenge_ttl_secs)?;
+        override_from_env("MFA_CHALLENGE_MAX_ATTEMPTS", &mut self.mfa.challenge_max_attempts)?;
+        override_from_env("MFA_RECOVERY_CODE_COUNT", &mut self.mfa.recovery_code_count)?;
+        override_from_env("MFA_TOTP_SKEW_STEPS", &mut self.mfa.totp_skew_steps)?;
+
+        override_from_env("OIDC_PROVIDER", &mut self.oidc.provider)?;
+        override_from_env("OIDC_DISCOVERY_URL", &mut self.oidc.discovery_url)?;
+        override_from_env("OIDC_CLIENT_ID", &mut self.oidc.client_id)?;
+        override_from_env("OIDC_CLIENT_SECRET", &mut self.oidc.client_secret)?;
+        override_from_env("OIDC_REDIRECT_URI", &mut self.oidc.redirect_uri)?;
+        override_from_env("OIDC_SCOPES", &mut self.oidc.scopes)?;
+        override_from_env("OIDC_STATE_TTL_SECS", &mut self.oidc.state_ttl_secs)?;
+        override_from_env("OIDC_HTTP_TIMEOUT_SECS", &mut self.oidc.http_timeout_secs)?;
+        Ok(())
+    }
+
+    // 설정 값의 유효성을 검사합니다.
+    // 문제가 여러 개인 경우 한 번에 모두 보고합니다.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let mut problems = Vec::new();
+
+        if self.server.host.trim().is_empty() {
+            problems.push("server.host (HOST) must not be empty".to_string());
+        }
+        if self.server.port == 0 {
+            problems.push("server.port (PORT) must be between 1 and 65535".to_string());
+        }
+
+        if self.database.url.trim().is_empty() {
+            problems.push("database.url (DATABASE_URL) must be set".to_string());
+        }
+        if self.database.max_connections == 0 {
+            problems.push("database.max_connections must be at least 1".to_string());
+        }
+        if self.database.min_connections > self.database.max_connections {
+            problems.push("database.min_connections must not exceed database.max_connections".to_string());
+        }
+        if self.database.acquire_timeout_secs == 0 {
+            problems.push("database.acquire_timeout_secs must be at least 1".to_string());
+        }
+
+        if self.jwt.keys.is_empty() {
+            if self.jwt.secret.len() < 32 {
+                problems.push("jwt.secret (JWT_SECRET) must be set and at least 32 bytes long".to_string());
+            }
+        } else {
+            problems.extend(self.jwt.key_problems());
+        }
+        if self.jwt.issuer.trim().is_empty() {
+            problems.push("jwt.issuer (JWT_ISSUER) must not be empty".to_string());
+        }
+        if self.jwt.audience.trim().is_empty() {
+            problems.push("jwt.audience (JWT_AUDIENCE) must not be empty".to_string());
+        }
+        if self.jwt.leeway_secs >= self.jwt.ttl_secs.max(0) as u64 {
+            problems.push("jwt.leeway_secs (JWT_LEEWAY_SECS) must be less than jwt.ttl_secs".to_string());
+        }
+        if self.jwt.ttl_secs <= 0 {
+            problems.push("jwt.ttl_secs (JWT_TTL_SECS) must be positive".to_string());
+        }
+        if self.jwt.refresh_ttl_secs <= self.jwt.ttl_secs {
+            problems.push("jwt.refresh_ttl_secs (JWT_REFRESH_TTL_SECS) must be greater than jwt.ttl_secs".to_string());
+        }
+
+        if !(4..=31).contains(&self.security.bcrypt_cost) {
+            problems.push("security.bcrypt_cost (BCRYPT_COST) must be between 4 and 31".to_string());
+        }
+        if let Err(e) = argon2::Params::new(
+            self.security.argon2_memory_kib,
+            self.security.argon2_iterations,
+            self.security.argon2_parallelism,
+            None,
+        ) {
+            problems.push(format!(
+                "security.argon2_memory_kib, argon2_iterations, argon2_parallelism (ARGON2_MEMORY_KIB, ARGON2_ITERATIONS, ARGON2_PARALLELISM) are not valid Argon2 parameters: {}",
+                e
+            ));
+        }
+        // bcrypt는 72바이트까지만 사용하므로, 최대 길이(MAX_PASSWORD_LENGTH)보다 작아야 합니다.
+        if !(1..=MAX_PASSWORD_LENGTH).contains(&self.security.password_min_length) {
+            problems.push(format!(
+                "security.password_min_length (PASSWORD_MIN_LENGTH) must be between 1 and {}",
+                MAX_PASSWORD_LENGTH
+            ));
+        }
+
+        if self.security.email_verification_ttl_secs <= 0 {
+            problems.push("security.email_verification_ttl_secs (EMAIL_VERIFICATION_TTL_SECS) must be positive".to_string());
+        }
+        if self.security.email_verification_resend_secs < 0 {
+            problems.push("security.email_verification_resend_secs (EMAIL_VERIFICATION_RESEND_SECS) must not be negative".to_string());
+        }
+        if self.security.password_reset_ttl_secs <= 0 {
+            problems.push("security.password_reset_ttl_secs (PASSWORD_RESET_TTL_SECS) must be positive".to_string());
+        }
+        if self.security.password_reset_resend_secs < 0 {
+            problems.push("security.password_reset_resend_secs (PASSWORD_RESET_RESEND_SECS) must not be negative".to_string());
+        }
+        if self.security.login_failure_window_secs <= 0 {
+            problems.push("security.login_failure_window_secs (LOGIN_FAILURE_WINDOW_SECS) must be positive".to_string());
+        }
+        if self.security.login_account_free_attempts < 0
+            || self.security.login_account_lockout_threshold <= self.security.login_account_free_attempts
+        {
+            problems.push(
+                "security.login_account_lockout_threshold (LOGIN_ACCOUNT_LOCKOUT_THRESHOLD) must be greater than security.login_account_free_attempts, which must not be negative"
+                    .to_string(),
+            );
+        }
+        if self.security.login_ip_free_attempts < 0
+            || self.security.login_ip_lockout_threshold <= self.security.login_ip_free_attempts
+        {
+            problems.push(
+                "security.login_ip_lockout_threshold (LOGIN_IP_LOCKOUT_THRESHOLD) must be greater than security.login_ip_free_attempts, which must not be negative"
+                    .to_string(),
+            );
+        }
+        if self.security.login_backoff_base_secs < 0 || self.security.login_backoff_max_secs < self.security.login_backoff_base_secs {
+            problems.push(
+                "security.login_backoff_max_secs (LOGIN_BACKOFF_MAX_SECS) must not be less than security.login_backoff_base_secs, which must not be negative"
+                    .to_string(),
+            );
+        }
+        if self.security.login_lockout_secs <= 0 {
+            problems.push("security.login_lockout_secs (LOGIN_LOCKOUT_SECS) must be positive".to_string());
+        }
+
+        if self.pagination.max_per_page < 1 {
+            problems.push("pagination.max_per_page must be at least 1".to_string());
+        }
+        if self.pagination.default_per_page < 1 || self.pagination.default_per_page > self.pagination.max_per_page {
+            problems.push("pagination.default_per_page must be between 1 and pagination.max_per_page".to_string());
+        }
+
+        if self.comments.max_depth < 1 {
+            problems.push("comments.max_depth (COMMENTS_MAX_DEPTH) must be at least 1".to_string());
+        }
+        if self.comments.tree_max_depth < 1 {
+            problems.push("comments.tree_max_depth must be at least 1".to_string());
+        }
+        if self.comments.tree_default_depth < 1 || self.comments.tree_default_depth > self.comments.tree_max_depth {
+            problems.push("comments.tree_default_depth must be between 1 and comments.tree_max_depth".to_string());
+        }
+        if self.comments.tree_max_limit < 1 {
+            problems.push("comments.tree_max_limit must be at least 1".to_string());
+        }
+        if self.comments.tree_default_limit < 1 || self.comments.tree_default_limit > self.comments.tree_max_limit {
+            problems.push("comments.tree_default_limit must be between 1 and comments.tree_max_limit".to_string());
+        }
+
+        problems.extend(self.mail.problems());
+        problems.extend(self.mfa.problems());
+        problems.extend(self.oidc.problems());
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError::Invalid(problems))
+        }
+    }
+
+    // 서버가 바인딩할 주소를 반환합니다.
+    pub fn bind_address(&self) -> (String, u16) {
+        (self.server.host.clone(), self.server.port)
+    }
+}
+
+impl MfaConfig {
+    // 2단계 인증 설정의 문제점을 모읍니다.
+    fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        if !self.encryption_key.is_empty() && self.encryption_key_bytes().is_none() {
+            problems.push("mfa.encryption_key (MFA_ENCRYPTION_KEY) must be 32 bytes encoded as base64".to_string());
+        }
+        if self.issuer.trim().is_empty() || self.issuer.contains(':') {
+            problems.push("mfa.issuer (MFA_ISSUER) must not be empty or contain ':'".to_string());
+        }
+        if self.challenge_ttl_secs <= 0 {
+            problems.push("mfa.challenge_ttl_secs (MFA_CHALLENGE_TTL_SECS) must be positive".to_string());
+        }
+        if self.challenge_max_attempts < 1 {
+            problems.push("mfa.challenge_max_attempts (MFA_CHALLENGE_MAX_ATTEMPTS) must be at least 1".to_string());
+        }
+        if !(1..=50).contains(&self.recovery_code_count) {
+            problems.push("mfa.recovery_code_count (MFA_RECOVERY_CODE_COUNT) must be between 1 and 50".to_string());
+        }
+        if self.totp_skew_steps > 10 {
+            problems.push("mfa.totp_skew_steps (MFA_TOTP_SKEW_STEPS) must be at most 10".to_string());
+        }
+        problems
+    }
+
+    // base64로 인코딩된 암호화 키를 디코딩합니다. 키가 없거나 32바이트가 아니면 None을 반환합니다.
+    pub fn encryption_key_bytes(&self) -> Option<[u8; 32]> {
+        STANDARD.decode(self.encryption_key.trim()).ok()?.try_into().ok()
+    }
+}
+
+impl OidcConfig {
+    // OIDC 로그인을 사용하는지 여부입니다.
+    pub fn is_enabled(&self) -> bool {
+        !self.discovery_url.trim().is_empty()
+    }
+
+    // OIDC 설정의 문제점을 모읍니다. 나머지 항목은 discovery_url이 설정되어 있을 때만 확인합니다.
+    fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if !self.is_enabled() {
+            return problems;
+        }
+
+        let is_http_url = |url: &str| url.starts_with("https://") || url.starts_with("http://");
+        if !is_http_url(&self.discovery_url) {
+            problems.push("oidc.discovery_url (OIDC_DISCOVERY_URL) must be an http(s) URL".to_string());
+        }
+        if self.provider.trim().is_empty() || self.provider.len() > 50 {
+            problems.push("oidc.provider (OIDC_PROVIDER) must be between 1 and 50 characters".to_string());
+        }
+        if self.client_id.trim().is_empty() {
+            problems.push("oidc.client_id (OIDC_CLIENT_ID) must be set when oidc.discovery_url is set".to_string());
+        }
+        if !is_http_url(&self.redirect_uri) {
+            problems.push("oidc.redirect_uri (OIDC_REDIRECT_URI) must be an http(s) URL".to_string());
+        }
+        if !self.scopes.split_whitespace().any(|scope| scope == "openid") {
+            problems.push("oidc.scopes (OIDC_SCOPES) must include openid".to_string());
+        }
+        if self.state_ttl_secs <= 0 {
+            problems.push("oidc.state_ttl_secs (OIDC_STATE_TTL_SECS) must be positive".to_string());
+        }
+        if self.http_timeout_secs == 0 {
+            problems.push("oidc.http_timeout_secs (OIDC_HTTP_TIMEOUT_SECS) must be at least 1".to_string());
+        }
+        problems
+    }
+}
+
+impl MailConfig {
+    // 메일 설정의 문제점을 모읍니다. SMTP 항목은 smtp 방식일 때만 확인합니다.
+    fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        if self.from.trim().is_empty() {
+            problems.push("mail.from (MAIL_FROM) must not be empty".to_string());
+        }
+        if self.verify_email_url.trim().is_empty() {
+            problems.push("mail.verify_email_url (MAIL_VERIFY_EMAIL_URL) must not be empty".to_string());
+        }
+        if self.reset_password_url.trim().is_empty() {
+            problems.push("mail.reset_password_url (MAIL_RESET_PASSWORD_URL) must not be empty".to_string());
+        }
+        match self.transport {
+            MailTransport::Log => {}
+            MailTransport::File => {
+                if self.file_dir.trim().is_empty() {
+                    problems.push("mail.file_dir (MAIL_FILE_DIR) must be set when mail.transport is file".to_string());
+                }
+            }
+            MailTransport::Smtp => {
+                if self.smtp.host.trim().is_empty() {
+                    problems.push("mail.smtp.host (SMTP_HOST) must be set when mail.transport is smtp".to_string());
+                }
+                if self.smtp.port == 0 {
+                    problems.push("mail.smtp.port (SMTP_PORT) must be between 1 and 65535".to_string());
+                }
+                if self.smtp.username.is_empty() != self.smtp.password.is_empty() {
+                    problems.push("mail.smtp.username and mail.smtp.password must be set together".to_string());
+                }
+                if self.smtp.timeout_secs == 0 {
+                    problems.push("mail.smtp.timeout_secs must be at least 1".to_string());
+                }
+            }
+        }
+        problems
+    }
+}
+
+// 환경 변수가 설정되어 있으면 파싱하여 대상 값을 덮어씁니다.
+fn override_from_env<T>(key: &'static str, target: &mut T) -> Result<(), ConfigError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    if let Ok(raw) = env::var(key) {
+        *target = raw.parse().map_err(|e: T::Err| ConfigError::Env {
+            key,
+            message: e.to_string(),
+        })?;
+    }
+    Ok(())
+}
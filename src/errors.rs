@@ -0,0 +1,322 @@
+// errors.rs
+// 애플리케이션 전역에서 사용하는 에러 타입을 정의합니다.
+// 모든 서비스와 핸들러는 AppError를 반환하며, ResponseError 구현을 통해
+// 항상 같은 형태의 JSON 에러 응답으로 변환됩니다.
+//
+// 응답 형식:
+// {
+//   "code": "NOT_FOUND",
+//   "message": "Post not found",
+//   "fields": [{ "field": "title", "message": "..." }],
+//   "request_id": "uuid"
+// }
+
+use actix_web::{
+    error::{JsonPayloadError, PathError, QueryPayloadError},
+    http::{header, StatusCode},
+    HttpRequest, HttpResponse, ResponseError,
+};
+use serde::Serialize;
+use std::fmt;
+
+// 필드 단위의 검증 에러입니다.
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldError {
+    pub field: String,    // 문제가 된 필드 이름
+    pub message: String,  // 사람이 읽을 수 있는 에러 메시지
+}
+
+// 액세스 토큰이 거부된 이유입니다.
+// 클라이언트가 토큰을 갱신할지, 다시 로그인할지 구분할 수 있도록 에러 코드와
+// WWW-Authenticate 헤더의 error_description으로 구분해서 전달합니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenError {
+    Expired,        // exp가 지남
+    NotYetValid,    // nbf 또는 iat가 아직 도래하지 않음
+    BadSignature,   // 서명 불일치
+    Malformed,      // 디코딩할 수 없는 토큰
+    InvalidClaims,  // iss, aud 등 클레임 값이 올바르지 않거나 누락됨
+    Revoked,        // 로그아웃 등으로 서버에서 폐기됨
+}
+
+impl TokenError {
+    // 에러 응답 본문에 포함되는 고정 에러 코드입니다.
+    pub fn code(&self) -> &'static str {
+        match self {
+            TokenError::Expired => "TOKEN_EXPIRED",
+            TokenError::NotYetValid => "TOKEN_NOT_YET_VALID",
+            TokenError::BadSignature => "TOKEN_BAD_SIGNATURE",
+            TokenError::Malformed => "TOKEN_MALFORMED",
+            TokenError::InvalidClaims => "TOKEN_INVALID_CLAIMS",
+            TokenError::Revoked => "TOKEN_REVOKED",
+        }
+    }
+
+    // 에러 메시지와 WWW-Authenticate 헤더의 error_description으로 사용합니다.
+    pub fn description(&self) -> &'static str {
+        match self {
+            TokenError::Expired => "The access token expired",
+            TokenError::NotYetValid => "The access token is not yet valid",
+            TokenError::BadSignature => "The access token signature is invalid",
+            TokenError::Malformed => "The access token is malformed",
+            TokenError::InvalidClaims => "The access token claims are invalid",
+            TokenError::Revoked => "The access token has been revoked",
+        }
+    }
+}
+
+// AppError는 애플리케이션에서 발생할 수 있는 모든 에러를 표현합니다.
+#[derive(Debug)]
+pub enum AppError {
+    NotFound(String),      // 리소스가 존재하지 않음 (404)
+    Forbidden(String),     // 권한 없음 (403)
+    Conflict(String),      // 중복 등 현재 상태와 충돌 (409)
+    BadRequest(String),    // 잘못된 요청 형식 (400)
+    Validation {           // 입력값 검증 실패 (422)
+        message: String,
+        fields: Vec<FieldError>,
+    },
+    Unauthorized(String),  // 인증 실패 (401)
+    InvalidToken(TokenError),  // 액세스 토큰 검증 실패 (401)
+    RateLimited {          // 요청 횟수 제한 초과 (429)
+        message: String,
+        retry_after: Option<u64>,  // 재시도까지 남은 시간 (초)
+    },
+    Internal(String),      // 서버 내부 오류 (500), 메시지는 클라이언트에 노출하지 않습니다
+}
+
+// 클라이언트에 반환되는 에러 응답 본문입니다.
+// 필드 구성이 바뀌지 않도록 값이 없더라도 항상 모든 키를 포함합니다.
+#[derive(Debug, Serialize)]
+pub struct ErrorBody<'a> {
+    pub code: &'static str,
+    pub message: &'a str,
+    pub fields: &'a [FieldError],
+    pub request_id: Option<&'a str>,
+}
+
+impl AppError {
+    // 단일 필드에 대한 검증 에러를 생성합니다.
+    pub fn validation(field: &str, message: &str) -> Self {
+        AppError::Validation {
+            message: "Validation failed".to_string(),
+            fields: vec![FieldError {
+                field: field.to_string(),
+                message: message.to_string(),
+            }],
+        }
+    }
+
+    // 에러 응답 본문에 포함되는 고정 에러 코드입니다.
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::NotFound(_) => "NOT_FOUND",
+            AppError::Forbidden(_) => "FORBIDDEN",
+            AppError::Conflict(_) => "CONFLICT",
+            AppError::BadRequest(_) => "BAD_REQUEST",
+            AppError::Validation { .. } => "VALIDATION_FAILED",
+            AppError::Unauthorized(_) => "UNAUTHORIZED",
+            AppError::InvalidToken(kind) => kind.code(),
+            AppError::RateLimited { .. } => "RATE_LIMITED",
+            AppError::Internal(_) => "INTERNAL_ERROR",
+        }
+    }
+
+    // 클라이언트에 노출해도 되는 메시지를 반환합니다.
+    fn public_message(&self) -> &str {
+        match self {
+            AppError::NotFound(msg)
+            | AppError::Forbidden(msg)
+            | AppError::Conflict(msg)
+            | AppError::BadRequest(msg)
+            | AppError::Unauthorized(msg) => msg,
+            AppError::InvalidToken(kind) => kind.description(),
+            AppError::Validation { message, .. } | AppError::RateLimited { message, .. } => message,
+            // 내부 오류의 상세 내용은 로그에만 남깁니다.
+            AppError::Internal(_) => "Internal server error",
+        }
+    }
+
+    // 요청 ID를 포함한 에러 응답을 생성합니다.
+    // RequestId 미들웨어가 요청 ID를 채워 넣을 때 사용합니다.
+    pub fn response_with_request_id(&self, request_id: Option<&str>) -> HttpResponse {
+        let fields: &[FieldError] = match self {
+            AppError::Validation { fields, .. } => fields,
+            _ => &[],
+        };
+
+        let mut builder = HttpResponse::build(self.status_code());
+        match self {
+            AppError::Unauthorized(_) => {
+                builder.insert_header((header::WWW_AUTHENTICATE, "Bearer"));
+            }
+            // RFC 6750 형식으로 토큰이 거부된 이유를 알려줍니다.
+            AppError::InvalidToken(kind) => {
+                builder.insert_header((
+                    header::WWW_AUTHENTICATE,
+                    format!(r#"Bearer error="invalid_token", error_description="{}""#, kind.description()),
+                ));
+            }
+            AppError::RateLimited { retry_after: Some(secs), .. } => {
+                builder.insert_header((header::RETRY_AFTER, secs.to_string()));
+            }
+            _ => {}
+        }
+
+        builder.json(ErrorBody {
+            code: self.code(),
+            message: self.public_message(),
+            fields,
+            request_id,
+        })
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::Internal(msg) => write!(f, "Internal error: {}", msg),
+            other => write!(f, "{}", other.public_message()),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl ResponseError for AppError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Forbidden(_) => StatusCode::FORBIDDEN,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            AppError::Validation { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::Unauthorized(_) | AppError::InvalidToken(_) => StatusCode::UNAUTHORIZED,
+            AppError::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+            AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        self.response_with_request_id(None)
+    }
+}
+
+// 데이터베이스 에러를 AppError로 변환합니다.
+// 유니크 제약 조건 위반은 Conflict로, 참조 대상이 없는 FK 위반은 NotFound로,
+// 그 외의 에러는 Internal로 처리합니다.
+impl From<sqlx::Error> for AppError {
+    fn from(err: sqlx::Error) -> Self {
+        match &err {
+            sqlx::Error::RowNotFound => AppError::NotFound("Resource not found".to_string()),
+            sqlx::Error::Database(db_err) if db_err.code().as_deref() == Some("23505") => {
+                AppError::Conflict("Resource already exists".to_string())
+            }
+            sqlx::Error::Database(db_err) if db_err.code().as_deref() == Some("23503") => {
+                AppError::NotFound("Referenced resource not found".to_string())
+            }
+            _ => {
+                log::error!("database error: {}", err);
+                AppError::Internal(err.to_string())
+            }
+        }
+    }
+}
+
+// DTO 검증 에러를 필드별 에러 목록을 담은 Validation으로 변환합니다.
+// 규칙에 메시지가 지정되지 않은 경우 규칙 코드를 메시지로 사용합니다.
+impl From<validator::ValidationErrors> for AppError {
+    fn from(errors: validator::ValidationErrors) -> Self {
+        let mut fields: Vec<FieldError> = errors
+            .field_errors()
+            .into_iter()
+            .flat_map(|(field, errors)| {
+                errors.iter().map(move |error| FieldError {
+                    field: field.to_string(),
+                    message: error
+                        .message
+                        .as_ref()
+                        .map_or_else(|| format!("failed {} check", error.code), |m| m.to_string()),
+                })
+            })
+            .collect();
+        // HashMap 순서에 영향을 받지 않도록 필드 이름으로 정렬합니다.
+        fields.sort_by(|a, b| a.field.cmp(&b.field));
+
+        AppError::Validation {
+            message: "Validation failed".to_string(),
+            fields,
+        }
+    }
+}
+
+impl From<bcrypt::BcryptError> for AppError {
+    fn from(err: bcrypt::BcryptError) -> Self {
+        log::error!("password hashing error: {}", err);
+        AppError::Internal(err.to_string())
+    }
+}
+
+impl From<argon2::password_hash::Error> for AppError {
+    fn from(err: argon2::password_hash::Error) -> Self {
+        log::error!("password hashing error: {}", err);
+        AppError::Internal(err.to_string())
+    }
+}
+
+// 외부 ID 공급자(OIDC)에 보낸 요청이 실패했거나 응답을 해석할 수 없는 경우입니다.
+impl From<reqwest::Error> for AppError {
+    fn from(err: reqwest::Error) -> Self {
+        log::error!("identity provider request error: {}", err);
+        AppError::Internal(err.to_string())
+    }
+}
+
+// 메일 발송에 실패한 경우입니다.
+impl From<crate::mail::MailError> for AppError {
+    fn from(err: crate::mail::MailError) -> Self {
+        log::error!("mail delivery error: {}", err);
+        AppError::Internal(err.to_string())
+    }
+}
+
+// 토큰 디코딩 실패를 원인별 TokenError로 분류합니다.
+impl From<&jsonwebtoken::errors::Error> for TokenError {
+    fn from(err: &jsonwebtoken::errors::Error) -> Self {
+        use jsonwebtoken::errors::ErrorKind;
+
+        match err.kind() {
+            ErrorKind::ExpiredSignature => TokenError::Expired,
+            ErrorKind::ImmatureSignature => TokenError::NotYetValid,
+            ErrorKind::InvalidSignature => TokenError::BadSignature,
+            ErrorKind::InvalidIssuer
+            | ErrorKind::InvalidAudience
+            | ErrorKind::InvalidSubject
+            | ErrorKind::InvalidAlgorithm
+            | ErrorKind::MissingRequiredClaim(_) => TokenError::InvalidClaims,
+            _ => TokenError::Malformed,
+        }
+    }
+}
+
+impl From<jsonwebtoken::errors::Error> for AppError {
+    fn from(err: jsonwebtoken::errors::Error) -> Self {
+        log::error!("token encoding error: {}", err);
+        AppError::Internal(err.to_string())
+    }
+}
+
+// 요청 본문(JSON)의 역직렬화 실패를 AppError로 변환합니다.
+pub fn json_error_handler(err: JsonPayloadError, _req: &HttpRequest) -> actix_web::Error {
+    AppError::BadRequest(format!("Invalid JSON body: {}", err)).into()
+}
+
+// 쿼리 문자열의 역직렬화 실패를 AppError로 변환합니다.
+pub fn query_error_handler(err: QueryPayloadError, _req: &HttpRequest) -> actix_web::Error {
+    AppError::BadRequest(format!("Invalid query string: {}", err)).into()
+}
+
+// 경로 매개변수의 역직렬화 실패를 AppError로 변환합니다.
+pub fn path_error_handler(err: PathError, _req: &HttpRequest) -> actix_web::Error {
+    AppError::BadRequest(format!("Invalid path parameter: {}", err)).into()
+}
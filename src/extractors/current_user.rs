@@ -0,0 +1,57 @@
+// current_user.rs
+// AuthMiddleware가 인증한 현재 사용자를 핸들러 인자로 꺼내는 추출기입니다.
+// 인증하지 않은 요청이면 401을 반환하므로, Auth::optional() 아래의 핸들러에서는 Option<CurrentUser>로 받습니다.
+//
+// 예:
+// pub async fn get_posts(user: Option<CurrentUser>, ...) -> Result<HttpResponse, AppError>
+
+use actix_web::{dev::{Extensions, Payload}, FromRequest, HttpMessage, HttpRequest};
+use std::future::{ready, Ready};
+use uuid::Uuid;
+
+use crate::errors::AppError;
+use crate::models::api_token::{Credential, Scope};
+use crate::models::user::{Actor, Role};
+
+// CurrentUser는 요청을 보낸 인증된 사용자와 인증에 사용한 자격 증명입니다.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CurrentUser {
+    pub id: Uuid,                // 사용자 ID
+    pub role: Role,              // 사용자 역할
+    pub credential: Credential,  // 액세스 토큰 또는 API 토큰
+}
+
+impl CurrentUser {
+    // 요청의 확장(extensions)에서 현재 사용자를 꺼냅니다. 인증하지 않은 요청이면 None을 반환합니다.
+    pub fn from_extensions(extensions: &Extensions) -> Option<Self> {
+        let actor = extensions.get::<Actor>().copied()?;
+        let credential = extensions.get::<Credential>().cloned().unwrap_or(Credential::AccessToken);
+        Some(Self { id: actor.id, role: actor.role, credential })
+    }
+
+    // 권한 확인에 사용할 Actor를 반환합니다.
+    pub fn actor(&self) -> Actor {
+        Actor { id: self.id, role: self.role }
+    }
+
+    // 현재 자격 증명이 주어진 권한을 가졌는지 확인하고 Actor를 반환합니다.
+    // 액세스 토큰은 모든 권한을 가지며, API 토큰은 만들 때 고른 권한이 없으면 Forbidden을 반환합니다.
+    pub fn require_scope(&self, scope: Scope) -> Result<Actor, AppError> {
+        if !self.credential.allows(scope) {
+            return Err(AppError::Forbidden(format!("API token is missing the {} scope", scope)));
+        }
+        Ok(self.actor())
+    }
+}
+
+impl FromRequest for CurrentUser {
+    type Error = AppError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(
+            CurrentUser::from_extensions(&req.extensions())
+                .ok_or_else(|| AppError::Unauthorized("Authentication required".to_string())),
+        )
+    }
+}
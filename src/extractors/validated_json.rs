@@ -0,0 +1,51 @@
+// validated_json.rs
+// JSON 요청 본문을 역직렬화한 뒤 validator 규칙으로 검증하는 추출기입니다.
+// web::Json과 같은 방식으로 사용하며, 검증에 실패하면 필드별 에러와 함께 422를 반환합니다.
+//
+// 예:
+// pub async fn create_post(dto: ValidatedJson<CreatePostDto>) -> Result<HttpResponse, AppError>
+
+use actix_web::{dev::Payload, web, Error, FromRequest, HttpRequest};
+use futures::future::LocalBoxFuture;
+use serde::de::DeserializeOwned;
+use std::ops::Deref;
+use validator::Validate;
+
+use crate::errors::AppError;
+
+// ValidatedJson은 검증을 통과한 요청 본문을 담습니다.
+#[derive(Debug)]
+pub struct ValidatedJson<T>(pub T);
+
+impl<T> ValidatedJson<T> {
+    // 내부 값을 꺼냅니다.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for ValidatedJson<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> FromRequest for ValidatedJson<T>
+where
+    T: DeserializeOwned + Validate + 'static,
+{
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        // 역직렬화는 web::Json에 맡기므로 JsonConfig의 크기 제한과 에러 핸들러가 그대로 적용됩니다.
+        let json = web::Json::<T>::from_request(req, payload);
+        Box::pin(async move {
+            let value = json.await?.into_inner();
+            value.validate().map_err(AppError::from)?;
+            Ok(ValidatedJson(value))
+        })
+    }
+}
@@ -1,35 +1,139 @@
-use actix_web::{web, HttpResponse, Responder};
-use crate::models::user::{CreateUserDto, LoginDto};
+use actix_web::{http::header, web, HttpResponse};
+use crate::errors::AppError;
+use crate::extractors::validated_json::ValidatedJson;
+use crate::middleware::auth_middleware::{require_current_claims, require_current_user};
+use crate::models::mfa::MfaVerifyDto;
+use crate::models::oidc::OidcCallbackDto;
+use crate::models::user::{
+    CreateUserDto, ForgotPasswordDto, LoginDto, LogoutDto, RefreshTokenDto, ResetPasswordDto, VerifyEmailDto,
+};
 use crate::services::auth_service::AuthService;
+use crate::services::email_verification_service::EmailVerificationService;
+use crate::services::oidc_service::OidcService;
 
+// 가입 후 인증 메일을 보냅니다.
+// 메일 발송에 실패해도 가입은 완료된 것이므로, 사용자는 resend-verification으로 다시 받을 수 있습니다.
 pub async fn register(
     service: web::Data<AuthService>,
-    dto: web::Json<CreateUserDto>,
-) -> impl Responder {
-    match service.register(dto.into_inner()).await {
-        Ok(user) => HttpResponse::Created().json(user),
-        Err(e) => {
-            if e.to_string().contains("already exists") {
-                HttpResponse::Conflict().body(e.to_string())
-            } else {
-                HttpResponse::InternalServerError().finish()
-            }
-        }
+    verification: web::Data<EmailVerificationService>,
+    dto: ValidatedJson<CreateUserDto>,
+) -> Result<HttpResponse, AppError> {
+    let user = service.register(dto.into_inner()).await?;
+    if let Err(e) = verification.send_verification(&user).await {
+        log::warn!("failed to send verification email to user {}: {}", user.id, e);
     }
+    Ok(HttpResponse::Created().json(user))
 }
 
+pub async fn verify_email(
+    service: web::Data<EmailVerificationService>,
+    dto: ValidatedJson<VerifyEmailDto>,
+) -> Result<HttpResponse, AppError> {
+    let user = service.verify_email(&dto.token).await?;
+    Ok(HttpResponse::Ok().json(user))
+}
+
+pub async fn resend_verification(
+    service: web::Data<EmailVerificationService>,
+    req: actix_web::HttpRequest,
+) -> Result<HttpResponse, AppError> {
+    let user_id = require_current_user(&req)?;
+
+    service.resend_verification(user_id).await?;
+    Ok(HttpResponse::Accepted().finish())
+}
+
+// 로그인 실패 제한에는 프록시 헤더(X-Forwarded-For 등) 대신 직접 연결한 클라이언트의 IP를 사용합니다.
 pub async fn login(
     service: web::Data<AuthService>,
-    dto: web::Json<LoginDto>,
-) -> impl Responder {
-    match service.login(dto.into_inner()).await {
-        Ok(auth_response) => HttpResponse::Ok().json(auth_response),
-        Err(e) => {
-            if e.to_string().contains("Invalid credentials") {
-                HttpResponse::Unauthorized().body(e.to_string())
-            } else {
-                HttpResponse::InternalServerError().finish()
-            }
-        }
-    }
+    dto: ValidatedJson<LoginDto>,
+    req: actix_web::HttpRequest,
+) -> Result<HttpResponse, AppError> {
+    let client_ip = req.peer_addr().map(|addr| addr.ip());
+    let outcome = service.login_from(dto.into_inner(), client_ip).await?;
+    Ok(HttpResponse::Ok().json(outcome))
+}
+
+pub async fn verify_mfa(
+    service: web::Data<AuthService>,
+    dto: ValidatedJson<MfaVerifyDto>,
+    req: actix_web::HttpRequest,
+) -> Result<HttpResponse, AppError> {
+    let client_ip = req.peer_addr().map(|addr| addr.ip());
+    let auth_response = service.verify_mfa(dto.into_inner(), client_ip).await?;
+    Ok(HttpResponse::Ok().json(auth_response))
+}
+
+// 외부 ID 공급자(OIDC) 로그인을 시작합니다. 클라이언트는 응답의 authorization_url로 이동합니다.
+pub async fn oidc_authorize(service: web::Data<OidcService>) -> Result<HttpResponse, AppError> {
+    let authorization = service.authorize().await?;
+    Ok(HttpResponse::Ok().json(authorization))
+}
+
+// 공급자가 redirect_uri로 돌려준 code와 state로 로그인을 마칩니다.
+// 응답은 비밀번호 로그인과 같으며, 2단계 인증을 사용하는 사용자는 MFA 토큰을 받습니다.
+pub async fn oidc_callback(
+    service: web::Data<AuthService>,
+    oidc: web::Data<OidcService>,
+    dto: ValidatedJson<OidcCallbackDto>,
+) -> Result<HttpResponse, AppError> {
+    let user = oidc.complete(&dto).await?;
+    let outcome = service.sign_in(user).await?;
+    Ok(HttpResponse::Ok().json(outcome))
+}
+
+pub async fn refresh(
+    service: web::Data<AuthService>,
+    dto: ValidatedJson<RefreshTokenDto>,
+) -> Result<HttpResponse, AppError> {
+    let auth_response = service.refresh(&dto.refresh_token).await?;
+    Ok(HttpResponse::Ok().json(auth_response))
+}
+
+// 요청 본문은 선택이며, 리프레시 토큰을 보내면 함께 폐기합니다.
+pub async fn logout(
+    service: web::Data<AuthService>,
+    dto: Option<ValidatedJson<LogoutDto>>,
+    req: actix_web::HttpRequest,
+) -> Result<HttpResponse, AppError> {
+    let claims = require_current_claims(&req)?;
+    let dto = dto.map(ValidatedJson::into_inner).unwrap_or_default();
+
+    service.logout(&claims, dto.refresh_token.as_deref()).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+pub async fn logout_all(
+    service: web::Data<AuthService>,
+    req: actix_web::HttpRequest,
+) -> Result<HttpResponse, AppError> {
+    let user_id = require_current_user(&req)?;
+
+    service.logout_all(user_id).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+// 이메일의 가입 여부와 관계없이 항상 202를 반환합니다.
+pub async fn forgot_password(
+    service: web::Data<AuthService>,
+    dto: ValidatedJson<ForgotPasswordDto>,
+) -> Result<HttpResponse, AppError> {
+    service.forgot_password(&dto.email).await?;
+    Ok(HttpResponse::Accepted().finish())
+}
+
+pub async fn reset_password(
+    service: web::Data<AuthService>,
+    dto: ValidatedJson<ResetPasswordDto>,
+) -> Result<HttpResponse, AppError> {
+    service.reset_password(&dto.token, &dto.password).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+// 액세스 토큰 검증용 공개 키 목록(JWKS)을 반환합니다.
+// 키 교체가 반영되도록 짧은 시간만 캐시하게 합니다.
+pub async fn jwks(service: web::Data<AuthService>) -> HttpResponse {
+    HttpResponse::Ok()
+        .insert_header((header::CACHE_CONTROL, "public, max-age=300"))
+        .json(service.jwks())
 }
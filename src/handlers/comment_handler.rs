@@ -1,75 +1,105 @@
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{web, HttpResponse};
 use uuid::Uuid;
-use crate::models::comment::{CreateCommentDto, UpdateCommentDto};
-use crate::services::comment_service::CommentService;
-use crate::middleware::auth_middleware::get_current_user;
+use crate::config::Config;
+use crate::errors::AppError;
+use crate::extractors::current_user::CurrentUser;
+use crate::extractors::validated_json::ValidatedJson;
+use crate::models::comment::{CommentTreeQuery, CreateCommentDto, UpdateCommentDto};
+use crate::models::pagination::PaginationQuery;
+use crate::services::{comment_service::CommentService, MutationOutcome};
+use crate::middleware::auth_middleware::require_scope;
+use crate::models::api_token::Scope;
 
 pub async fn create_comment(
     service: web::Data<CommentService>,
     post_id: web::Path<Uuid>,
-    dto: web::Json<CreateCommentDto>,
+    dto: ValidatedJson<CreateCommentDto>,
     req: actix_web::HttpRequest,
-) -> impl Responder {
-    let author_id = match get_current_user(&req.into()) {
-        Some(id) => id,
-        None => return HttpResponse::Unauthorized().finish(),
-    };
+) -> Result<HttpResponse, AppError> {
+    let author_id = require_scope(&req, Scope::CommentsWrite)?.id;
 
-    match service
+    let comment = service
         .create_comment(post_id.into_inner(), author_id, dto.into_inner())
-        .await
-    {
-        Ok(comment) => HttpResponse::Created().json(comment),
-        Err(_) => HttpResponse::InternalServerError().finish(),
-    }
+        .await?;
+    Ok(HttpResponse::Created().json(comment))
 }
 
 pub async fn get_post_comments(
     service: web::Data<CommentService>,
     post_id: web::Path<Uuid>,
+    config: web::Data<Config>,
     query: web::Query<PaginationQuery>,
-) -> impl Responder {
-    let page = query.page.unwrap_or(1);
-    let per_page = query.per_page.unwrap_or(10);
+    user: Option<CurrentUser>,
+) -> Result<HttpResponse, AppError> {
+    // 조회는 로그인 없이도 가능하지만, API 토큰으로 요청했다면 읽기 권한이 있어야 합니다.
+    if let Some(user) = &user {
+        user.require_scope(Scope::CommentsRead)?;
+    }
 
-    match service.get_post_comments(post_id.into_inner(), page, per_page).await {
-        Ok(comments) => HttpResponse::Ok().json(comments),
-        Err(_) => HttpResponse::InternalServerError().finish(),
+    let post_id = post_id.into_inner();
+    if let Some(params) = query.resolve_cursor(&config.pagination)? {
+        let comments = service.get_post_comments_by_cursor(post_id, params).await?;
+        return Ok(HttpResponse::Ok().json(comments));
     }
+
+    let params = query.resolve(&config.pagination)?;
+
+    let comments = service.get_post_comments(post_id, params).await?;
+    Ok(HttpResponse::Ok().json(comments))
+}
+
+// GET /api/posts/{post_id}/comments/tree?depth=3&limit=10
+pub async fn get_comment_tree(
+    service: web::Data<CommentService>,
+    post_id: web::Path<Uuid>,
+    config: web::Data<Config>,
+    query: web::Query<CommentTreeQuery>,
+    user: Option<CurrentUser>,
+) -> Result<HttpResponse, AppError> {
+    // 조회는 로그인 없이도 가능하지만, API 토큰으로 요청했다면 읽기 권한이 있어야 합니다.
+    if let Some(user) = &user {
+        user.require_scope(Scope::CommentsRead)?;
+    }
+
+    let params = query.resolve(&config.comments)?;
+
+    let tree = service.get_comment_tree(post_id.into_inner(), params).await?;
+    Ok(HttpResponse::Ok().json(tree))
 }
 
 pub async fn get_replies(
     service: web::Data<CommentService>,
     comment_id: web::Path<Uuid>,
+    config: web::Data<Config>,
     query: web::Query<PaginationQuery>,
-) -> impl Responder {
-    let page = query.page.unwrap_or(1);
-    let per_page = query.per_page.unwrap_or(10);
-
-    match service.get_replies(comment_id.into_inner(), page, per_page).await {
-        Ok(replies) => HttpResponse::Ok().json(replies),
-        Err(_) => HttpResponse::InternalServerError().finish(),
+    user: Option<CurrentUser>,
+) -> Result<HttpResponse, AppError> {
+    // 조회는 로그인 없이도 가능하지만, API 토큰으로 요청했다면 읽기 권한이 있어야 합니다.
+    if let Some(user) = &user {
+        user.require_scope(Scope::CommentsRead)?;
     }
+
+    let params = query.resolve(&config.pagination)?;
+
+    let replies = service.get_replies(comment_id.into_inner(), params).await?;
+    Ok(HttpResponse::Ok().json(replies))
 }
 
 pub async fn update_comment(
     service: web::Data<CommentService>,
     comment_id: web::Path<Uuid>,
-    dto: web::Json<UpdateCommentDto>,
+    dto: ValidatedJson<UpdateCommentDto>,
     req: actix_web::HttpRequest,
-) -> impl Responder {
-    let author_id = match get_current_user(&req.into()) {
-        Some(id) => id,
-        None => return HttpResponse::Unauthorized().finish(),
-    };
+) -> Result<HttpResponse, AppError> {
+    let actor = require_scope(&req, Scope::CommentsWrite)?;
 
     match service
-        .update_comment(comment_id.into_inner(), author_id, dto.into_inner())
-        .await
+        .update_comment(comment_id.into_inner(), &actor, dto.into_inner())
+        .await?
     {
-        Ok(Some(comment)) => HttpResponse::Ok().json(comment),
-        Ok(None) => HttpResponse::NotFound().finish(),
-        Err(_) => HttpResponse::InternalServerError().finish(),
+        MutationOutcome::Done(comment) => Ok(HttpResponse::Ok().json(comment)),
+        MutationOutcome::NotFound => Err(AppError::NotFound("Comment not found".to_string())),
+        MutationOutcome::Forbidden => Err(AppError::Forbidden("Only the author or a moderator can edit this comment".to_string())),
     }
 }
 
@@ -77,21 +107,12 @@ pub async fn delete_comment(
     service: web::Data<CommentService>,
     comment_id: web::Path<Uuid>,
     req: actix_web::HttpRequest,
-) -> impl Responder {
-    let author_id = match get_current_user(&req.into()) {
-        Some(id) => id,
-        None => return HttpResponse::Unauthorized().finish(),
-    };
-
-    match service.delete_comment(comment_id.into_inner(), author_id).await {
-        Ok(true) => HttpResponse::NoContent().finish(),
-        Ok(false) => HttpResponse::NotFound().finish(),
-        Err(_) => HttpResponse::InternalServerError().finish(),
-    }
-}
+) -> Result<HttpResponse, AppError> {
+    let actor = require_scope(&req, Scope::CommentsWrite)?;
 
-#[derive(serde::Deserialize)]
-pub struct PaginationQuery {
-    page: Option<i64>,
-    per_page: Option<i64>,
+    match service.delete_comment(comment_id.into_inner(), &actor).await? {
+        MutationOutcome::Done(()) => Ok(HttpResponse::NoContent().finish()),
+        MutationOutcome::NotFound => Err(AppError::NotFound("Comment not found".to_string())),
+        MutationOutcome::Forbidden => Err(AppError::Forbidden("You are not allowed to delete this comment".to_string())),
+    }
 }
@@ -1 +1,5 @@
+pub mod auth_handler;
 pub mod post_handler;
+pub mod comment_handler;
+pub mod moderation_handler;
+pub mod user_handler;
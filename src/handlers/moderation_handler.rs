@@ -0,0 +1,40 @@
+// moderation_handler.rs
+// 모더레이터와 관리자 전용 HTTP 요청을 처리하는 핸들러들을 정의합니다.
+// 역할 확인은 라우트에 등록된 RequireRole 미들웨어가 수행합니다.
+
+use actix_web::{web, HttpResponse};
+use uuid::Uuid;
+use crate::config::Config;
+use crate::errors::AppError;
+use crate::extractors::validated_json::ValidatedJson;
+use crate::middleware::auth_middleware::require_current_actor;
+use crate::models::pagination::PaginationQuery;
+use crate::models::user::UpdateRoleDto;
+use crate::services::{auth_service::AuthService, moderation_service::ModerationService};
+
+// 모더레이터 작업 기록 조회 핸들러
+// GET /api/moderation/actions?page=1&per_page=10
+pub async fn get_actions(
+    service: web::Data<ModerationService>,
+    config: web::Data<Config>,
+    query: web::Query<PaginationQuery>,
+) -> Result<HttpResponse, AppError> {
+    let params = query.resolve(&config.pagination)?;
+
+    let actions = service.get_actions(params).await?;
+    Ok(HttpResponse::Ok().json(actions))
+}
+
+// 사용자 역할 변경 핸들러
+// PUT /api/admin/users/{user_id}/role
+pub async fn update_user_role(
+    service: web::Data<AuthService>,
+    user_id: web::Path<Uuid>,
+    dto: ValidatedJson<UpdateRoleDto>,
+    req: actix_web::HttpRequest,
+) -> Result<HttpResponse, AppError> {
+    let actor = require_current_actor(&req)?;
+
+    let user = service.set_role(&actor, user_id.into_inner(), dto.role).await?;
+    Ok(HttpResponse::Ok().json(user))
+}
@@ -2,30 +2,31 @@
 // 게시글 관련 HTTP 요청을 처리하는 핸들러들을 정의합니다.
 // 각 핸들러는 요청을 받아 적절한 서비스 메서드를 호출하고 결과를 반환합니다.
 
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{web, HttpResponse};
 use uuid::Uuid;
+use crate::config::Config;
+use crate::errors::AppError;
+use crate::extractors::current_user::CurrentUser;
+use crate::extractors::validated_json::ValidatedJson;
+use crate::models::pagination::PaginationQuery;
 use crate::models::post::{CreatePostDto, UpdatePostDto};
-use crate::services::post_service::PostService;
-use crate::middleware::auth_middleware::get_current_user;
+use crate::services::{post_service::PostService, MutationOutcome};
+use crate::middleware::auth_middleware::require_scope;
+use crate::models::api_token::Scope;
 
 // 게시글 작성 핸들러
 // POST /api/posts
 pub async fn create_post(
     service: web::Data<PostService>,  // 의존성 주입된 PostService
-    dto: web::Json<CreatePostDto>,    // JSON 요청 본문
+    dto: ValidatedJson<CreatePostDto>,    // 검증된 JSON 요청 본문
     req: actix_web::HttpRequest,      // 현재 요청 객체
-) -> impl Responder {
-    // 현재 인증된 사용자의 ID를 가져옵니다.
-    let author_id = match get_current_user(&req.into()) {
-        Some(id) => id,
-        None => return HttpResponse::Unauthorized().finish(),
-    };
+) -> Result<HttpResponse, AppError> {
+    // 현재 인증된 사용자의 ID를 가져옵니다. API 토큰은 posts:write 권한이 있어야 합니다.
+    let author_id = require_scope(&req, Scope::PostsWrite)?.id;
 
     // PostService를 통해 게시글을 생성합니다.
-    match service.create_post(dto.into_inner(), author_id).await {
-        Ok(post) => HttpResponse::Created().json(post),
-        Err(_) => HttpResponse::InternalServerError().finish(),
-    }
+    let post = service.create_post(dto.into_inner(), author_id).await?;
+    Ok(HttpResponse::Created().json(post))
 }
 
 // 게시글 상세 조회 핸들러
@@ -33,28 +34,44 @@ pub async fn create_post(
 pub async fn get_post(
     service: web::Data<PostService>,  // 의존성 주입된 PostService
     post_id: web::Path<Uuid>,         // URL 경로 매개변수
-) -> impl Responder {
-    match service.get_post(post_id.into_inner()).await {
-        Ok(Some(post)) => HttpResponse::Ok().json(post),
-        Ok(None) => HttpResponse::NotFound().finish(),
-        Err(_) => HttpResponse::InternalServerError().finish(),
+    user: Option<CurrentUser>,  // 로그인하지 않은 요청이면 None
+) -> Result<HttpResponse, AppError> {
+    // 조회는 로그인 없이도 가능하지만, API 토큰으로 요청했다면 읽기 권한이 있어야 합니다.
+    if let Some(user) = &user {
+        user.require_scope(Scope::PostsRead)?;
+    }
+
+    match service.get_post(post_id.into_inner()).await? {
+        Some(post) => Ok(HttpResponse::Ok().json(post)),
+        None => Err(AppError::NotFound("Post not found".to_string())),
     }
 }
 
 // 게시글 목록 조회 핸들러
 // GET /api/posts?page=1&per_page=10
+// GET /api/posts?after=<cursor>&per_page=10
 pub async fn get_posts(
     service: web::Data<PostService>,
+    config: web::Data<Config>,           // 애플리케이션 설정
     query: web::Query<PaginationQuery>,  // URL 쿼리 매개변수
-) -> impl Responder {
-    // 페이지네이션 매개변수의 기본값을 설정합니다.
-    let page = query.page.unwrap_or(1);
-    let per_page = query.per_page.unwrap_or(10);
+    user: Option<CurrentUser>,  // 로그인하지 않은 요청이면 None
+) -> Result<HttpResponse, AppError> {
+    // 조회는 로그인 없이도 가능하지만, API 토큰으로 요청했다면 읽기 권한이 있어야 합니다.
+    if let Some(user) = &user {
+        user.require_scope(Scope::PostsRead)?;
+    }
 
-    match service.get_posts(page, per_page).await {
-        Ok(posts) => HttpResponse::Ok().json(posts),
-        Err(_) => HttpResponse::InternalServerError().finish(),
+    // 커서(?after=, ?before=)가 있으면 커서 방식으로 조회합니다.
+    if let Some(params) = query.resolve_cursor(&config.pagination)? {
+        let posts = service.get_posts_by_cursor(params).await?;
+        return Ok(HttpResponse::Ok().json(posts));
     }
+
+    // 페이지네이션 매개변수를 검증하고 기본값을 설정합니다.
+    let params = query.resolve(&config.pagination)?;
+
+    let posts = service.get_posts(params).await?;
+    Ok(HttpResponse::Ok().json(posts))
 }
 
 // 게시글 수정 핸들러
@@ -62,23 +79,20 @@ pub async fn get_posts(
 pub async fn update_post(
     service: web::Data<PostService>,
     post_id: web::Path<Uuid>,
-    dto: web::Json<UpdatePostDto>,
+    dto: ValidatedJson<UpdatePostDto>,
     req: actix_web::HttpRequest,
-) -> impl Responder {
-    // 현재 인증된 사용자의 ID를 가져옵니다.
-    let author_id = match get_current_user(&req.into()) {
-        Some(id) => id,
-        None => return HttpResponse::Unauthorized().finish(),
-    };
+) -> Result<HttpResponse, AppError> {
+    // 현재 인증된 사용자의 ID와 역할을 가져옵니다. API 토큰은 posts:write 권한이 있어야 합니다.
+    let actor = require_scope(&req, Scope::PostsWrite)?;
 
-    // 게시글을 수정합니다. 작성자만 수정할 수 있습니다.
+    // 게시글을 수정합니다. 작성자와 모더레이터만 수정할 수 있습니다.
     match service
-        .update_post(post_id.into_inner(), dto.into_inner(), author_id)
-        .await
+        .update_post(post_id.into_inner(), dto.into_inner(), &actor)
+        .await?
     {
-        Ok(Some(post)) => HttpResponse::Ok().json(post),
-        Ok(None) => HttpResponse::NotFound().finish(),
-        Err(_) => HttpResponse::InternalServerError().finish(),
+        MutationOutcome::Done(post) => Ok(HttpResponse::Ok().json(post)),
+        MutationOutcome::NotFound => Err(AppError::NotFound("Post not found".to_string())),
+        MutationOutcome::Forbidden => Err(AppError::Forbidden("Only the author or a moderator can edit this post".to_string())),
     }
 }
 
@@ -88,24 +102,14 @@ pub async fn delete_post(
     service: web::Data<PostService>,
     post_id: web::Path<Uuid>,
     req: actix_web::HttpRequest,
-) -> impl Responder {
-    // 현재 인증된 사용자의 ID를 가져옵니다.
-    let author_id = match get_current_user(&req.into()) {
-        Some(id) => id,
-        None => return HttpResponse::Unauthorized().finish(),
-    };
+) -> Result<HttpResponse, AppError> {
+    // 현재 인증된 사용자의 ID와 역할을 가져옵니다. API 토큰은 posts:write 권한이 있어야 합니다.
+    let actor = require_scope(&req, Scope::PostsWrite)?;
 
-    // 게시글을 삭제합니다. 작성자만 삭제할 수 있습니다.
-    match service.delete_post(post_id.into_inner(), author_id).await {
-        Ok(true) => HttpResponse::NoContent().finish(),
-        Ok(false) => HttpResponse::NotFound().finish(),
-        Err(_) => HttpResponse::InternalServerError().finish(),
+    // 게시글을 삭제합니다. 작성자와 모더레이터만 삭제할 수 있습니다.
+    match service.delete_post(post_id.into_inner(), &actor).await? {
+        MutationOutcome::Done(()) => Ok(HttpResponse::NoContent().finish()),
+        MutationOutcome::NotFound => Err(AppError::NotFound("Post not found".to_string())),
+        MutationOutcome::Forbidden => Err(AppError::Forbidden("Only the author or a moderator can delete this post".to_string())),
     }
 }
-
-// 페이지네이션을 위한 쿼리 매개변수 구조체
-#[derive(serde::Deserialize)]
-pub struct PaginationQuery {
-    page: Option<i64>,      // 요청할 페이지 번호
-    per_page: Option<i64>,  // 페이지당 항목 수
-}
@@ -0,0 +1,114 @@
+// user_handler.rs
+// 로그인한 사용자의 계정 정보, 2단계 인증 설정, 개인 API 토큰(/api/users/me) 관련 HTTP 요청을 처리하는 핸들러들을 정의합니다.
+// 계정을 다루는 엔드포인트이므로 API 토큰으로는 사용할 수 없습니다.
+
+use actix_web::{web, HttpRequest, HttpResponse};
+use uuid::Uuid;
+use crate::errors::AppError;
+use crate::extractors::validated_json::ValidatedJson;
+use crate::middleware::auth_middleware::require_current_user;
+use crate::models::api_token::CreateApiTokenDto;
+use crate::models::mfa::{DisableTotpDto, MfaCodeDto};
+use crate::models::user::{ChangePasswordDto, UpdateProfileDto};
+use crate::services::api_token_service::ApiTokenService;
+use crate::services::mfa_service::MfaService;
+use crate::services::user_service::UserService;
+
+pub async fn get_me(
+    service: web::Data<UserService>,
+    req: HttpRequest,
+) -> Result<HttpResponse, AppError> {
+    let user_id = require_current_user(&req)?;
+
+    let user = service.get_user(user_id).await?;
+    Ok(HttpResponse::Ok().json(user))
+}
+
+pub async fn update_me(
+    service: web::Data<UserService>,
+    dto: ValidatedJson<UpdateProfileDto>,
+    req: HttpRequest,
+) -> Result<HttpResponse, AppError> {
+    let user_id = require_current_user(&req)?;
+
+    let user = service.update_profile(user_id, dto.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(user))
+}
+
+// 비밀번호를 바꾸면 기존 세션이 모두 폐기되므로 클라이언트는 다시 로그인해야 합니다.
+pub async fn change_password(
+    service: web::Data<UserService>,
+    dto: ValidatedJson<ChangePasswordDto>,
+    req: HttpRequest,
+) -> Result<HttpResponse, AppError> {
+    let user_id = require_current_user(&req)?;
+
+    service.change_password(user_id, dto.into_inner()).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+// 2단계 인증 등록을 시작합니다. 응답의 비밀 키와 URI로 인증 앱에 등록한 뒤 confirm으로 확인해야 합니다.
+pub async fn begin_totp(
+    service: web::Data<MfaService>,
+    req: HttpRequest,
+) -> Result<HttpResponse, AppError> {
+    let user_id = require_current_user(&req)?;
+
+    let enrollment = service.begin_totp_enrollment(user_id).await?;
+    Ok(HttpResponse::Ok().json(enrollment))
+}
+
+pub async fn confirm_totp(
+    service: web::Data<MfaService>,
+    dto: ValidatedJson<MfaCodeDto>,
+    req: HttpRequest,
+) -> Result<HttpResponse, AppError> {
+    let user_id = require_current_user(&req)?;
+
+    let recovery_codes = service.confirm_totp(user_id, &dto.code).await?;
+    Ok(HttpResponse::Ok().json(recovery_codes))
+}
+
+pub async fn disable_totp(
+    service: web::Data<MfaService>,
+    dto: ValidatedJson<DisableTotpDto>,
+    req: HttpRequest,
+) -> Result<HttpResponse, AppError> {
+    let user_id = require_current_user(&req)?;
+
+    service.disable_totp(user_id, &dto).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+// 개인 API 토큰을 만듭니다. 응답의 token은 다시 확인할 수 없으므로 클라이언트가 바로 보관해야 합니다.
+pub async fn create_api_token(
+    service: web::Data<ApiTokenService>,
+    dto: ValidatedJson<CreateApiTokenDto>,
+    req: HttpRequest,
+) -> Result<HttpResponse, AppError> {
+    let user_id = require_current_user(&req)?;
+
+    let created = service.create(user_id, dto.into_inner()).await?;
+    Ok(HttpResponse::Created().json(created))
+}
+
+pub async fn list_api_tokens(
+    service: web::Data<ApiTokenService>,
+    req: HttpRequest,
+) -> Result<HttpResponse, AppError> {
+    let user_id = require_current_user(&req)?;
+
+    let tokens = service.list(user_id).await?;
+    Ok(HttpResponse::Ok().json(tokens))
+}
+
+pub async fn revoke_api_token(
+    service: web::Data<ApiTokenService>,
+    token_id: web::Path<Uuid>,
+    req: HttpRequest,
+) -> Result<HttpResponse, AppError> {
+    let user_id = require_current_user(&req)?;
+
+    service.revoke(user_id, token_id.into_inner()).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,13 @@
+// lib.rs
+// 게시판 애플리케이션의 라이브러리 크레이트입니다.
+// main.rs와 통합 테스트(tests/)에서 공통으로 사용하는 모듈들을 공개합니다.
+
+pub mod config;
+pub mod errors;
+pub mod extractors;
+pub mod handlers;
+pub mod mail;
+pub mod middleware;
+pub mod models;
+pub mod policy;
+pub mod services;
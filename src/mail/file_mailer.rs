@@ -0,0 +1,49 @@
+// file_mailer.rs
+// 메일을 보내지 않고 디렉터리에 .eml 파일로 저장하는 Mailer입니다.
+// 로컬 개발 중에 메일 클라이언트로 열어 보거나, 테스트에서 발송된 메일을 확인할 때 사용합니다.
+
+use async_trait::async_trait;
+use chrono::Utc;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::mail::{Email, MailError, Mailer};
+
+// FileMailer는 메일마다 하나의 .eml 파일을 만듭니다.
+pub struct FileMailer {
+    dir: PathBuf,  // 메일을 저장할 디렉터리 (없으면 만듭니다)
+    from: String,  // 보내는 사람
+}
+
+impl FileMailer {
+    pub fn new(dir: impl AsRef<Path>, from: &str) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            from: from.to_string(),
+        }
+    }
+
+    // 메일이 저장되는 디렉터리를 반환합니다.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+#[async_trait]
+impl Mailer for FileMailer {
+    async fn send(&self, email: &Email) -> Result<(), MailError> {
+        tokio::fs::create_dir_all(&self.dir)
+            .await
+            .map_err(|e| MailError(format!("failed to create {}: {}", self.dir.display(), e)))?;
+
+        // 파일 이름이 발송 순서대로 정렬되도록 시각을 앞에 붙입니다.
+        let name = format!("{}-{}.eml", Utc::now().format("%Y%m%dT%H%M%S%.6f"), Uuid::new_v4());
+        let path = self.dir.join(name);
+        tokio::fs::write(&path, email.to_message(&self.from))
+            .await
+            .map_err(|e| MailError(format!("failed to write {}: {}", path.display(), e)))?;
+
+        log::info!("mail to {} saved to {}", email.to, path.display());
+        Ok(())
+    }
+}
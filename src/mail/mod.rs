@@ -0,0 +1,172 @@
+// mail/mod.rs
+// 인증 메일 등 사용자에게 보내는 메일을 발송하는 모듈입니다.
+// 서비스는 Mailer 트레이트에만 의존하며, 실제 발송 방식은 설정(mail.transport)에 따라 선택됩니다.
+// - LogMailer: 메일을 로그로 출력합니다 (기본값, 로컬 개발용, 본문은 debug 수준에서만 출력)
+// - FileMailer: 메일을 .eml 파일로 저장합니다 (로컬 개발 및 테스트용)
+// - SmtpMailer: lettre의 비동기 SMTP 전송으로 발송합니다
+
+pub mod file_mailer;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use lettre::{
+    message::{header::ContentType, Mailbox},
+    transport::smtp::authentication::Credentials,
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+use std::{fmt, sync::Arc, time::Duration};
+use uuid::Uuid;
+
+use crate::config::{MailConfig, MailTransport, SmtpConfig, SmtpSecurity};
+
+pub use file_mailer::FileMailer;
+
+// 발송할 메일입니다. 본문은 일반 텍스트입니다.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Email {
+    pub to: String,       // 받는 사람 주소
+    pub subject: String,  // 제목
+    pub body: String,     // 본문
+}
+
+impl Email {
+    // 보내는 사람을 붙여 RFC 5322 형식의 메시지로 변환합니다.
+    // 헤더 값의 줄바꿈은 제거하고, 본문의 줄바꿈은 CRLF로 통일합니다.
+    pub fn to_message(&self, from: &str) -> String {
+        let domain = from
+            .rsplit_once('@')
+            .map(|(_, rest)| rest.trim_end_matches('>'))
+            .unwrap_or("localhost");
+
+        let mut message = String::new();
+        message.push_str(&format!("From: {}\r\n", header_value(from)));
+        message.push_str(&format!("To: {}\r\n", header_value(&self.to)));
+        message.push_str(&format!("Subject: {}\r\n", header_value(&self.subject)));
+        message.push_str(&format!("Date: {}\r\n", Utc::now().to_rfc2822()));
+        message.push_str(&format!("Message-ID: <{}@{}>\r\n", Uuid::new_v4(), domain));
+        message.push_str("MIME-Version: 1.0\r\n");
+        message.push_str("Content-Type: text/plain; charset=utf-8\r\n");
+        message.push_str("Content-Transfer-Encoding: 8bit\r\n");
+        message.push_str("\r\n");
+        for line in self.body.lines() {
+            message.push_str(line);
+            message.push_str("\r\n");
+        }
+        message
+    }
+
+    // lettre로 보낼 메시지를 만듭니다. 주소 파싱, 헤더 인코딩, 점(.) 이스케이프는 lettre가 처리합니다.
+    fn to_lettre(&self, from: &Mailbox) -> Result<Message, MailError> {
+        let to: Mailbox = self
+            .to
+            .parse()
+            .map_err(|e| MailError(format!("invalid recipient '{}': {}", self.to, e)))?;
+        Message::builder()
+            .from(from.clone())
+            .to(to)
+            .subject(self.subject.as_str())
+            .header(ContentType::TEXT_PLAIN)
+            .body(self.body.clone())
+            .map_err(|e| MailError(format!("failed to build message: {}", e)))
+    }
+}
+
+// 메일 발송 실패 원인입니다.
+#[derive(Debug)]
+pub struct MailError(pub String);
+
+impl fmt::Display for MailError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for MailError {}
+
+// Mailer는 메일을 발송하는 방식을 추상화합니다.
+// 여러 워커가 하나의 인스턴스를 공유하므로 Send + Sync여야 합니다.
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, email: &Email) -> Result<(), MailError>;
+}
+
+// LogMailer는 메일을 보내지 않고 받는 사람과 제목을 로그로 출력합니다. 본문은 debug 수준에서만 출력합니다.
+pub struct LogMailer {
+    from: String,  // 보내는 사람
+}
+
+impl LogMailer {
+    pub fn new(from: &str) -> Self {
+        Self { from: from.to_string() }
+    }
+}
+
+#[async_trait]
+impl Mailer for LogMailer {
+    async fn send(&self, email: &Email) -> Result<(), MailError> {
+        // 본문에는 인증 토큰과 비밀번호 재설정 링크가 담기므로, 기본 로그 수준(info)에는 남기지 않습니다.
+        log::info!("mail from {} to {}: {}", self.from, email.to, email.subject);
+        log::debug!("mail body to {}:\n{}", email.to, email.body);
+        Ok(())
+    }
+}
+
+// SmtpMailer는 설정된 SMTP 서버로 메일을 발송합니다.
+// 연결은 메일마다 새로 맺고, 발송이 끝나면 QUIT으로 닫습니다.
+#[derive(Clone)]
+pub struct SmtpMailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,  // SMTP 전송
+    from: Mailbox,                                  // 보내는 사람
+}
+
+impl SmtpMailer {
+    // 설정에 맞게 SMTP 전송을 구성합니다. 보내는 사람 주소나 TLS 설정이 잘못되면 에러를 반환합니다.
+    pub fn new(config: &SmtpConfig, from: &str) -> Result<Self, MailError> {
+        let from = from
+            .parse()
+            .map_err(|e| MailError(format!("invalid sender '{}': {}", from, e)))?;
+        let builder = match config.security {
+            SmtpSecurity::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host),
+            SmtpSecurity::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)
+                .map_err(|e| MailError(format!("invalid SMTP TLS settings: {}", e)))?,
+            SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host)
+                .map_err(|e| MailError(format!("invalid SMTP TLS settings: {}", e)))?,
+        };
+        let mut builder = builder
+            .port(config.port)
+            .timeout(Some(Duration::from_secs(config.timeout_secs)));
+        // 사용자 이름이 비어 있으면 인증하지 않습니다.
+        if !config.username.is_empty() {
+            builder = builder.credentials(Credentials::new(config.username.clone(), config.password.clone()));
+        }
+        Ok(Self { transport: builder.build(), from })
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, email: &Email) -> Result<(), MailError> {
+        let message = email.to_lettre(&self.from)?;
+        self.transport
+            .send(message)
+            .await
+            .map(|_| ())
+            .map_err(|e| MailError(format!("SMTP delivery to {} failed: {}", email.to, e)))
+    }
+}
+
+// 설정에 맞는 Mailer를 생성합니다.
+// SMTP 설정으로 전송을 구성할 수 없으면 에러를 반환합니다.
+pub fn from_config(config: &MailConfig) -> Result<Arc<dyn Mailer>, MailError> {
+    let mailer: Arc<dyn Mailer> = match config.transport {
+        MailTransport::Log => Arc::new(LogMailer::new(&config.from)),
+        MailTransport::File => Arc::new(FileMailer::new(&config.file_dir, &config.from)),
+        MailTransport::Smtp => Arc::new(SmtpMailer::new(&config.smtp, &config.from)?),
+    };
+    Ok(mailer)
+}
+
+// 헤더 인젝션을 막기 위해 헤더 값에서 줄바꿈을 제거합니다.
+fn header_value(value: &str) -> String {
+    value.replace(['\r', '\n'], " ")
+}
@@ -5,18 +5,18 @@
 use actix_web::{App, HttpServer, middleware::Logger, web};
 use dotenv::dotenv;
 use sqlx::postgres::PgPoolOptions;
-use std::env;
+use std::{sync::Arc, time::Duration};
 
-mod config;
-mod models;
-mod handlers;
-mod middleware;
-mod services;
-mod utils;
-
-use handlers::{auth_handler, post_handler, comment_handler};
-use services::{auth_service::AuthService, post_service::PostService, comment_service::CommentService};
-use middleware::auth_middleware::Auth;
+use rust_study::config::Config;
+use rust_study::errors;
+use rust_study::mail;
+use rust_study::handlers::{auth_handler, post_handler, comment_handler, moderation_handler, user_handler};
+use rust_study::models::user::Role;
+use rust_study::services::{
+    auth_service::AuthService, email_verification_service::EmailVerificationService, jwt_keys::JwtKeys, post_service::PostService, comment_service::CommentService,
+    moderation_service::ModerationService, user_service::UserService, mfa_service::MfaService, oidc_service::OidcService, api_token_service::ApiTokenService,
+};
+use rust_study::middleware::{auth_middleware::Auth, request_id::RequestId, require_role::RequireRole};
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
@@ -25,22 +25,65 @@ async fn main() -> std::io::Result<()> {
     // env_logger를 초기화하여 로깅을 활성화합니다.
     env_logger::init();
 
-    // 환경 변수에서 서버 설정을 읽어옵니다.
-    // unwrap_or_else를 사용하여 환경 변수가 없을 경우 기본값을 제공합니다.
-    let host = env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
-    let port = env::var("PORT").unwrap_or_else(|_| "8080".to_string());
-    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    // 설정 파일과 환경 변수에서 설정을 읽어오고 검증합니다.
+    // 설정이 올바르지 않으면 원인을 출력하고 서버를 시작하지 않습니다.
+    let config = match Config::load() {
+        Ok(config) => Arc::new(config),
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // 액세스 토큰 서명 키를 읽어옵니다. 키 파일에 문제가 있으면 서버를 시작하지 않습니다.
+    let jwt_keys = match JwtKeys::from_config(&config.jwt) {
+        Ok(keys) => Arc::new(keys),
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            std::process::exit(1);
+        }
+    };
 
     // PostgreSQL 연결 풀을 생성합니다.
-    // 동시에 최대 5개의 데이터베이스 연결을 유지합니다.
+    // 연결 수와 타임아웃은 설정 값을 따릅니다.
     let pool = PgPoolOptions::new()
-        .max_connections(5)
-        .connect(&database_url)
+        .max_connections(config.database.max_connections)
+        .min_connections(config.database.min_connections)
+        .acquire_timeout(Duration::from_secs(config.database.acquire_timeout_secs))
+        .idle_timeout(Duration::from_secs(config.database.idle_timeout_secs))
+        .connect(&config.database.url)
         .await
         .expect("Failed to create pool");
 
+    let (host, port) = config.bind_address();
     println!("🚀 Server running at http://{}:{}", host, port);
 
+    let server_config = config.clone();
+
+    // 메일 발송 방식은 설정(mail.transport)에 따라 정해집니다.
+    let mailer = match mail::from_config(&config.mail) {
+        Ok(mailer) => mailer,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            std::process::exit(1);
+        }
+    };
+    // AuthService는 토큰 폐기 상태 캐시를 가지고 있으므로, 모든 워커가 같은 인스턴스를 공유합니다.
+    let auth_service = web::Data::new(
+        AuthService::with_keys(pool.clone(), config.clone(), jwt_keys).with_mailer(mailer.clone()),
+    );
+    let verification_service = web::Data::new(EmailVerificationService::new(pool.clone(), config.clone(), mailer.clone()));
+    // UserService는 비밀번호 변경 시 AuthService와 같은 폐기 상태 캐시를 무효화합니다.
+    let user_service = web::Data::new(UserService::new(
+        pool.clone(),
+        config.clone(),
+        auth_service.sessions(),
+        verification_service.clone().into_inner(),
+    ));
+
+    // OidcService는 공급자의 discovery 문서와 검증 키를 캐시하므로, 모든 워커가 같은 인스턴스를 공유합니다.
+    let oidc_service = web::Data::new(OidcService::new(pool.clone(), config.clone()));
+
     // HTTP 서버를 구성하고 시작합니다.
     HttpServer::new(move || {
         // 새로운 App 인스턴스를 생성합니다.
@@ -48,11 +91,26 @@ async fn main() -> std::io::Result<()> {
         App::new()
             // Logger 미들웨어를 추가하여 HTTP 요청 로깅을 활성화합니다.
             .wrap(Logger::default())
+            // 모든 응답에 요청 ID를 부여하고, 에러 응답 본문에 포함시킵니다.
+            .wrap(RequestId)
+            // 요청 본문, 쿼리, 경로 매개변수의 파싱 에러도 AppError 형식으로 응답합니다.
+            .app_data(web::JsonConfig::default().error_handler(errors::json_error_handler))
+            .app_data(web::QueryConfig::default().error_handler(errors::query_error_handler))
+            .app_data(web::PathConfig::default().error_handler(errors::path_error_handler))
             // 서비스 인스턴스들을 애플리케이션 데이터로 등록합니다.
             // web::Data로 래핑하여 여러 스레드에서 안전하게 공유할 수 있게 합니다.
-            .app_data(web::Data::new(AuthService::new(pool.clone())))
+            .app_data(web::Data::from(config.clone()))
+            .app_data(auth_service.clone())
+            .app_data(verification_service.clone())
+            .app_data(user_service.clone())
+            .app_data(web::Data::new(MfaService::new(pool.clone(), config.clone())))
+            .app_data(oidc_service.clone())
+            .app_data(web::Data::new(ApiTokenService::new(pool.clone())))
             .app_data(web::Data::new(PostService::new(pool.clone())))
-            .app_data(web::Data::new(CommentService::new(pool.clone())))
+            .app_data(web::Data::new(CommentService::new(pool.clone(), config.clone())))
+            .app_data(web::Data::new(ModerationService::new(pool.clone())))
+            // 다른 서비스가 액세스 토큰을 검증할 수 있도록 공개 키를 제공합니다.
+            .route("/.well-known/jwks.json", web::get().to(auth_handler::jwks))
             // API 라우트를 설정합니다.
             .service(
                 web::scope("/api")  // /api 접두사로 모든 엔드포인트를 그룹화합니다.
@@ -61,36 +119,93 @@ async fn main() -> std::io::Result<()> {
                         web::scope("/auth")
                             .route("/register", web::post().to(auth_handler::register))
                             .route("/login", web::post().to(auth_handler::login))
+                            .route("/refresh", web::post().to(auth_handler::refresh))
+                            .route("/verify-email", web::post().to(auth_handler::verify_email))
+                            .route("/forgot-password", web::post().to(auth_handler::forgot_password))
+                            .route("/reset-password", web::post().to(auth_handler::reset_password))
+                            .route("/mfa/verify", web::post().to(auth_handler::verify_mfa))
+                            .route("/oidc/authorize", web::get().to(auth_handler::oidc_authorize))
+                            .route("/oidc/callback", web::post().to(auth_handler::oidc_callback))
+                            .service(
+                                web::resource("/resend-verification")
+                                    .wrap(Auth::required())
+                                    .route(web::post().to(auth_handler::resend_verification))
+                            )
+                            .service(
+                                web::resource("/logout")
+                                    .wrap(Auth::required())
+                                    .route(web::post().to(auth_handler::logout))
+                            )
+                            .service(
+                                web::resource("/logout-all")
+                                    .wrap(Auth::required())
+                                    .route(web::post().to(auth_handler::logout_all))
+                            )
+                    )
+                    .service(
+                        // 로그인한 사용자의 계정 관련 엔드포인트 (/api/users/me/...)
+                        web::scope("/users/me")
+                            .wrap(Auth::required())
+                            .route("", web::get().to(user_handler::get_me))
+                            .route("", web::patch().to(user_handler::update_me))
+                            .route("/password", web::post().to(user_handler::change_password))
+                            .route("/mfa/totp", web::post().to(user_handler::begin_totp))
+                            .route("/mfa/totp", web::delete().to(user_handler::disable_totp))
+                            .route("/mfa/totp/confirm", web::post().to(user_handler::confirm_totp))
+                            .route("/tokens", web::post().to(user_handler::create_api_token))
+                            .route("/tokens", web::get().to(user_handler::list_api_tokens))
+                            .route("/tokens/{token_id}", web::delete().to(user_handler::revoke_api_token))
                     )
                     .service(
                         // 게시글 관련 엔드포인트 (/api/posts/...)
+                        // 조회(GET)는 로그인 없이 사용할 수 있고, 작성/수정/삭제는 로그인이 필요합니다.
                         web::scope("/posts")
-                            .wrap(Auth)  // 인증 미들웨어 적용
-                            .route("", web::post().to(post_handler::create_post))
-                            .route("", web::get().to(post_handler::get_posts))
-                            .route("/{post_id}", web::get().to(post_handler::get_post))
-                            .route("/{post_id}", web::put().to(post_handler::update_post))
-                            .route("/{post_id}", web::delete().to(post_handler::delete_post))
+                            .route("", web::post().to(post_handler::create_post).wrap(Auth::required()))
+                            .route("", web::get().to(post_handler::get_posts).wrap(Auth::optional()))
+                            .route("/{post_id}", web::get().to(post_handler::get_post).wrap(Auth::optional()))
+                            .route("/{post_id}", web::put().to(post_handler::update_post).wrap(Auth::required()))
+                            .route("/{post_id}", web::delete().to(post_handler::delete_post).wrap(Auth::required()))
                             .service(
                                 // 게시글의 댓글 관련 엔드포인트
                                 web::scope("/{post_id}/comments")
-                                    .wrap(Auth)
-                                    .route("", web::post().to(comment_handler::create_comment))
-                                    .route("", web::get().to(comment_handler::get_post_comments))
+                                    .route("", web::post().to(comment_handler::create_comment).wrap(Auth::required()))
+                                    .route("", web::get().to(comment_handler::get_post_comments).wrap(Auth::optional()))
+                                    .route("/tree", web::get().to(comment_handler::get_comment_tree).wrap(Auth::optional()))
                             )
                     )
                     .service(
                         // 댓글 관련 엔드포인트 (/api/comments/...)
                         web::scope("/comments")
-                            .wrap(Auth)
-                            .route("/{comment_id}", web::put().to(comment_handler::update_comment))
-                            .route("/{comment_id}", web::delete().to(comment_handler::delete_comment))
-                            .route("/{comment_id}/replies", web::get().to(comment_handler::get_replies))
+                            .route("/{comment_id}", web::put().to(comment_handler::update_comment).wrap(Auth::required()))
+                            .route("/{comment_id}", web::delete().to(comment_handler::delete_comment).wrap(Auth::required()))
+                            .route("/{comment_id}/replies", web::get().to(comment_handler::get_replies).wrap(Auth::optional()))
+                    )
+                    .service(
+                        // 모더레이터 전용 엔드포인트 (/api/moderation/...)
+                        // 미들웨어는 나중에 등록한 것이 먼저 실행되므로 Auth가 역할 확인보다 먼저 실행됩니다.
+                        web::scope("/moderation")
+                            .wrap(RequireRole::new(Role::Moderator))
+                            .wrap(Auth::required())
+                            .route("/actions", web::get().to(moderation_handler::get_actions))
+                    )
+                    .service(
+                        // 관리자 전용 엔드포인트 (/api/admin/...)
+                        web::scope("/admin")
+                            .wrap(RequireRole::new(Role::Admin))
+                            .wrap(Auth::required())
+                            .route("/users/{user_id}/role", web::put().to(moderation_handler::update_user_role))
                     )
             )
     })
+    // 요청 헤더 수신 제한 시간을 설정합니다.
+    .client_request_timeout(Duration::from_secs(server_config.server.client_request_timeout_secs))
+    // 워커 수가 지정된 경우에만 적용하고, 그렇지 않으면 CPU 코어 수를 사용합니다.
+    .workers(match server_config.server.workers {
+        0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    })
     // 서버를 바인딩하고 시작합니다.
-    .bind(format!("{}:{}", host, port))?
+    .bind((host, port))?
     .run()
     .await
 }
@@ -1,31 +1,60 @@
 // auth_middleware.rs
 // JWT 토큰을 검증하고 현재 인증된 사용자의 ID를 요청에 주입하는 미들웨어입니다.
+// 서명과 만료 시간뿐 아니라 로그아웃 등으로 서버에서 폐기된 토큰인지도 확인합니다.
+// 개인 API 토큰(rsb_로 시작)도 받으며, 어떤 토큰으로 인증했는지는 Credential로 요청에 함께 주입합니다.
+// API 토큰은 require_scope로 권한을 확인하는 핸들러에서만 사용할 수 있고, 나머지 헬퍼는 API 토큰 요청을 거부합니다.
+// 토큰이 없으면 WWW-Authenticate: Bearer만, 토큰이 거부되면 RFC 6750의 invalid_token과 그 이유를 함께 응답합니다.
+// Auth::optional()로 등록하면 Authorization 헤더가 없는 요청도 익명 요청으로 통과시킵니다.
+// 헤더가 있으면 required()와 같이 검증하므로, 만료되거나 폐기된 토큰은 익명 요청으로 취급하지 않고 거부합니다.
 // Actix-web의 미들웨어 시스템을 사용하여 구현되었습니다.
 
 use actix_web::{
-    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
-    Error, HttpMessage,
+    body::{EitherBody, MessageBody},
+    dev::{forward_ready, Extensions, Service, ServiceRequest, ServiceResponse, Transform},
+    http::header, web, Error, HttpMessage, HttpRequest,
 };
 use futures::future::LocalBoxFuture;
 use std::future::{ready, Ready};
+use std::rc::Rc;
 use uuid::Uuid;
 
-use crate::services::auth_service::AuthService;
+use crate::errors::{AppError, TokenError};
+use crate::extractors::current_user::CurrentUser;
+use crate::models::api_token::{Credential, Scope};
+use crate::models::user::Actor;
+use crate::services::api_token_service;
+use crate::services::auth_service::{AuthService, Claims};
 
 // Auth 구조체는 미들웨어 팩토리입니다.
 // 이 구조체는 새로운 미들웨어 인스턴스를 생성하는 역할을 합니다.
-pub struct Auth;
+#[derive(Debug, Clone, Copy)]
+pub struct Auth {
+    optional: bool,  // true이면 토큰이 없는 요청도 통과시킵니다
+}
+
+impl Auth {
+    // 유효한 토큰이 없으면 요청을 거부하는 미들웨어를 생성합니다.
+    pub fn required() -> Self {
+        Self { optional: false }
+    }
+
+    // 토큰이 있으면 검증하여 사용자를 주입하고, 없으면 익명 요청으로 통과시키는 미들웨어를 생성합니다.
+    // 로그인하지 않아도 사용할 수 있는 조회 엔드포인트에 사용합니다.
+    pub fn optional() -> Self {
+        Self { optional: true }
+    }
+}
 
 // Transform 트레이트 구현
 // 이는 미들웨어 팩토리의 동작을 정의합니다.
 impl<S, B> Transform<S, ServiceRequest> for Auth
 where
     // S는 다음 미들웨어 또는 핸들러를 나타냅니다.
-    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
     S::Future: 'static,
-    B: 'static,
+    B: MessageBody + 'static,
 {
-    type Response = ServiceResponse<B>;
+    type Response = ServiceResponse<EitherBody<B>>;
     type Error = Error;
     type InitError = ();
     type Transform = AuthMiddleware<S>;
@@ -33,24 +62,29 @@ where
 
     // 새로운 미들웨어 인스턴스를 생성합니다.
     fn new_transform(&self, service: S) -> Self::Future {
-        ready(Ok(AuthMiddleware { service }))
+        ready(Ok(AuthMiddleware {
+            service: Rc::new(service),
+            optional: self.optional,
+        }))
     }
 }
 
 // AuthMiddleware는 실제 미들웨어 구현체입니다.
+// 토큰 검증이 끝난 뒤 비동기 블록 안에서 다음 서비스를 호출하기 위해 Rc로 감쌉니다.
 pub struct AuthMiddleware<S> {
-    service: S,
+    service: Rc<S>,
+    optional: bool,
 }
 
 // Service 트레이트 구현
 // 이는 실제 미들웨어의 동작을 정의합니다.
 impl<S, B> Service<ServiceRequest> for AuthMiddleware<S>
 where
-    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
     S::Future: 'static,
-    B: 'static,
+    B: MessageBody + 'static,
 {
-    type Response = ServiceResponse<B>;
+    type Response = ServiceResponse<EitherBody<B>>;
     type Error = Error;
     type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
 
@@ -59,48 +93,128 @@ where
 
     // 실제 미들웨어 로직이 구현된 부분입니다.
     fn call(&self, req: ServiceRequest) -> Self::Future {
-        // Authorization 헤더에서 토큰을 추출합니다.
-        let auth_header = req.headers().get("Authorization");
-        // AuthService 인스턴스를 가져옵니다.
-        let auth_service = req.app_data::<actix_web::web::Data<AuthService>>().cloned();
-
-        // 토큰이나 AuthService가 없으면 인증 실패
-        if auth_header.is_none() || auth_service.is_none() {
-            return Box::pin(ready(Err(actix_web::error::ErrorUnauthorized("Unauthorized"))));
+        // 선택적 인증에서는 Authorization 헤더가 없는 요청을 사용자 없이 그대로 통과시킵니다.
+        if self.optional && !req.headers().contains_key(header::AUTHORIZATION) {
+            let fut = self.service.call(req);
+            return Box::pin(async move { Ok(fut.await?.map_into_left_body()) });
         }
 
-        // "Bearer " 접두사를 확인하고 실제 토큰을 추출합니다.
-        let auth_header = auth_header.unwrap().to_str().unwrap_or("");
-        if !auth_header.starts_with("Bearer ") {
-            return Box::pin(ready(Err(actix_web::error::ErrorUnauthorized("Invalid token format"))));
-        }
+        let (auth_service, token) = match bearer_token(&req) {
+            Ok(found) => found,
+            // 토큰이 없으면 다음 서비스를 호출하지 않고 바로 에러 응답을 반환합니다.
+            Err(e) => return Box::pin(ready(Ok(req.error_response(e).map_into_right_body()))),
+        };
 
-        let token = auth_header[7..].to_string();
-        let auth_service = auth_service.unwrap();
+        // 폐기 여부 확인에 데이터베이스 조회가 필요할 수 있으므로, 다음 서비스는 검증이 끝난 뒤 호출합니다.
+        let service = self.service.clone();
+        Box::pin(async move {
+            // 접두사로 API 토큰과 JWT 액세스 토큰을 구분합니다. 클레임은 액세스 토큰에만 있습니다.
+            let authenticated = if api_token_service::is_api_token(&token) {
+                auth_service
+                    .authenticate_api_token(&token)
+                    .await
+                    .map(|(actor, credential)| (actor, credential, None))
+            } else {
+                auth_service.authenticate(&token).await.and_then(|claims| {
+                    let user_id = Uuid::parse_str(&claims.sub)
+                        .map_err(|_| AppError::InvalidToken(TokenError::InvalidClaims))?;
+                    Ok((Actor { id: user_id, role: claims.role }, Credential::AccessToken, Some(claims)))
+                })
+            };
 
-        // 토큰을 검증하고 사용자 ID를 추출합니다.
-        match auth_service.verify_token(&token) {
-            Ok(claims) => {
-                if let Ok(user_id) = Uuid::parse_str(&claims.sub) {
-                    // 사용자 ID를 요청의 확장(extensions)에 저장합니다.
+            match authenticated {
+                Ok((actor, credential, claims)) => {
+                    // 사용자 ID와 자격 증명을 요청의 확장(extensions)에 저장합니다.
                     // 이를 통해 핸들러에서 현재 인증된 사용자의 ID를 조회할 수 있습니다.
-                    req.extensions_mut().insert(user_id);
-                    let fut = self.service.call(req);
-                    Box::pin(async move {
-                        let res = fut.await?;
-                        Ok(res)
-                    })
-                } else {
-                    Box::pin(ready(Err(actix_web::error::ErrorUnauthorized("Invalid user ID"))))
+                    req.extensions_mut().insert(actor.id);
+                    req.extensions_mut().insert(actor);
+                    req.extensions_mut().insert(credential);
+                    if let Some(claims) = claims {
+                        req.extensions_mut().insert(claims);
+                    }
+                    let res = service.call(req).await?;
+                    Ok(res.map_into_left_body())
                 }
+                Err(e) => Ok(req.error_response(e).map_into_right_body()),
             }
-            Err(_) => Box::pin(ready(Err(actix_web::error::ErrorUnauthorized("Invalid token")))),
-        }
+        })
     }
 }
 
+// AuthService와 Authorization 헤더의 Bearer 토큰을 꺼냅니다.
+fn bearer_token(req: &ServiceRequest) -> Result<(web::Data<AuthService>, String), AppError> {
+    // AuthService 인스턴스를 가져옵니다.
+    let auth_service = req
+        .app_data::<web::Data<AuthService>>()
+        .cloned()
+        .ok_or_else(|| AppError::Internal("AuthService is not registered".to_string()))?;
+
+    // Authorization 헤더에서 토큰을 추출합니다.
+    let auth_header = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .ok_or_else(|| AppError::Unauthorized("Unauthorized".to_string()))?
+        .to_str()
+        .unwrap_or("");
+
+    // "Bearer " 접두사를 확인하고 실제 토큰을 추출합니다.
+    let token = auth_header
+        .strip_prefix("Bearer ")
+        .ok_or_else(|| AppError::Unauthorized("Invalid token format".to_string()))?;
+
+    Ok((auth_service, token.to_string()))
+}
+
 // 현재 인증된 사용자의 ID를 가져오는 헬퍼 함수입니다.
 // 핸들러에서 이 함수를 사용하여 현재 요청을 보낸 사용자의 ID를 조회할 수 있습니다.
-pub fn get_current_user(req: &ServiceRequest) -> Option<Uuid> {
+pub fn get_current_user(req: &HttpRequest) -> Option<Uuid> {
     req.extensions().get::<Uuid>().copied()
 }
+
+// API 토큰으로 인증된 요청이면 Forbidden을 반환합니다.
+// 계정 관리나 모더레이션처럼 scope로 허용하지 않은 기능은 로그인으로 발급된 액세스 토큰으로만 사용할 수 있습니다.
+pub fn reject_api_token(extensions: &Extensions) -> Result<(), AppError> {
+    match extensions.get::<Credential>() {
+        Some(Credential::ApiToken { .. }) => {
+            Err(AppError::Forbidden("API tokens cannot be used for this endpoint".to_string()))
+        }
+        _ => Ok(()),
+    }
+}
+
+// 현재 인증된 사용자의 ID를 가져오고, 없으면 Unauthorized 에러를 반환합니다.
+// API 토큰으로 인증된 요청은 Forbidden을 반환합니다.
+pub fn require_current_user(req: &HttpRequest) -> Result<Uuid, AppError> {
+    reject_api_token(&req.extensions())?;
+    get_current_user(req).ok_or_else(|| AppError::Unauthorized("Authentication required".to_string()))
+}
+
+// 현재 인증된 사용자의 ID와 역할을 가져오고, 없으면 Unauthorized 에러를 반환합니다.
+// 작성자나 역할에 따라 권한이 달라지는 핸들러에서 사용합니다. API 토큰으로 인증된 요청은 Forbidden을 반환합니다.
+pub fn require_current_actor(req: &HttpRequest) -> Result<Actor, AppError> {
+    let extensions = req.extensions();
+    reject_api_token(&extensions)?;
+    extensions
+        .get::<Actor>()
+        .copied()
+        .ok_or_else(|| AppError::Unauthorized("Authentication required".to_string()))
+}
+
+// 현재 인증된 사용자가 주어진 권한을 가졌는지 확인하고, 사용자의 ID와 역할을 반환합니다.
+// 액세스 토큰은 모든 권한을 가지며, API 토큰은 만들 때 고른 권한이 없으면 Forbidden을 반환합니다.
+pub fn require_scope(req: &HttpRequest, scope: Scope) -> Result<Actor, AppError> {
+    CurrentUser::from_extensions(&req.extensions())
+        .ok_or_else(|| AppError::Unauthorized("Authentication required".to_string()))?
+        .require_scope(scope)
+}
+
+// 현재 요청의 액세스 토큰 클레임을 가져오고, 없으면 Unauthorized 에러를 반환합니다.
+// 로그아웃처럼 토큰 자체를 다루는 핸들러에서 사용합니다. API 토큰으로 인증된 요청은 Forbidden을 반환합니다.
+pub fn require_current_claims(req: &HttpRequest) -> Result<Claims, AppError> {
+    let extensions = req.extensions();
+    reject_api_token(&extensions)?;
+    extensions
+        .get::<Claims>()
+        .cloned()
+        .ok_or_else(|| AppError::Unauthorized("Authentication required".to_string()))
+}
@@ -0,0 +1,3 @@
+pub mod auth_middleware;
+pub mod request_id;
+pub mod require_role;
@@ -0,0 +1,121 @@
+// request_id.rs
+// 모든 요청에 요청 ID를 부여하는 미들웨어입니다.
+// 클라이언트가 X-Request-Id 헤더를 보내면 그 값을 사용하고, 없으면 새로 생성합니다.
+// 응답 헤더에 요청 ID를 추가하고, AppError 응답 본문의 request_id 필드를 채웁니다.
+
+use actix_web::{
+    body::{EitherBody, MessageBody},
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    error::InternalError,
+    http::header::{HeaderMap, HeaderName, HeaderValue},
+    Error, HttpMessage,
+};
+use futures::future::LocalBoxFuture;
+use std::future::{ready, Ready};
+use uuid::Uuid;
+
+use crate::errors::AppError;
+
+// 요청 ID를 주고받는 HTTP 헤더 이름입니다.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+// 요청 확장(extensions)에 저장되는 요청 ID입니다.
+#[derive(Debug, Clone)]
+pub struct RequestIdValue(pub String);
+
+// RequestId 구조체는 미들웨어 팩토리입니다.
+pub struct RequestId;
+
+impl<S, B> Transform<S, ServiceRequest> for RequestId
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RequestIdMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestIdMiddleware { service }))
+    }
+}
+
+// RequestIdMiddleware는 실제 미들웨어 구현체입니다.
+pub struct RequestIdMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestIdMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        // 클라이언트가 보낸 요청 ID가 올바른 형식이면 재사용하고, 아니면 새로 생성합니다.
+        let request_id = req
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .filter(|value| is_valid_request_id(value))
+            .map(str::to_owned)
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+        req.extensions_mut().insert(RequestIdValue(request_id.clone()));
+        // 라우팅 중에 요청을 수정하므로 HttpRequest를 미리 복제해 두면 안 됩니다.
+        let fut = self.service.call(req);
+
+        Box::pin(async move {
+            match fut.await {
+                Ok(res) => {
+                    // 핸들러가 AppError를 반환했다면 요청 ID를 포함한 응답으로 다시 만듭니다.
+                    let rebuilt = res
+                        .response()
+                        .error()
+                        .and_then(|err| err.as_error::<AppError>())
+                        .map(|err| err.response_with_request_id(Some(&request_id)));
+                    let mut res = match rebuilt {
+                        Some(response) => res.into_response(response).map_into_right_body(),
+                        None => res.map_into_left_body(),
+                    };
+                    insert_request_id(res.headers_mut(), &request_id);
+                    Ok(res)
+                }
+                Err(err) => {
+                    // 미들웨어에서 발생한 에러도 같은 형식의 응답으로 변환하고, 에러로 그대로 전달합니다.
+                    let mut response = match err.as_error::<AppError>() {
+                        Some(app_err) => app_err.response_with_request_id(Some(&request_id)),
+                        None => err.error_response(),
+                    };
+                    insert_request_id(response.headers_mut(), &request_id);
+                    Err(InternalError::from_response(err, response).into())
+                }
+            }
+        })
+    }
+}
+
+// 응답 헤더에 요청 ID를 추가합니다.
+fn insert_request_id(headers: &mut HeaderMap, request_id: &str) {
+    if let Ok(value) = HeaderValue::from_str(request_id) {
+        headers.insert(HeaderName::from_static(REQUEST_ID_HEADER), value);
+    }
+}
+
+// 외부에서 전달된 요청 ID는 길이와 문자 집합을 제한합니다.
+fn is_valid_request_id(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= 64
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
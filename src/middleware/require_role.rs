@@ -0,0 +1,86 @@
+// require_role.rs
+// 현재 사용자가 지정한 역할 이상인지 확인하는 미들웨어입니다.
+// 사용자 정보는 AuthMiddleware가 요청에 넣어 두므로, 반드시 Auth 안쪽에서 실행되도록 등록해야 합니다.
+// Actix-web은 나중에 등록한 미들웨어를 먼저 실행하므로 .wrap(RequireRole::new(..)).wrap(Auth::required()) 순서로 등록합니다.
+// 역할이 필요한 엔드포인트는 개인 API 토큰으로 사용할 수 없습니다.
+
+use actix_web::{
+    body::{EitherBody, MessageBody},
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    Error, HttpMessage,
+};
+use futures::future::LocalBoxFuture;
+use std::future::{ready, Ready};
+
+use crate::errors::AppError;
+use crate::middleware::auth_middleware::reject_api_token;
+use crate::models::user::{Actor, Role};
+
+// RequireRole은 필요한 최소 역할을 가진 미들웨어 팩토리입니다.
+pub struct RequireRole {
+    role: Role,  // 요청을 허용할 최소 역할
+}
+
+impl RequireRole {
+    // 주어진 역할 이상인 사용자만 허용하는 미들웨어를 생성합니다.
+    pub fn new(role: Role) -> Self {
+        Self { role }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RequireRole
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RequireRoleMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequireRoleMiddleware {
+            service,
+            role: self.role,
+        }))
+    }
+}
+
+// RequireRoleMiddleware는 실제 역할 확인을 수행합니다.
+pub struct RequireRoleMiddleware<S> {
+    service: S,
+    role: Role,
+}
+
+impl<S, B> Service<ServiceRequest> for RequireRoleMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let rejected = reject_api_token(&req.extensions()).err();
+        let actor = req.extensions().get::<Actor>().copied();
+
+        let error = match (rejected, actor) {
+            (Some(error), _) => error,
+            (None, Some(actor)) if actor.role >= self.role => {
+                let fut = self.service.call(req);
+                return Box::pin(async move { Ok(fut.await?.map_into_left_body()) });
+            }
+            (None, Some(_)) => AppError::Forbidden("Insufficient role".to_string()),
+            (None, None) => AppError::Unauthorized("Authentication required".to_string()),
+        };
+
+        // 역할이 부족하면 다음 서비스를 호출하지 않고 바로 에러 응답을 반환합니다.
+        Box::pin(ready(Ok(req.error_response(error).map_into_right_body())))
+    }
+}
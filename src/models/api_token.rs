@@ -0,0 +1,108 @@
+// api_token.rs
+// 스크립트나 봇이 사용하는 개인 API 토큰과 관련된 데이터 모델을 정의합니다.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+use validator::Validate;
+
+// API 토큰으로 허용할 수 있는 권한입니다.
+// 로그인으로 발급된 액세스 토큰은 모든 권한을 가지며, API 토큰은 만들 때 고른 권한만 가집니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Scope {
+    #[serde(rename = "posts:read")]
+    PostsRead,      // 게시글 조회
+    #[serde(rename = "posts:write")]
+    PostsWrite,     // 게시글 작성, 수정, 삭제
+    #[serde(rename = "comments:read")]
+    CommentsRead,   // 댓글 조회
+    #[serde(rename = "comments:write")]
+    CommentsWrite,  // 댓글 작성, 수정, 삭제
+}
+
+impl Scope {
+    // 데이터베이스와 API 응답에서 사용하는 이름입니다.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::PostsRead => "posts:read",
+            Scope::PostsWrite => "posts:write",
+            Scope::CommentsRead => "comments:read",
+            Scope::CommentsWrite => "comments:write",
+        }
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Scope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "posts:read" => Ok(Scope::PostsRead),
+            "posts:write" => Ok(Scope::PostsWrite),
+            "comments:read" => Ok(Scope::CommentsRead),
+            "comments:write" => Ok(Scope::CommentsWrite),
+            _ => Err(format!("unknown scope: {}", s)),
+        }
+    }
+}
+
+// 요청을 인증한 자격 증명입니다. AuthMiddleware가 요청의 확장(extensions)에 저장합니다.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Credential {
+    AccessToken,  // 로그인으로 발급된 JWT 액세스 토큰
+    ApiToken {    // 개인 API 토큰
+        id: Uuid,
+        scopes: Vec<Scope>,
+    },
+}
+
+impl Credential {
+    // 이 자격 증명으로 주어진 권한이 필요한 작업을 할 수 있는지 확인합니다.
+    pub fn allows(&self, scope: Scope) -> bool {
+        match self {
+            Credential::AccessToken => true,
+            Credential::ApiToken { scopes, .. } => scopes.contains(&scope),
+        }
+    }
+}
+
+// ApiToken은 API 토큰 목록과 생성 응답에 포함되는 토큰 정보입니다.
+// 토큰 원문은 포함되지 않으며, prefix로 어떤 토큰인지 구분할 수 있습니다.
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiToken {
+    pub id: Uuid,                              // 토큰 ID (폐기할 때 사용)
+    pub name: String,                          // 사용자가 붙인 이름
+    pub prefix: String,                        // 토큰 원문의 앞부분
+    pub scopes: Vec<Scope>,                    // 허용된 권한
+    pub expires_at: Option<DateTime<Utc>>,     // 만료 시간 (없으면 폐기할 때까지 사용 가능)
+    pub last_used_at: Option<DateTime<Utc>>,   // 마지막 사용 시간
+    pub created_at: DateTime<Utc>,             // 생성 시간
+}
+
+// CreateApiTokenDto는 API 토큰을 만들 때 사용하는 데이터 구조입니다.
+// 만료 시간은 현재 시각 이후여야 하며, ApiTokenService::create에서 검사합니다.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct CreateApiTokenDto {
+    #[validate(length(min = 1, max = 100, message = "name must be between 1 and 100 characters"))]
+    pub name: String,                       // 토큰 이름 (예: "deploy bot")
+    #[validate(length(min = 1, message = "scopes must contain at least one scope"))]
+    pub scopes: Vec<Scope>,                 // 허용할 권한
+    pub expires_at: Option<DateTime<Utc>>,  // 만료 시간 (선택)
+}
+
+// CreatedApiToken은 API 토큰을 만들었을 때 반환되는 응답 구조입니다.
+// 토큰 원문은 해시만 저장하므로 이 응답에서만 확인할 수 있습니다.
+#[derive(Debug, Serialize)]
+pub struct CreatedApiToken {
+    pub token: String,  // Authorization: Bearer 헤더에 사용할 토큰 원문
+    #[serde(flatten)]
+    pub api_token: ApiToken,
+}
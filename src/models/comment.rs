@@ -2,10 +2,18 @@
 // 댓글 관련 데이터 모델과 DTO를 정의합니다.
 // 댓글의 CRUD 작업과 대댓글 기능에 사용되는 구조체들이 포함되어 있습니다.
 
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
+use validator::Validate;
+
+use crate::config::CommentsConfig;
+use crate::errors::AppError;
+use crate::models::user::AuthorSummary;
+use crate::models::validation::not_blank;
 
 // Comment 구조체는 데이터베이스의 comments 테이블과 매핑됩니다.
 #[derive(Debug, Serialize, Deserialize, FromRow)]
@@ -15,19 +23,200 @@ pub struct Comment {
     pub post_id: Uuid,         // 게시글 ID (posts 테이블의 FK)
     pub author_id: Uuid,       // 작성자 ID (users 테이블의 FK)
     pub parent_id: Option<Uuid>, // 부모 댓글 ID (대댓글인 경우)
+    pub depth: i32,              // 중첩 깊이 (최상위 댓글이 1)
+    pub created_at: DateTime<Utc>, // 작성 시간
+    pub updated_at: DateTime<Utc>, // 수정 시간
+}
+
+// CommentView는 API 응답으로 반환되는 댓글입니다.
+// author_id 대신 작성자 요약 정보(author)를 포함합니다.
+#[derive(Debug, Serialize)]
+pub struct CommentView {
+    pub id: Uuid,                  // 댓글의 고유 식별자
+    pub content: String,           // 댓글 내용
+    pub post_id: Uuid,             // 게시글 ID
+    pub author: AuthorSummary,     // 작성자 요약 정보
+    pub parent_id: Option<Uuid>,   // 부모 댓글 ID (대댓글인 경우)
+    pub depth: i32,                // 중첩 깊이 (최상위 댓글이 1)
+    pub reply_count: i64,          // 직접 달린 대댓글 수
     pub created_at: DateTime<Utc>, // 작성 시간
     pub updated_at: DateTime<Utc>, // 수정 시간
 }
 
+// CommentViewRow는 comments와 users를 LEFT JOIN한 조회 결과 한 행입니다.
+// 작성자 계정이 없으면 author_username이 NULL입니다.
+// reply_count는 조회 시점에 집계하므로, 부모 댓글 삭제로 인한 CASCADE 삭제 후에도 항상 정확합니다.
+#[derive(Debug, FromRow)]
+pub struct CommentViewRow {
+    pub id: Uuid,
+    pub content: String,
+    pub post_id: Uuid,
+    pub author_id: Uuid,
+    pub author_username: Option<String>,
+    pub reply_count: i64,
+    pub parent_id: Option<Uuid>,
+    pub depth: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<CommentViewRow> for CommentView {
+    fn from(row: CommentViewRow) -> Self {
+        Self {
+            id: row.id,
+            content: row.content,
+            post_id: row.post_id,
+            author: AuthorSummary::from_join(row.author_id, row.author_username),
+            parent_id: row.parent_id,
+            depth: row.depth,
+            reply_count: row.reply_count,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
 // CreateCommentDto는 댓글 작성 요청에서 사용되는 데이터 구조입니다.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
 pub struct CreateCommentDto {
+    #[validate(
+        length(max = 10000, message = "content must be at most 10000 characters"),
+        custom = "not_blank"
+    )]
     pub content: String,         // 댓글 내용 (필수)
     pub parent_id: Option<Uuid>, // 부모 댓글 ID (대댓글 작성 시)
 }
 
 // UpdateCommentDto는 댓글 수정 요청에서 사용되는 데이터 구조입니다.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
 pub struct UpdateCommentDto {
+    #[validate(
+        length(max = 10000, message = "content must be at most 10000 characters"),
+        custom = "not_blank"
+    )]
     pub content: String,  // 새로운 댓글 내용
 }
+
+// CommentTreeQuery는 댓글 트리 조회 요청의 쿼리 매개변수입니다.
+// 예: ?depth=3&limit=10
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CommentTreeQuery {
+    pub depth: Option<i32>,  // 펼칠 최대 깊이 (최상위 댓글이 1)
+    pub limit: Option<i64>,  // 단계별(같은 부모 아래) 최대 댓글 수
+}
+
+// CommentTreeParams는 검증이 끝난 댓글 트리 조회 값입니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommentTreeParams {
+    pub max_depth: i32,
+    pub limit: i64,
+}
+
+impl CommentTreeQuery {
+    // 쿼리 매개변수를 검증하고 기본값을 채웁니다.
+    // depth와 limit은 1 이상이어야 하며, 설정된 최댓값으로 제한됩니다.
+    pub fn resolve(&self, config: &CommentsConfig) -> Result<CommentTreeParams, AppError> {
+        let max_depth = self.depth.unwrap_or(config.tree_default_depth);
+        if max_depth < 1 {
+            return Err(AppError::validation("depth", "depth must be at least 1"));
+        }
+        let limit = self.limit.unwrap_or(config.tree_default_limit);
+        if limit < 1 {
+            return Err(AppError::validation("limit", "limit must be at least 1"));
+        }
+
+        Ok(CommentTreeParams {
+            max_depth: max_depth.min(config.tree_max_depth),
+            limit: limit.min(config.tree_max_limit),
+        })
+    }
+}
+
+// CommentTreeRow는 재귀 CTE로 조회한 댓글 트리의 한 행입니다.
+// sibling_count는 같은 부모 아래의 전체 댓글 수로, 잘린 최상위 댓글 수를 계산하는 데 사용됩니다.
+#[derive(Debug, FromRow)]
+pub struct CommentTreeRow {
+    pub id: Uuid,
+    pub content: String,
+    pub post_id: Uuid,
+    pub author_id: Uuid,
+    pub author_username: Option<String>,
+    pub reply_count: i64,
+    pub parent_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub depth: i32,
+    pub sibling_count: i64,
+}
+
+// CommentNode는 댓글 트리의 한 노드입니다.
+// 깊이는 comment.depth로 함께 직렬화됩니다.
+// more_replies가 0보다 크면 깊이 또는 단계별 개수 제한으로 일부 대댓글이 생략된 것이며,
+// 클라이언트는 GET /api/comments/{id}/replies로 나머지를 불러올 수 있습니다.
+#[derive(Debug, Serialize)]
+pub struct CommentNode {
+    #[serde(flatten)]
+    pub comment: CommentView,     // 댓글 정보
+    pub replies: Vec<CommentNode>, // 트리에 포함된 대댓글
+    pub more_replies: i64,        // 트리에 포함되지 않은 직접 대댓글 수
+}
+
+// CommentTree는 게시글의 댓글 트리 조회 응답입니다.
+#[derive(Debug, Serialize)]
+pub struct CommentTree {
+    pub items: Vec<CommentNode>,  // 최상위 댓글 노드
+    pub more_comments: i64,       // 트리에 포함되지 않은 최상위 댓글 수
+    pub max_depth: i32,           // 적용된 최대 깊이
+    pub limit: i64,               // 적용된 단계별 최대 댓글 수
+}
+
+impl CommentTree {
+    // 재귀 CTE 조회 결과로 트리를 구성합니다.
+    // rows는 같은 부모 아래에서 작성 순서대로 정렬되어 있어야 합니다.
+    pub fn from_rows(rows: Vec<CommentTreeRow>, params: CommentTreeParams) -> Self {
+        let total_top_level = rows
+            .iter()
+            .find(|row| row.parent_id.is_none())
+            .map_or(0, |row| row.sibling_count);
+
+        // 부모 ID별로 자식 행을 모읍니다.
+        let mut children: HashMap<Option<Uuid>, Vec<CommentTreeRow>> = HashMap::new();
+        for row in rows {
+            children.entry(row.parent_id).or_default().push(row);
+        }
+
+        let items = build_nodes(None, &mut children);
+        Self {
+            more_comments: total_top_level - items.len() as i64,
+            items,
+            max_depth: params.max_depth,
+            limit: params.limit,
+        }
+    }
+}
+
+// parent 아래의 자식 행들을 노드로 변환합니다.
+fn build_nodes(parent: Option<Uuid>, children: &mut HashMap<Option<Uuid>, Vec<CommentTreeRow>>) -> Vec<CommentNode> {
+    let rows = children.remove(&parent).unwrap_or_default();
+    rows.into_iter()
+        .map(|row| {
+            let replies = build_nodes(Some(row.id), children);
+            let more_replies = row.reply_count - replies.len() as i64;
+            CommentNode {
+                comment: CommentView {
+                    id: row.id,
+                    content: row.content,
+                    post_id: row.post_id,
+                    author: AuthorSummary::from_join(row.author_id, row.author_username),
+                    parent_id: row.parent_id,
+                    depth: row.depth,
+                    reply_count: row.reply_count,
+                    created_at: row.created_at,
+                    updated_at: row.updated_at,
+                },
+                replies,
+                more_replies,
+            }
+        })
+        .collect()
+}
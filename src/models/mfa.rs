@@ -0,0 +1,55 @@
+// mfa.rs
+// 2단계 인증(TOTP)과 관련된 데이터 모델을 정의합니다.
+
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+// TotpEnrollment는 TOTP 등록을 시작할 때 반환되는 응답 구조입니다.
+// 비밀 키는 이 응답에서만 확인할 수 있습니다.
+#[derive(Debug, Serialize)]
+pub struct TotpEnrollment {
+    pub secret: String,            // 인증 앱에 직접 입력할 base32 비밀 키
+    pub provisioning_uri: String,  // QR 코드로 만들어 인증 앱으로 읽을 otpauth:// URI
+}
+
+// MfaCodeDto는 인증 앱의 코드나 복구 코드를 제출할 때 사용하는 데이터 구조입니다.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct MfaCodeDto {
+    #[validate(length(min = 1, max = 32, message = "code must be between 1 and 32 characters"))]
+    pub code: String,  // 6자리 TOTP 코드 또는 복구 코드
+}
+
+// DisableTotpDto는 2단계 인증을 끌 때 사용하는 데이터 구조입니다.
+// 탈취한 세션만으로 끌 수 없도록 현재 비밀번호와 인증 코드(또는 복구 코드)를 함께 받습니다.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct DisableTotpDto {
+    #[validate(length(min = 1, max = 72, message = "current_password must be between 1 and 72 characters"))]
+    pub current_password: String,  // 현재 비밀번호 (평문)
+    #[validate(length(min = 1, max = 32, message = "code must be between 1 and 32 characters"))]
+    pub code: String,              // 6자리 TOTP 코드 또는 복구 코드
+}
+
+// RecoveryCodes는 TOTP 등록을 마쳤을 때 반환되는 복구 코드 목록입니다.
+// 복구 코드는 해시만 저장하므로 이 응답에서만 확인할 수 있습니다.
+#[derive(Debug, Serialize)]
+pub struct RecoveryCodes {
+    pub recovery_codes: Vec<String>,  // 각각 한 번만 사용할 수 있는 복구 코드
+}
+
+// MfaChallenge는 2단계 인증을 사용하는 사용자가 비밀번호를 확인했을 때 반환되는 응답 구조입니다.
+// mfa_token과 인증 코드를 POST /api/auth/mfa/verify로 보내면 액세스 토큰을 받을 수 있습니다.
+#[derive(Debug, Serialize)]
+pub struct MfaChallenge {
+    pub mfa_required: bool,  // 항상 true (AuthResponse와 구분하기 위한 필드)
+    pub mfa_token: String,   // 인증 코드와 함께 제출할 토큰
+    pub expires_in: i64,     // mfa_token 유효 시간 (초)
+}
+
+// MfaVerifyDto는 로그인의 두 번째 단계에서 사용하는 데이터 구조입니다.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct MfaVerifyDto {
+    #[validate(length(min = 1, max = 128, message = "mfa_token must be between 1 and 128 characters"))]
+    pub mfa_token: String,  // 로그인 응답으로 받은 MFA 토큰
+    #[validate(length(min = 1, max = 32, message = "code must be between 1 and 32 characters"))]
+    pub code: String,       // 6자리 TOTP 코드 또는 복구 코드
+}
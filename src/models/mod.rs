@@ -1,3 +1,9 @@
 pub mod user;
 pub mod post;
 pub mod comment;
+pub mod pagination;
+pub mod validation;
+pub mod moderation;
+pub mod mfa;
+pub mod oidc;
+pub mod api_token;
@@ -0,0 +1,29 @@
+// moderation.rs
+// 모더레이터 작업 기록과 관련된 데이터 모델을 정의합니다.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+// 기록되는 모더레이터 작업의 종류입니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type)]
+#[sqlx(type_name = "moderator_action", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ModeratorAction {
+    UpdatePost,
+    DeletePost,
+    UpdateComment,
+    DeleteComment,
+}
+
+// ModeratorActionLog는 moderator_actions 테이블과 매핑됩니다.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ModeratorActionLog {
+    pub id: Uuid,                    // 기록 ID
+    pub moderator_id: Option<Uuid>,  // 작업한 모더레이터 (계정이 삭제되면 None)
+    pub action: ModeratorAction,     // 작업 종류
+    pub target_id: Uuid,             // 대상 게시글 또는 댓글 ID
+    pub target_author_id: Uuid,      // 대상 콘텐츠의 작성자 ID
+    pub created_at: DateTime<Utc>,   // 작업 시간
+}
@@ -0,0 +1,22 @@
+// oidc.rs
+// 외부 ID 공급자(OIDC) 로그인과 관련된 데이터 모델을 정의합니다.
+
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+// OidcAuthorization은 OIDC 로그인을 시작할 때 반환되는 응답 구조입니다.
+// 클라이언트는 authorization_url로 이동하여 공급자에서 로그인합니다.
+#[derive(Debug, Serialize)]
+pub struct OidcAuthorization {
+    pub authorization_url: String,  // 공급자의 로그인 페이지 주소 (state, nonce, PKCE code_challenge 포함)
+    pub expires_in: i64,            // 이 시간(초) 안에 로그인을 마쳐야 합니다
+}
+
+// OidcCallbackDto는 공급자가 redirect_uri로 돌려준 값을 제출할 때 사용하는 데이터 구조입니다.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct OidcCallbackDto {
+    #[validate(length(min = 1, max = 2048, message = "code must be between 1 and 2048 characters"))]
+    pub code: String,   // 공급자가 발급한 인가 코드
+    #[validate(length(min = 1, max = 128, message = "state must be between 1 and 128 characters"))]
+    pub state: String,  // 로그인을 시작할 때 받은 state
+}
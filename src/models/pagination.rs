@@ -0,0 +1,264 @@
+// pagination.rs
+// 목록 조회 API에서 공통으로 사용하는 페이지네이션 모델을 정의합니다.
+// 쿼리 매개변수 검증과 응답 봉투(envelope) 구조체가 포함되어 있습니다.
+//
+// 두 가지 방식을 지원합니다.
+// - 오프셋 방식: ?page=2&per_page=10 (기존 방식, 전체 개수와 페이지 수를 함께 반환)
+// - 커서 방식: ?after=<cursor> 또는 ?before=<cursor>
+//   (created_at, id) 기준의 키셋 페이지네이션으로, 깊은 페이지에서도 빠르고
+//   스크롤 도중 새 항목이 추가되어도 항목이 중복되거나 누락되지 않습니다.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::config::PaginationConfig;
+use crate::errors::{AppError, FieldError};
+
+// PaginationQuery는 목록 조회 요청의 쿼리 매개변수입니다.
+// 예: ?page=1&per_page=10, ?after=<cursor>&per_page=10
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PaginationQuery {
+    pub page: Option<i64>,       // 요청할 페이지 번호 (1부터 시작)
+    pub per_page: Option<i64>,   // 페이지당 항목 수
+    pub after: Option<String>,   // 이 커서 다음(더 오래된) 항목부터 조회
+    pub before: Option<String>,  // 이 커서 이전(더 최신) 항목까지 조회
+}
+
+// PageParams는 검증이 끝난 오프셋 방식의 페이지네이션 값입니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageParams {
+    pub page: i64,      // 1 이상의 페이지 번호
+    pub per_page: i64,  // 1 이상, 설정된 최댓값 이하의 페이지 크기
+}
+
+// Cursor는 목록에서의 위치를 나타냅니다.
+// 정렬 기준인 (created_at, id)를 담으며, 클라이언트에는 불투명한 문자열로 전달됩니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor {
+    pub created_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+// 커서 방식의 조회 방향입니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CursorDirection {
+    After(Cursor),   // 커서보다 뒤(정렬상 다음)의 항목
+    Before(Cursor),  // 커서보다 앞(정렬상 이전)의 항목
+}
+
+// CursorParams는 검증이 끝난 커서 방식의 페이지네이션 값입니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CursorParams {
+    pub direction: CursorDirection,
+    pub per_page: i64,
+}
+
+impl PaginationQuery {
+    // 오프셋 방식의 쿼리 매개변수를 검증하고 기본값을 채웁니다.
+    // page와 per_page는 1 이상이어야 하며, per_page는 설정된 최댓값으로 제한됩니다.
+    // OFFSET이 i64 범위를 넘을 만큼 큰 page도 거부합니다.
+    pub fn resolve(&self, config: &PaginationConfig) -> Result<PageParams, AppError> {
+        let page = self.page.unwrap_or(1);
+
+        let mut fields = Vec::new();
+        let per_page = self.resolve_per_page(config, &mut fields);
+        if page < 1 {
+            fields.insert(0, field_error("page", "page must be at least 1"));
+        } else if (page - 1).checked_mul(per_page.max(1)).is_none() {
+            fields.insert(0, field_error("page", "page is too large"));
+        }
+        if !fields.is_empty() {
+            return Err(invalid_pagination(fields));
+        }
+
+        Ok(PageParams { page, per_page })
+    }
+
+    // 커서 방식의 쿼리 매개변수를 검증합니다.
+    // after와 before가 모두 없으면 None을 반환하며, 이 경우 오프셋 방식을 사용합니다.
+    pub fn resolve_cursor(&self, config: &PaginationConfig) -> Result<Option<CursorParams>, AppError> {
+        let mut fields = Vec::new();
+        let direction = match (&self.after, &self.before) {
+            (None, None) => return Ok(None),
+            (Some(_), Some(_)) => {
+                fields.push(field_error("after", "after and before cannot be used together"));
+                None
+            }
+            (Some(raw), None) => decode_cursor_field("after", raw, &mut fields).map(CursorDirection::After),
+            (None, Some(raw)) => decode_cursor_field("before", raw, &mut fields).map(CursorDirection::Before),
+        };
+        if self.page.is_some() {
+            fields.push(field_error("page", "page cannot be combined with a cursor"));
+        }
+        let per_page = self.resolve_per_page(config, &mut fields);
+
+        match direction {
+            Some(direction) if fields.is_empty() => Ok(Some(CursorParams { direction, per_page })),
+            _ => Err(invalid_pagination(fields)),
+        }
+    }
+
+    // per_page를 검증하고 설정된 최댓값으로 제한합니다.
+    fn resolve_per_page(&self, config: &PaginationConfig, fields: &mut Vec<FieldError>) -> i64 {
+        let per_page = self.per_page.unwrap_or(config.default_per_page);
+        if per_page < 1 {
+            fields.push(field_error("per_page", "per_page must be at least 1"));
+        }
+        per_page.min(config.max_per_page)
+    }
+}
+
+impl PageParams {
+    pub fn new(page: i64, per_page: i64) -> Self {
+        Self { page, per_page }
+    }
+
+    // SQL OFFSET 값을 계산합니다.
+    pub fn offset(&self) -> i64 {
+        (self.page - 1) * self.per_page
+    }
+}
+
+impl Cursor {
+    pub fn new(created_at: DateTime<Utc>, id: Uuid) -> Self {
+        Self { created_at, id }
+    }
+
+    // 커서를 URL에 안전한 불투명 문자열로 인코딩합니다.
+    // PostgreSQL 타임스탬프 정밀도에 맞춰 마이크로초 단위로 저장합니다.
+    pub fn encode(&self) -> String {
+        let raw = format!("{}:{}", self.created_at.timestamp_micros(), self.id);
+        URL_SAFE_NO_PAD.encode(raw)
+    }
+
+    // 인코딩된 커서 문자열을 해석합니다. 형식이 올바르지 않으면 None을 반환합니다.
+    pub fn decode(encoded: &str) -> Option<Self> {
+        let raw = String::from_utf8(URL_SAFE_NO_PAD.decode(encoded).ok()?).ok()?;
+        let (micros, id) = raw.split_once(':')?;
+        Some(Self {
+            created_at: DateTime::from_timestamp_micros(micros.parse().ok()?)?,
+            id: Uuid::parse_str(id).ok()?,
+        })
+    }
+}
+
+impl CursorParams {
+    pub fn after(cursor: Cursor, per_page: i64) -> Self {
+        Self {
+            direction: CursorDirection::After(cursor),
+            per_page,
+        }
+    }
+
+    pub fn before(cursor: Cursor, per_page: i64) -> Self {
+        Self {
+            direction: CursorDirection::Before(cursor),
+            per_page,
+        }
+    }
+
+    // 다음 페이지가 있는지 확인하기 위해 한 건을 더 조회합니다.
+    pub fn fetch_limit(&self) -> i64 {
+        self.per_page + 1
+    }
+}
+
+// Page는 오프셋 방식의 목록 조회 응답을 감싸는 구조체입니다.
+// README에 문서화된 { items, total, page, per_page, total_pages } 형식으로 직렬화됩니다.
+#[derive(Debug, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,       // 현재 페이지의 항목
+    pub total: i64,          // 전체 항목 수
+    pub page: i64,           // 현재 페이지 번호
+    pub per_page: i64,       // 페이지당 항목 수
+    pub total_pages: i64,    // 전체 페이지 수
+    // 다음 페이지가 있으면 마지막 항목의 커서를 담습니다.
+    // 클라이언트는 이 값으로 커서 방식(?after=)으로 전환할 수 있습니다.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+impl<T> Page<T> {
+    pub fn new(items: Vec<T>, total: i64, params: PageParams) -> Self {
+        // 올림 나눗셈으로 전체 페이지 수를 계산합니다.
+        let total_pages = (total + params.per_page - 1) / params.per_page;
+        Self {
+            items,
+            total,
+            page: params.page,
+            per_page: params.per_page,
+            total_pages,
+            next_cursor: None,
+        }
+    }
+
+    // 다음 페이지가 있으면 마지막 항목으로 next_cursor를 채웁니다.
+    pub fn with_next_cursor(mut self, cursor_of: impl Fn(&T) -> Cursor) -> Self {
+        if self.page < self.total_pages {
+            self.next_cursor = self.items.last().map(|item| cursor_of(item).encode());
+        }
+        self
+    }
+}
+
+// CursorPage는 커서 방식의 목록 조회 응답을 감싸는 구조체입니다.
+#[derive(Debug, Serialize)]
+pub struct CursorPage<T> {
+    pub items: Vec<T>,                // 현재 페이지의 항목
+    pub per_page: i64,                // 페이지당 항목 수
+    pub next_cursor: Option<String>,  // 다음 페이지 조회용 커서 (?after=)
+    pub prev_cursor: Option<String>,  // 이전 페이지 조회용 커서 (?before=)
+}
+
+impl<T> CursorPage<T> {
+    // 조회 결과로 커서 페이지를 만듭니다.
+    // rows는 fetch_limit()만큼 조회한 결과이며, 요청 방향의 정렬 순서를 따릅니다.
+    // Before 방향의 경우 rows는 역순으로 조회되었으므로 목록 순서로 되돌립니다.
+    pub fn from_rows(mut rows: Vec<T>, params: CursorParams, cursor_of: impl Fn(&T) -> Cursor) -> Self {
+        let has_more = rows.len() as i64 > params.per_page;
+        rows.truncate(params.per_page as usize);
+
+        let (has_next, has_prev) = match params.direction {
+            // 커서 이전에는 커서가 가리키던 항목이 있으므로 이전 페이지가 존재합니다.
+            CursorDirection::After(_) => (has_more, true),
+            CursorDirection::Before(_) => {
+                rows.reverse();
+                (true, has_more)
+            }
+        };
+
+        let next_cursor = if has_next { rows.last().map(|item| cursor_of(item).encode()) } else { None };
+        let prev_cursor = if has_prev { rows.first().map(|item| cursor_of(item).encode()) } else { None };
+
+        Self {
+            items: rows,
+            per_page: params.per_page,
+            next_cursor,
+            prev_cursor,
+        }
+    }
+}
+
+fn field_error(field: &str, message: &str) -> FieldError {
+    FieldError {
+        field: field.to_string(),
+        message: message.to_string(),
+    }
+}
+
+fn invalid_pagination(fields: Vec<FieldError>) -> AppError {
+    AppError::Validation {
+        message: "Invalid pagination parameters".to_string(),
+        fields,
+    }
+}
+
+fn decode_cursor_field(field: &str, raw: &str, fields: &mut Vec<FieldError>) -> Option<Cursor> {
+    let cursor = Cursor::decode(raw);
+    if cursor.is_none() {
+        fields.push(field_error(field, "invalid cursor"));
+    }
+    cursor
+}
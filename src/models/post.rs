@@ -6,6 +6,10 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::user::AuthorSummary;
+use crate::models::validation::not_blank;
 
 // Post 구조체는 데이터베이스의 posts 테이블과 매핑됩니다.
 #[derive(Debug, Serialize, Deserialize, FromRow)]
@@ -18,16 +22,75 @@ pub struct Post {
     pub updated_at: DateTime<Utc>, // 수정 시간
 }
 
+// PostView는 API 응답으로 반환되는 게시글입니다.
+// author_id 대신 작성자 요약 정보(author)를 포함합니다.
+#[derive(Debug, Serialize)]
+pub struct PostView {
+    pub id: Uuid,                  // 게시글의 고유 식별자
+    pub title: String,             // 게시글 제목
+    pub content: String,           // 게시글 내용
+    pub author: AuthorSummary,     // 작성자 요약 정보
+    pub comment_count: i64,        // 댓글 수 (대댓글 포함)
+    pub created_at: DateTime<Utc>, // 작성 시간
+    pub updated_at: DateTime<Utc>, // 수정 시간
+}
+
+// PostViewRow는 posts와 users를 LEFT JOIN한 조회 결과 한 행입니다.
+// 작성자 계정이 없으면 author_username이 NULL입니다.
+// comment_count는 조회 시점에 comments 테이블에서 집계하므로, CASCADE 삭제 후에도 항상 정확합니다.
+#[derive(Debug, FromRow)]
+pub struct PostViewRow {
+    pub id: Uuid,
+    pub title: String,
+    pub content: String,
+    pub author_id: Uuid,
+    pub author_username: Option<String>,
+    pub comment_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<PostViewRow> for PostView {
+    fn from(row: PostViewRow) -> Self {
+        Self {
+            id: row.id,
+            title: row.title,
+            content: row.content,
+            author: AuthorSummary::from_join(row.author_id, row.author_username),
+            comment_count: row.comment_count,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
 // CreatePostDto는 게시글 작성 요청에서 사용되는 데이터 구조입니다.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
 pub struct CreatePostDto {
+    #[validate(
+        length(max = 255, message = "title must be at most 255 characters"),
+        custom = "not_blank"
+    )]
     pub title: String,    // 게시글 제목 (필수)
+    #[validate(
+        length(max = 50000, message = "content must be at most 50000 characters"),
+        custom = "not_blank"
+    )]
     pub content: String,  // 게시글 내용 (필수)
 }
 
 // UpdatePostDto는 게시글 수정 요청에서 사용되는 데이터 구조입니다.
-#[derive(Debug, Deserialize)]
+// 값이 있는 필드만 검증하며, 규칙은 CreatePostDto와 같습니다.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
 pub struct UpdatePostDto {
+    #[validate(
+        length(max = 255, message = "title must be at most 255 characters"),
+        custom = "not_blank"
+    )]
     pub title: Option<String>,    // 게시글 제목 (선택)
+    #[validate(
+        length(max = 50000, message = "content must be at most 50000 characters"),
+        custom = "not_blank"
+    )]
     pub content: Option<String>,  // 게시글 내용 (선택)
 }
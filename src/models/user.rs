@@ -6,6 +6,29 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
+use validator::Validate;
+
+use crate::errors::AppError;
+use crate::models::mfa::MfaChallenge;
+use crate::models::validation::valid_username;
+
+// 사용자 역할입니다. 선언 순서대로 권한이 커집니다 (User < Moderator < Admin).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "user_role", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    #[default]
+    User,       // 일반 사용자
+    Moderator,  // 다른 사용자의 게시글과 댓글을 수정, 삭제할 수 있음
+    Admin,      // 모더레이터 권한과 함께 사용자 역할을 변경할 수 있음
+}
+
+// Actor는 요청을 보낸 인증된 사용자입니다. 권한 확인에 사용합니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Actor {
+    pub id: Uuid,    // 사용자 ID
+    pub role: Role,  // 액세스 토큰에 담긴 역할
+}
 
 // User 구조체는 데이터베이스의 users 테이블과 매핑됩니다.
 #[derive(Debug, Serialize, Deserialize, FromRow)]
@@ -14,22 +37,177 @@ pub struct User {
     pub username: String,       // 사용자 이름
     pub email: String,         // 이메일 주소 (유니크)
     #[serde(skip_serializing)] // 비밀번호 해시는 JSON 응답에 포함되지 않습니다
-    pub password_hash: String, // bcrypt로 해시화된 비밀번호
+    pub password_hash: String, // 해시화된 비밀번호 (Argon2id 또는 bcrypt, 접두사로 구분)
+    #[serde(skip_serializing)]
+    pub token_version: i32,    // 액세스 토큰 버전 (올리면 기존 토큰이 모두 무효화됨)
+    pub role: Role,            // 사용자 역할
+    pub email_verified_at: Option<DateTime<Utc>>, // 이메일 인증 시간 (인증 전에는 None)
     pub created_at: DateTime<Utc>, // 계정 생성 시간
     pub updated_at: DateTime<Utc>, // 계정 업데이트 시간
 }
 
 // CreateUserDto는 회원가입 요청에서 사용되는 데이터 구조입니다.
-#[derive(Debug, Deserialize)]
+// 비밀번호 강도 규칙은 설정에 따라 달라지므로 AuthService::register에서 검사합니다.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
 pub struct CreateUserDto {
-    pub username: String,  // 사용자 이름 (필수)
+    #[validate(
+        length(min = 3, max = 50, message = "username must be between 3 and 50 characters"),
+        custom = "valid_username"
+    )]
+    pub username: String,  // 사용자 이름 (필수, 대소문자 구분 없이 유니크)
+    #[validate(
+        email(message = "email must be a valid email address"),
+        length(max = 255, message = "email must be at most 255 characters")
+    )]
     pub email: String,     // 이메일 주소 (필수, 유니크)
+    #[validate(length(max = 72, message = "password must be at most 72 characters"))]
     pub password: String,  // 비밀번호 (필수, 평문)
 }
 
 // LoginDto는 로그인 요청에서 사용되는 데이터 구조입니다.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
 pub struct LoginDto {
+    #[validate(email(message = "email must be a valid email address"))]
     pub email: String,     // 이메일 주소
+    #[validate(length(min = 1, max = 72, message = "password must be between 1 and 72 characters"))]
     pub password: String,  // 비밀번호 (평문)
 }
+
+// RefreshTokenDto는 토큰 갱신 요청에서 사용되는 데이터 구조입니다.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct RefreshTokenDto {
+    #[validate(length(min = 1, max = 128, message = "refresh_token must be between 1 and 128 characters"))]
+    pub refresh_token: String,  // 로그인 또는 이전 갱신에서 발급된 리프레시 토큰
+}
+
+// LogoutDto는 로그아웃 요청에서 사용되는 데이터 구조입니다.
+// 리프레시 토큰을 함께 보내면 해당 로그인에서 이어진 리프레시 토큰도 폐기합니다.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Validate)]
+pub struct LogoutDto {
+    #[validate(length(min = 1, max = 128, message = "refresh_token must be between 1 and 128 characters"))]
+    pub refresh_token: Option<String>,  // 함께 폐기할 리프레시 토큰 (선택)
+}
+
+// VerifyEmailDto는 이메일 인증 요청에서 사용되는 데이터 구조입니다.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct VerifyEmailDto {
+    #[validate(length(min = 1, max = 128, message = "token must be between 1 and 128 characters"))]
+    pub token: String,  // 인증 메일로 받은 토큰
+}
+
+// ForgotPasswordDto는 비밀번호 재설정 메일을 요청할 때 사용하는 데이터 구조입니다.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct ForgotPasswordDto {
+    #[validate(
+        email(message = "email must be a valid email address"),
+        length(max = 255, message = "email must be at most 255 characters")
+    )]
+    pub email: String,  // 가입한 이메일 주소
+}
+
+// ResetPasswordDto는 재설정 토큰으로 새 비밀번호를 설정할 때 사용하는 데이터 구조입니다.
+// 비밀번호 강도 규칙은 설정에 따라 달라지므로 AuthService::reset_password에서 검사합니다.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct ResetPasswordDto {
+    #[validate(length(min = 1, max = 128, message = "token must be between 1 and 128 characters"))]
+    pub token: String,     // 재설정 메일로 받은 토큰
+    #[validate(length(max = 72, message = "password must be at most 72 characters"))]
+    pub password: String,  // 새 비밀번호 (평문)
+}
+
+// UpdateProfileDto는 자신의 프로필을 수정할 때 사용하는 데이터 구조입니다.
+// 보낸 필드만 수정하며, 이메일을 바꾸려면 현재 비밀번호가 필요하고 새 주소로 다시 인증해야 합니다.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Validate)]
+pub struct UpdateProfileDto {
+    #[validate(
+        length(min = 3, max = 50, message = "username must be between 3 and 50 characters"),
+        custom = "valid_username"
+    )]
+    pub username: Option<String>,  // 새 사용자 이름 (선택)
+    #[validate(
+        email(message = "email must be a valid email address"),
+        length(max = 255, message = "email must be at most 255 characters")
+    )]
+    pub email: Option<String>,     // 새 이메일 주소 (선택)
+    #[validate(length(min = 1, max = 72, message = "current_password must be between 1 and 72 characters"))]
+    pub current_password: Option<String>,  // 현재 비밀번호 (이메일을 바꿀 때 필수)
+}
+
+// ChangePasswordDto는 로그인한 사용자가 비밀번호를 바꿀 때 사용하는 데이터 구조입니다.
+// 비밀번호 강도 규칙은 설정에 따라 달라지므로 UserService::change_password에서 검사합니다.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct ChangePasswordDto {
+    #[validate(length(min = 1, max = 72, message = "current_password must be between 1 and 72 characters"))]
+    pub current_password: String,  // 현재 비밀번호 (평문)
+    #[validate(length(max = 72, message = "password must be at most 72 characters"))]
+    pub password: String,          // 새 비밀번호 (평문)
+}
+
+// UpdateRoleDto는 관리자가 사용자 역할을 변경할 때 사용하는 데이터 구조입니다.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct UpdateRoleDto {
+    pub role: Role,  // 새 역할
+}
+
+// AuthResponse는 로그인 또는 토큰 갱신 성공 시 반환되는 응답 구조입니다.
+#[derive(Debug, Serialize)]
+pub struct AuthResponse {
+    pub token: String,          // 발급된 JWT 액세스 토큰
+    pub expires_in: i64,        // 액세스 토큰 유효 시간 (초)
+    pub refresh_token: String,  // 액세스 토큰 갱신용 리프레시 토큰 (한 번만 사용 가능)
+    pub user: User,             // 로그인한 사용자 정보
+}
+
+// LoginOutcome은 로그인 결과입니다.
+// 2단계 인증을 사용하지 않으면 바로 토큰을 발급하고, 사용하면 인증 코드를 요구합니다.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum LoginOutcome {
+    Authenticated(AuthResponse),  // 로그인 완료
+    MfaRequired(MfaChallenge),    // 인증 코드 확인 필요
+}
+
+impl LoginOutcome {
+    // 발급된 토큰을 꺼냅니다. 인증 코드 확인이 필요하면 Unauthorized를 반환합니다.
+    pub fn tokens(self) -> Result<AuthResponse, AppError> {
+        match self {
+            LoginOutcome::Authenticated(response) => Ok(response),
+            LoginOutcome::MfaRequired(_) => {
+                Err(AppError::Unauthorized("Two-factor authentication required".to_string()))
+            }
+        }
+    }
+}
+
+// 탈퇴 등으로 계정이 없는 작성자에게 표시할 이름입니다.
+pub const DELETED_USERNAME: &str = "[deleted]";
+
+// 이메일을 비교하고 저장할 때 쓰는 형태로 바꿉니다 (앞뒤 공백 제거, 소문자).
+// 사용자 조회와 로그인 실패 기록이 같은 값을 사용해야 대소문자만 바꿔 제한을 피할 수 없습니다.
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+// AuthorSummary는 게시글과 댓글 응답에 포함되는 작성자 요약 정보입니다.
+// 작성자 계정이 삭제된 경우 username은 DELETED_USERNAME으로, deleted는 true로 채워집니다.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AuthorSummary {
+    pub id: Uuid,          // 작성자 ID
+    pub username: String,  // 작성자 이름
+    pub deleted: bool,     // 작성자 계정이 삭제되었는지 여부
+}
+
+impl AuthorSummary {
+    // users 테이블과 LEFT JOIN한 결과로 작성자 요약 정보를 만듭니다.
+    // 조인된 사용자가 없으면 삭제된 작성자로 처리합니다.
+    pub fn from_join(id: Uuid, username: Option<String>) -> Self {
+        match username {
+            Some(username) => Self { id, username, deleted: false },
+            None => Self {
+                id,
+                username: DELETED_USERNAME.to_string(),
+                deleted: true,
+            },
+        }
+    }
+}
@@ -0,0 +1,71 @@
+// validation.rs
+// DTO 검증에 사용하는 공통 규칙을 정의합니다.
+// 필드 단위 규칙은 각 DTO의 #[validate(...)] 속성에서 사용하며,
+// 설정에 따라 달라지는 비밀번호 강도 규칙은 validate_password로 검사합니다.
+
+use validator::ValidationError;
+
+use crate::config::{SecurityConfig, MAX_PASSWORD_LENGTH};
+use crate::errors::{AppError, FieldError};
+use crate::models::user::DELETED_USERNAME;
+
+// 빈 문자열이나 공백만으로 이루어진 문자열을 거부합니다.
+pub fn not_blank(value: &str) -> Result<(), ValidationError> {
+    if value.trim().is_empty() {
+        let mut error = ValidationError::new("blank");
+        error.message = Some("must not be blank".into());
+        return Err(error);
+    }
+    Ok(())
+}
+
+// 사용자 이름 규칙을 검사합니다.
+// 공백만으로 이루어질 수 없으며, 삭제된 작성자 표시에 쓰는 이름(DELETED_USERNAME)은 사용할 수 없습니다.
+pub fn valid_username(value: &str) -> Result<(), ValidationError> {
+    not_blank(value)?;
+    if value.trim().eq_ignore_ascii_case(DELETED_USERNAME) {
+        let mut error = ValidationError::new("reserved");
+        error.message = Some("username is reserved".into());
+        return Err(error);
+    }
+    Ok(())
+}
+
+// 설정된 비밀번호 강도 규칙을 검사합니다.
+// 위반한 규칙을 모두 모아 password 필드의 검증 에러로 반환합니다.
+pub fn validate_password(password: &str, config: &SecurityConfig) -> Result<(), AppError> {
+    let mut problems = Vec::new();
+
+    if password.chars().count() < config.password_min_length {
+        problems.push(format!("password must be at least {} characters", config.password_min_length));
+    }
+    if password.len() > MAX_PASSWORD_LENGTH {
+        problems.push(format!("password must be at most {} bytes", MAX_PASSWORD_LENGTH));
+    }
+    if config.password_require_letter && !password.chars().any(|c| c.is_alphabetic()) {
+        problems.push("password must contain a letter".to_string());
+    }
+    if config.password_require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+        problems.push("password must contain a digit".to_string());
+    }
+    if config.password_require_uppercase && !password.chars().any(|c| c.is_uppercase()) {
+        problems.push("password must contain an uppercase letter".to_string());
+    }
+    if config.password_require_symbol && !password.chars().any(|c| !c.is_alphanumeric() && !c.is_whitespace()) {
+        problems.push("password must contain a symbol".to_string());
+    }
+
+    if problems.is_empty() {
+        return Ok(());
+    }
+    Err(AppError::Validation {
+        message: "Validation failed".to_string(),
+        fields: problems
+            .into_iter()
+            .map(|message| FieldError {
+                field: "password".to_string(),
+                message,
+            })
+            .collect(),
+    })
+}
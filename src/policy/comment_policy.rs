@@ -0,0 +1,50 @@
+// comment_policy.rs
+// 댓글 수정, 삭제 권한 정책입니다.
+// 공통 규칙에 더해, 게시판 설정(board.post_author_can_delete_comments)에 따라
+// 게시글 작성자가 자기 게시글에 달린 댓글을 삭제할 수 있습니다. 수정은 할 수 없습니다.
+
+use uuid::Uuid;
+
+use crate::config::BoardConfig;
+use crate::models::user::Actor;
+use crate::policy::{author_or_moderator, Action, Decision, Grant, Policy};
+
+// 권한 판단에 필요한 댓글 정보입니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommentResource {
+    pub author_id: Uuid,       // 댓글 작성자 ID
+    pub post_author_id: Uuid,  // 댓글이 달린 게시글의 작성자 ID
+}
+
+// CommentPolicy는 댓글에 대한 권한을 판단합니다.
+#[derive(Debug, Clone, Copy)]
+pub struct CommentPolicy {
+    post_author_can_delete_comments: bool,  // 게시글 작성자의 댓글 삭제 허용 여부
+}
+
+impl CommentPolicy {
+    // 게시판 설정으로 정책을 만듭니다.
+    pub fn new(board: &BoardConfig) -> Self {
+        Self {
+            post_author_can_delete_comments: board.post_author_can_delete_comments,
+        }
+    }
+}
+
+impl Policy for CommentPolicy {
+    type Resource = CommentResource;
+
+    fn can(&self, actor: &Actor, action: Action, comment: &CommentResource) -> Decision {
+        let decision = author_or_moderator(actor, comment.author_id);
+        if decision.is_allowed() {
+            return decision;
+        }
+
+        match action {
+            Action::Delete if self.post_author_can_delete_comments && actor.id == comment.post_author_id => {
+                Decision::Allow(Grant::PostAuthor)
+            }
+            _ => Decision::Deny,
+        }
+    }
+}
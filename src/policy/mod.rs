@@ -0,0 +1,72 @@
+// policy/mod.rs
+// 게시글과 댓글에 대한 권한 규칙을 한곳에 모은 정책 모듈입니다.
+// 서비스는 데이터베이스에서 리소스 정보(작성자 등)만 읽어 오고,
+// 허용 여부는 Policy::can으로 판단합니다. 정책은 순수 함수이므로 데이터베이스 없이 테스트할 수 있습니다.
+//
+// 공통 규칙:
+// - 작성자는 자신의 콘텐츠를 수정, 삭제할 수 있습니다.
+// - 모더레이터와 관리자는 모든 콘텐츠를 수정, 삭제할 수 있습니다.
+// 리소스별 규칙은 post_policy.rs와 comment_policy.rs에 있습니다.
+
+pub mod comment_policy;
+pub mod post_policy;
+
+use uuid::Uuid;
+
+use crate::models::user::{Actor, Role};
+
+pub use comment_policy::{CommentPolicy, CommentResource};
+pub use post_policy::{PostPolicy, PostResource};
+
+// 정책으로 확인하는 작업입니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Update,  // 수정
+    Delete,  // 삭제
+}
+
+// 작업이 허용된 근거입니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Grant {
+    Author,      // 콘텐츠 작성자 본인
+    PostAuthor,  // 댓글이 달린 게시글의 작성자
+    Moderator,   // 모더레이터 또는 관리자 권한 (모더레이터 작업으로 기록됨)
+}
+
+// 정책 판단 결과입니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    Allow(Grant),  // 허용 (근거 포함)
+    Deny,          // 거부
+}
+
+impl Decision {
+    // 작업이 허용되었는지 확인합니다.
+    pub fn is_allowed(&self) -> bool {
+        matches!(self, Decision::Allow(_))
+    }
+
+    // 모더레이터 권한으로 허용되어 기록이 필요한지 확인합니다.
+    pub fn is_moderation(&self) -> bool {
+        matches!(self, Decision::Allow(Grant::Moderator))
+    }
+}
+
+// Policy는 사용자(actor)가 리소스에 작업(action)을 할 수 있는지 판단합니다.
+pub trait Policy {
+    type Resource;
+
+    fn can(&self, actor: &Actor, action: Action, resource: &Self::Resource) -> Decision;
+}
+
+// 작성자 본인 여부를 확인하고, 아니라면 모더레이터 권한으로 허용되는지 확인합니다.
+// 리소스별 정책이 공통으로 사용하는 규칙입니다.
+fn author_or_moderator(actor: &Actor, author_id: Uuid) -> Decision {
+    if actor.id == author_id {
+        Decision::Allow(Grant::Author)
+    } else if actor.role >= Role::Moderator {
+        Decision::Allow(Grant::Moderator)
+    } else {
+        Decision::Deny
+    }
+}
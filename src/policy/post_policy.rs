@@ -0,0 +1,28 @@
+// post_policy.rs
+// 게시글 수정, 삭제 권한 정책입니다.
+// 게시글에는 공통 규칙(작성자, 모더레이터, 관리자)만 적용됩니다.
+
+use uuid::Uuid;
+
+use crate::models::user::Actor;
+use crate::policy::{author_or_moderator, Action, Decision, Policy};
+
+// 권한 판단에 필요한 게시글 정보입니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PostResource {
+    pub author_id: Uuid,  // 게시글 작성자 ID
+}
+
+// PostPolicy는 게시글에 대한 권한을 판단합니다.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PostPolicy;
+
+impl Policy for PostPolicy {
+    type Resource = PostResource;
+
+    fn can(&self, actor: &Actor, action: Action, post: &PostResource) -> Decision {
+        match action {
+            Action::Update | Action::Delete => author_or_moderator(actor, post.author_id),
+        }
+    }
+}
@@ -0,0 +1,176 @@
+// api_token_service.rs
+// 스크립트나 봇이 사용하는 개인 API 토큰을 발급, 조회, 폐기하고 요청의 API 토큰을 확인하는 서비스입니다.
+//
+// API 토큰은 TOKEN_PREFIX로 시작하므로 JWT 액세스 토큰과 구분됩니다 (AuthMiddleware가 이 접두사로 확인 방법을 고릅니다).
+// 데이터베이스에는 토큰 원문 대신 SHA-256 해시와 목록에 표시할 앞부분만 저장하므로, 원문은 발급할 때만 확인할 수 있습니다.
+// 토큰은 만들 때 고른 권한(scope)만 가지며, 사용자의 역할과 관계없이 항상 일반 사용자(Role::User)로 동작합니다.
+// 유출된 스크립트 토큰으로 다른 사용자의 글을 모더레이션할 수 없도록, 모더레이션은 로그인 세션으로만 할 수 있습니다.
+
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::errors::{AppError, TokenError};
+use crate::models::api_token::{ApiToken, CreateApiTokenDto, CreatedApiToken, Credential, Scope};
+use crate::models::user::{Actor, Role};
+use crate::services::opaque_token;
+
+// API 토큰 원문의 접두사입니다.
+pub const TOKEN_PREFIX: &str = "rsb_";
+
+// 목록에서 토큰을 구분할 수 있도록 저장하는 원문 앞부분의 길이입니다 (접두사 포함).
+const DISPLAY_PREFIX_LEN: usize = 12;
+
+// 요청마다 쓰기가 일어나지 않도록 last_used_at은 이 간격보다 오래된 경우에만 갱신합니다.
+const LAST_USED_UPDATE_INTERVAL_SECS: i64 = 60;
+
+// API 토큰 형식인지 확인합니다.
+pub fn is_api_token(token: &str) -> bool {
+    token.starts_with(TOKEN_PREFIX)
+}
+
+// 데이터베이스의 scopes 배열을 Scope 목록으로 변환합니다. 알 수 없는 이름은 무시합니다.
+fn parse_scopes(scopes: &[String]) -> Vec<Scope> {
+    scopes.iter().filter_map(|scope| scope.parse().ok()).collect()
+}
+
+// ApiTokenService는 개인 API 토큰 관리와 확인 기능을 제공합니다.
+pub struct ApiTokenService {
+    db: PgPool,  // 데이터베이스 연결 풀
+}
+
+impl ApiTokenService {
+    // 새로운 ApiTokenService 인스턴스를 생성합니다.
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    // 새 API 토큰을 발급합니다. 응답의 토큰 원문은 다시 확인할 수 없습니다.
+    // 만료 시간이 현재 시각 이전이면 expires_at 필드의 Validation 에러를 반환합니다.
+    pub async fn create(&self, user_id: Uuid, dto: CreateApiTokenDto) -> Result<CreatedApiToken, AppError> {
+        if dto.expires_at.is_some_and(|expires_at| expires_at <= Utc::now()) {
+            return Err(AppError::validation("expires_at", "expires_at must be in the future"));
+        }
+
+        // 같은 권한을 여러 번 보내도 한 번만 저장합니다.
+        let mut scopes = dto.scopes;
+        scopes.sort_by_key(|scope| scope.as_str());
+        scopes.dedup();
+        let scope_names: Vec<String> = scopes.iter().map(|scope| scope.to_string()).collect();
+
+        let token = format!("{}{}", TOKEN_PREFIX, opaque_token::generate());
+        let prefix = token[..DISPLAY_PREFIX_LEN].to_string();
+        let row = sqlx::query!(
+            r#"
+            INSERT INTO api_tokens (user_id, name, token_prefix, token_hash, scopes, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, created_at
+            "#,
+            user_id,
+            dto.name,
+            prefix,
+            opaque_token::hash(&token),
+            &scope_names,
+            dto.expires_at
+        )
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(CreatedApiToken {
+            token,
+            api_token: ApiToken {
+                id: row.id,
+                name: dto.name,
+                prefix,
+                scopes,
+                expires_at: dto.expires_at,
+                last_used_at: None,
+                created_at: row.created_at,
+            },
+        })
+    }
+
+    // 사용자의 API 토큰 목록을 최근에 만든 순서로 조회합니다. 폐기한 토큰은 포함하지 않습니다.
+    pub async fn list(&self, user_id: Uuid) -> Result<Vec<ApiToken>, AppError> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT id, name, token_prefix, scopes, expires_at, last_used_at, created_at
+            FROM api_tokens
+            WHERE user_id = $1 AND revoked_at IS NULL
+            ORDER BY created_at DESC, id
+            "#,
+            user_id
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ApiToken {
+                id: row.id,
+                name: row.name,
+                prefix: row.token_prefix,
+                scopes: parse_scopes(&row.scopes),
+                expires_at: row.expires_at,
+                last_used_at: row.last_used_at,
+                created_at: row.created_at,
+            })
+            .collect())
+    }
+
+    // API 토큰을 폐기합니다. 폐기한 토큰은 바로 사용할 수 없습니다.
+    // 다른 사용자의 토큰이거나 이미 폐기한 토큰이면 NotFound를 반환합니다.
+    pub async fn revoke(&self, user_id: Uuid, token_id: Uuid) -> Result<(), AppError> {
+        let result = sqlx::query!(
+            "UPDATE api_tokens SET revoked_at = CURRENT_TIMESTAMP WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL",
+            token_id,
+            user_id
+        )
+        .execute(&self.db)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("API token not found".to_string()));
+        }
+        Ok(())
+    }
+
+    // 요청의 API 토큰을 확인하고, 토큰 주인과 허용된 권한을 반환합니다.
+    // 없는 토큰, 폐기된 토큰, 만료된 토큰은 각각의 이유와 함께 InvalidToken을 반환합니다.
+    pub async fn authenticate(&self, token: &str) -> Result<(Actor, Credential), AppError> {
+        let row = sqlx::query!(
+            r#"
+            SELECT id, user_id, scopes, expires_at, last_used_at, revoked_at
+            FROM api_tokens
+            WHERE token_hash = $1
+            "#,
+            opaque_token::hash(token)
+        )
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or(AppError::InvalidToken(TokenError::Malformed))?;
+
+        let now = Utc::now();
+        if row.revoked_at.is_some() {
+            return Err(AppError::InvalidToken(TokenError::Revoked));
+        }
+        if row.expires_at.is_some_and(|expires_at| expires_at <= now) {
+            return Err(AppError::InvalidToken(TokenError::Expired));
+        }
+
+        if is_stale(row.last_used_at, now) {
+            sqlx::query!("UPDATE api_tokens SET last_used_at = $2 WHERE id = $1", row.id, now)
+                .execute(&self.db)
+                .await?;
+        }
+
+        // 작성자 본인 여부만으로 권한을 판단하도록 역할은 항상 User입니다 (PostPolicy, CommentPolicy).
+        let actor = Actor { id: row.user_id, role: Role::User };
+        let credential = Credential::ApiToken { id: row.id, scopes: parse_scopes(&row.scopes) };
+        Ok((actor, credential))
+    }
+}
+
+// last_used_at을 갱신할 때가 되었는지 확인합니다.
+fn is_stale(last_used_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+    last_used_at.is_none_or(|last_used_at| now - last_used_at >= Duration::seconds(LAST_USED_UPDATE_INTERVAL_SECS))
+}
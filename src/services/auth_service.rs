@@ -1,38 +1,140 @@
 // auth_service.rs
 // 사용자 인증과 관련된 비즈니스 로직을 처리하는 서비스입니다.
 // 회원가입, 로그인, JWT 토큰 관리 등의 기능을 제공합니다.
+//
+// 로그인하면 수명이 짧은 JWT 액세스 토큰과 불투명한 리프레시 토큰을 함께 발급합니다.
+// 리프레시 토큰은 한 번만 사용할 수 있으며, 갱신할 때마다 새 토큰으로 교체(rotation)됩니다.
+// 이미 교체된 토큰이 다시 제출되면 탈취된 것으로 보고 같은 family의 토큰을 모두 폐기합니다.
+//
+// 액세스 토큰은 만료 전에도 서버에서 폐기할 수 있습니다.
+// - 로그아웃: 토큰의 jti를 revoked_tokens에 기록합니다.
+// - 전체 로그아웃: users.token_version을 올려 ver 클레임이 다른 토큰을 모두 거부합니다.
+// 요청마다 데이터베이스를 조회하지 않도록 폐기 상태는 SessionCache를 거쳐 확인합니다.
+//
+// 비밀번호는 PasswordHasher로 해시하며, 로그인에 성공했을 때 저장된 해시가 현재 설정보다 오래된 방식이면 다시 해시합니다.
+//
+// 로그인 실패는 계정과 IP별로 기록하여, 반복해서 실패하면 점점 오래 기다리게 하고 일정 횟수 이상이면 잠급니다.
+// 가입하지 않은 이메일도 더미 해시로 비밀번호를 검증하여, 응답 시간으로 가입 여부가 드러나지 않게 합니다.
+//
+// 2단계 인증을 사용하는 사용자는 비밀번호를 확인하면 토큰 대신 수명이 짧은 MFA 토큰을 받고,
+// 인증 코드와 함께 verify_mfa로 제출해야 토큰을 발급받습니다. 틀린 코드도 로그인 실패로 기록됩니다.
+//
+// 외부 ID 공급자(OIDC)로 본인 확인을 마친 사용자도 sign_in으로 같은 방식의 토큰(또는 MFA 토큰)을 받습니다.
+//
+// 스크립트나 봇은 액세스 토큰 대신 사용자가 만든 개인 API 토큰을 사용할 수 있습니다 (authenticate_api_token).
+//
+// 비밀번호를 잊은 사용자는 메일로 받은 일회용 토큰으로 비밀번호를 재설정할 수 있습니다.
+// 재설정 요청의 응답은 이메일 가입 여부와 관계없이 같으며, 재설정하면 기존 세션이 모두 폐기됩니다.
 
-use bcrypt::{hash, verify, DEFAULT_COST};
-use chrono::{Duration, Utc};
-use jsonwebtoken::{encode, EncodingKey, Header};
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{decode, decode_header, encode, Validation};
+use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
+use std::collections::HashSet;
+use std::net::IpAddr;
+use std::sync::Arc;
+use tokio::sync::OnceCell;
 use uuid::Uuid;
 
-use crate::models::user::{CreateUserDto, LoginDto, User, AuthResponse};
-use crate::errors::AppError;
-use crate::config::JWT_SECRET;
+use crate::config::Config;
+use crate::mail::{self, Email, Mailer};
+use crate::models::api_token::Credential;
+use crate::models::mfa::{MfaChallenge, MfaVerifyDto};
+use crate::models::user::{normalize_email, Actor, CreateUserDto, LoginDto, LoginOutcome, Role, User, AuthResponse};
+use crate::services::api_token_service::ApiTokenService;
+use crate::services::jwt_keys::JwtKeys;
+use crate::services::login_throttle::LoginThrottle;
+use crate::services::mfa_service::MfaService;
+use crate::services::opaque_token;
+use crate::services::password_hasher::PasswordHasher;
+use crate::services::session_cache::{SessionCache, SessionState};
+use crate::models::validation::validate_password;
+use crate::errors::{AppError, TokenError};
+
+// JWT 토큰에 담기는 클레임입니다.
+// 토큰 발급(create_token)과 검증(verify_token)이 같은 구조체를 사용합니다.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Claims {
+    pub sub: String,  // 토큰 주체 (사용자 ID)
+    pub exp: i64,     // 만료 시간 (Unix timestamp)
+    pub nbf: i64,     // 이 시각 이전에는 사용할 수 없음 (Unix timestamp)
+    pub iat: i64,     // 발급 시간 (Unix timestamp)
+    pub iss: String,  // 토큰 발급자
+    pub aud: String,  // 토큰 수신자
+    pub jti: String,  // 토큰 고유 ID (개별 폐기에 사용)
+    pub ver: i32,     // 발급 시점의 사용자 token_version
+    #[serde(default)]
+    pub role: Role,   // 발급 시점의 사용자 역할
+}
 
 // AuthService는 사용자 인증 관련 기능을 제공하는 서비스 구조체입니다.
+// 폐기 상태 캐시를 워커 간에 공유하려면 하나의 인스턴스를 만들어 web::Data로 복제해야 합니다.
 pub struct AuthService {
-    db: PgPool,  // 데이터베이스 연결 풀
+    db: PgPool,             // 데이터베이스 연결 풀
+    config: Arc<Config>,    // 애플리케이션 설정 (JWT, 비밀번호 규칙 등)
+    keys: Arc<JwtKeys>,     // 액세스 토큰 서명 및 검증 키
+    sessions: Arc<SessionCache>, // 사용자별 토큰 폐기 상태 캐시 (UserService와 공유)
+    mailer: Arc<dyn Mailer>, // 비밀번호 재설정 메일 발송에 사용할 Mailer
+    throttle: LoginThrottle, // 로그인 실패 기록 및 제한
+    mfa: MfaService,         // 2단계 인증 코드 확인
+    api_tokens: ApiTokenService, // 개인 API 토큰 확인
+    passwords: PasswordHasher,   // 비밀번호 해시 및 확인
+    dummy_hash: OnceCell<String>, // 가입하지 않은 이메일의 비밀번호 검증에 사용할 해시
 }
 
 impl AuthService {
     // 새로운 AuthService 인스턴스를 생성합니다.
-    pub fn new(db: PgPool) -> Self {
-        Self { db }
+    // 서명 키를 읽을 수 없으면 패닉하므로, 서버 시작 시에는 키를 먼저 읽어 with_keys를 사용합니다.
+    pub fn new(db: PgPool, config: Arc<Config>) -> Self {
+        let keys = JwtKeys::from_config(&config.jwt).unwrap_or_else(|e| panic!("{}", e));
+        Self::with_keys(db, config, Arc::new(keys))
+    }
+
+    // 미리 읽어 둔 서명 키로 AuthService 인스턴스를 생성합니다.
+    // 메일은 설정(mail.transport)에 맞는 Mailer로 발송하며, 메일 설정이 잘못되면 패닉합니다.
+    pub fn with_keys(db: PgPool, config: Arc<Config>, keys: Arc<JwtKeys>) -> Self {
+        let sessions = Arc::new(SessionCache::new(std::time::Duration::from_secs(config.jwt.revocation_cache_ttl_secs)));
+        let mailer = mail::from_config(&config.mail).unwrap_or_else(|e| panic!("{}", e));
+        let throttle = LoginThrottle::new(db.clone(), config.clone());
+        let mfa = MfaService::new(db.clone(), config.clone());
+        let api_tokens = ApiTokenService::new(db.clone());
+        let passwords = PasswordHasher::new(&config.security);
+        Self { db, config, keys, sessions, mailer, throttle, mfa, api_tokens, passwords, dummy_hash: OnceCell::new() }
+    }
+
+    // 메일 발송에 사용할 Mailer를 교체합니다.
+    pub fn with_mailer(mut self, mailer: Arc<dyn Mailer>) -> Self {
+        self.mailer = mailer;
+        self
+    }
+
+    // 토큰 폐기 상태 캐시를 반환합니다.
+    // 비밀번호 변경처럼 다른 서비스에서 세션을 폐기할 때 같은 캐시를 무효화하는 데 사용합니다.
+    pub fn sessions(&self) -> Arc<SessionCache> {
+        self.sessions.clone()
     }
 
     // 회원가입 처리를 수행합니다.
-    // 이메일 중복 체크 후 비밀번호를 해시화하여 저장합니다.
+    // 이메일과 사용자 이름 중복 체크 후 비밀번호를 해시화하여 저장합니다.
     pub async fn register(&self, dto: CreateUserDto) -> Result<User, AppError> {
+        // 설정된 비밀번호 강도 규칙 검사
+        validate_password(&dto.password, &self.config.security)?;
+
         // 이메일 중복 체크
-        if self.get_user_by_email(&dto.email).await?.is_some() {
-            return Err(AppError::EmailAlreadyExists);
+        if find_user_by_email(&self.db, &dto.email).await?.is_some() {
+            return Err(AppError::Conflict("Email already exists".to_string()));
+        }
+
+        // 사용자 이름 중복 체크 (대소문자 구분 없음)
+        // 앞뒤 공백만 다른 이름으로 같아 보이는 계정을 만들 수 없도록, 확인과 저장 모두 공백을 제거한 이름을 사용합니다.
+        let username = dto.username.trim();
+        if username_taken(&self.db, username, None).await? {
+            return Err(AppError::Conflict("Username already exists".to_string()));
         }
 
         // 비밀번호 해시화
-        let password_hash = hash(dto.password.as_bytes(), DEFAULT_COST)?;
+        let password_hash = self.passwords.hash(&dto.password).await?;
 
         // 새 사용자 생성
         let user = sqlx::query_as!(
@@ -40,10 +142,10 @@ impl AuthService {
             r#"
             INSERT INTO users (username, email, password_hash)
             VALUES ($1, $2, $3)
-            RETURNING *
+            RETURNING id, username, email, password_hash, token_version, role AS "role: Role", email_verified_at, created_at, updated_at
             "#,
-            dto.username,
-            dto.email,
+            username,
+            normalize_email(&dto.email),
             password_hash
         )
         .fetch_one(&self.db)
@@ -52,61 +154,708 @@ impl AuthService {
         Ok(user)
     }
 
+    // 로그인 처리를 수행합니다.
+    // 클라이언트 IP를 알 수 없는 경우에 사용하며, 계정별 실패 제한만 적용됩니다.
+    pub async fn login(&self, dto: LoginDto) -> Result<LoginOutcome, AppError> {
+        self.login_from(dto, None).await
+    }
+
     // 로그인 처리를 수행합니다.
     // 이메일과 비밀번호를 검증하고 JWT 토큰을 발급합니다.
-    pub async fn login(&self, dto: LoginDto) -> Result<AuthResponse, AppError> {
+    // 계정이나 IP의 실패가 누적되어 제한 중이면 비밀번호를 확인하지 않고 RateLimited를 반환합니다.
+    // 2단계 인증을 사용하는 사용자에게는 토큰 대신 MFA 토큰을 발급합니다.
+    pub async fn login_from(&self, dto: LoginDto, client_ip: Option<IpAddr>) -> Result<LoginOutcome, AppError> {
+        let attempt = self.throttle.reserve(&dto.email, client_ip).await?;
+
         // 사용자 조회
-        let user = self.get_user_by_email(&dto.email)
-            .await?
-            .ok_or(AppError::InvalidCredentials)?;
+        let user = find_user_by_email(&self.db, &dto.email).await?;
 
         // 비밀번호 검증
-        if !verify(dto.password.as_bytes(), &user.password_hash)? {
-            return Err(AppError::InvalidCredentials);
+        // 가입하지 않은 이메일도 현재 설정으로 만든 더미 해시를 검증하여 응답 시간을 맞춥니다.
+        let valid = match &user {
+            Some(user) => self.passwords.verify(&dto.password, &user.password_hash).await?,
+            None => {
+                self.passwords.verify(&dto.password, self.dummy_hash().await?).await?;
+                false
+            }
+        };
+        let user = match user {
+            Some(user) if valid => user,
+            user => {
+                self.throttle.record_failure(attempt, user.map(|u| u.id)).await?;
+                return Err(AppError::Unauthorized("Invalid credentials".to_string()));
+            }
+        };
+        self.upgrade_password_hash(&user, &dto.password).await;
+
+        // 인증 코드까지 확인해야 로그인에 성공한 것이므로, 2단계 인증을 사용하면 실패 기록은 verify_mfa에서 지웁니다.
+        let outcome = self.sign_in(user).await?;
+        match outcome {
+            LoginOutcome::Authenticated(_) => self.throttle.record_success(attempt).await?,
+            LoginOutcome::MfaRequired(_) => self.throttle.cancel(attempt).await?,
         }
+        Ok(outcome)
+    }
 
-        // JWT 토큰 생성
-        let token = self.create_token(user.id)?;
+    // 본인 확인(비밀번호 또는 외부 ID 공급자)을 마친 사용자를 로그인시킵니다.
+    // 2단계 인증을 사용하는 사용자에게는 토큰 대신 MFA 토큰을 발급합니다.
+    pub async fn sign_in(&self, user: User) -> Result<LoginOutcome, AppError> {
+        if self.mfa.is_enabled(user.id).await? {
+            return Ok(LoginOutcome::MfaRequired(self.create_mfa_challenge(user.id).await?));
+        }
 
-        Ok(AuthResponse { token })
+        // 액세스 토큰과 리프레시 토큰 발급
+        Ok(LoginOutcome::Authenticated(self.issue_tokens(user).await?))
     }
 
-    // 이메일로 사용자를 조회합니다.
-    async fn get_user_by_email(&self, email: &str) -> Result<Option<User>, AppError> {
+    // 로그인의 두 번째 단계: MFA 토큰과 인증 코드(또는 복구 코드)를 확인하고 토큰을 발급합니다.
+    // MFA 토큰은 한 번만 사용할 수 있고, mfa.challenge_max_attempts번 틀리면 더 이상 사용할 수 없습니다.
+    pub async fn verify_mfa(&self, dto: MfaVerifyDto, client_ip: Option<IpAddr>) -> Result<AuthResponse, AppError> {
+        let mut tx = self.db.begin().await?;
+
+        // 같은 MFA 토큰으로 동시에 시도하는 요청이 시도 횟수 제한을 피하지 않도록 행을 잠급니다.
+        let challenge = sqlx::query!(
+            r#"
+            SELECT c.id, c.user_id, u.email
+            FROM mfa_challenges c
+            JOIN users u ON u.id = c.user_id
+            WHERE c.token_hash = $1 AND c.used_at IS NULL AND c.expires_at > CURRENT_TIMESTAMP
+            FOR UPDATE OF c
+            "#,
+            opaque_token::hash(&dto.mfa_token)
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::Unauthorized("Invalid or expired MFA token".to_string()))?;
+
+        let attempt = self.throttle.reserve(&challenge.email, client_ip).await?;
+
+        if !self.mfa.verify_code(challenge.user_id, &dto.code).await? {
+            sqlx::query!(
+                r#"
+                UPDATE mfa_challenges
+                SET attempts = attempts + 1,
+                    used_at = CASE WHEN attempts + 1 >= $2 THEN CURRENT_TIMESTAMP END
+                WHERE id = $1
+                "#,
+                challenge.id,
+                self.config.mfa.challenge_max_attempts
+            )
+            .execute(&mut *tx)
+            .await?;
+            tx.commit().await?;
+
+            self.throttle.record_failure(attempt, Some(challenge.user_id)).await?;
+            return Err(AppError::Unauthorized("Invalid authentication code".to_string()));
+        }
+
+        sqlx::query!(
+            "UPDATE mfa_challenges SET used_at = CURRENT_TIMESTAMP WHERE id = $1",
+            challenge.id
+        )
+        .execute(&mut *tx)
+        .await?;
         let user = sqlx::query_as!(
             User,
-            "SELECT * FROM users WHERE email = $1",
-            email
+            r#"
+            SELECT id, username, email, password_hash, token_version, role AS "role: Role", email_verified_at, created_at, updated_at
+            FROM users WHERE id = $1
+            "#,
+            challenge.user_id
         )
-        .fetch_optional(&self.db)
+        .fetch_one(&mut *tx)
         .await?;
+        tx.commit().await?;
+        self.throttle.record_success(attempt).await?;
 
-        Ok(user)
+        self.issue_tokens(user).await
     }
 
-    // JWT 토큰을 생성합니다.
-    // 토큰은 24시간 동안 유효합니다.
-    fn create_token(&self, user_id: Uuid) -> Result<String, AppError> {
-        #[derive(serde::Serialize)]
-        struct Claims {
-            sub: Uuid,           // 토큰 주체 (사용자 ID)
-            exp: i64,            // 만료 시간 (Unix timestamp)
-            iat: i64,            // 발급 시간 (Unix timestamp)
+    // 비밀번호를 확인한 사용자에게 인증 코드를 입력할 때까지 사용할 MFA 토큰을 발급합니다.
+    async fn create_mfa_challenge(&self, user_id: Uuid) -> Result<MfaChallenge, AppError> {
+        let token = opaque_token::generate();
+        let ttl = self.config.mfa.challenge_ttl_secs;
+        sqlx::query!(
+            r#"
+            INSERT INTO mfa_challenges (user_id, token_hash, expires_at)
+            VALUES ($1, $2, $3)
+            "#,
+            user_id,
+            opaque_token::hash(&token),
+            Utc::now() + Duration::seconds(ttl)
+        )
+        .execute(&self.db)
+        .await?;
+
+        Ok(MfaChallenge {
+            mfa_required: true,
+            mfa_token: token,
+            expires_in: ttl,
+        })
+    }
+
+    // 현재 설정으로 만든 더미 해시를 반환합니다. 처음 사용할 때 한 번만 만듭니다.
+    async fn dummy_hash(&self) -> Result<&str, AppError> {
+        let dummy = self
+            .dummy_hash
+            .get_or_try_init(|| async { self.passwords.hash(&Uuid::new_v4().to_string()).await })
+            .await?;
+        Ok(dummy)
+    }
+
+    // 저장된 해시의 알고리즘이나 비용이 현재 설정과 다르면, 확인을 마친 비밀번호로 다시 해시하여 저장합니다.
+    // 그 사이에 비밀번호가 바뀌었으면 덮어쓰지 않으며, 실패해도 로그인은 계속 진행합니다.
+    async fn upgrade_password_hash(&self, user: &User, password: &str) {
+        if !self.passwords.needs_rehash(&user.password_hash) {
+            return;
+        }
+        let result = async {
+            let password_hash = self.passwords.hash(password).await?;
+            sqlx::query!(
+                "UPDATE users SET password_hash = $3 WHERE id = $1 AND password_hash = $2",
+                user.id,
+                user.password_hash,
+                password_hash
+            )
+            .execute(&self.db)
+            .await?;
+            Ok::<_, AppError>(())
+        }
+        .await;
+        if let Err(e) = result {
+            log::warn!("failed to upgrade password hash for user {}: {:?}", user.id, e);
+        }
+    }
+
+    // 리프레시 토큰으로 새 액세스 토큰과 리프레시 토큰을 발급합니다.
+    // 제출된 리프레시 토큰은 사용 처리되어 다시 사용할 수 없습니다.
+    pub async fn refresh(&self, refresh_token: &str) -> Result<AuthResponse, AppError> {
+        let token_hash = opaque_token::hash(refresh_token);
+        let mut tx = self.db.begin().await?;
+
+        // 동시에 같은 토큰으로 갱신하는 요청이 둘 다 성공하지 않도록 행을 잠급니다.
+        let stored = sqlx::query!(
+            r#"
+            SELECT id, user_id, family_id, expires_at, used_at, revoked_at
+            FROM refresh_tokens
+            WHERE token_hash = $1
+            FOR UPDATE
+            "#,
+            token_hash
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::Unauthorized("Invalid refresh token".to_string()))?;
+
+        if stored.revoked_at.is_some() {
+            return Err(AppError::Unauthorized("Invalid refresh token".to_string()));
+        }
+
+        // 이미 교체된 토큰의 재사용: 토큰이 탈취된 것으로 보고 family 전체를 폐기합니다.
+        if stored.used_at.is_some() {
+            sqlx::query!(
+                r#"
+                UPDATE refresh_tokens
+                SET revoked_at = CURRENT_TIMESTAMP
+                WHERE family_id = $1 AND revoked_at IS NULL
+                "#,
+                stored.family_id
+            )
+            .execute(&mut *tx)
+            .await?;
+            tx.commit().await?;
+
+            log::warn!(
+                "refresh token reuse detected for user {}, revoked token family {}",
+                stored.user_id,
+                stored.family_id
+            );
+            return Err(AppError::Unauthorized("Invalid refresh token".to_string()));
         }
 
+        if stored.expires_at <= Utc::now() {
+            return Err(AppError::Unauthorized("Refresh token expired".to_string()));
+        }
+
+        let user = sqlx::query_as!(
+            User,
+            r#"
+            SELECT id, username, email, password_hash, token_version, role AS "role: Role", email_verified_at, created_at, updated_at
+            FROM users WHERE id = $1
+            "#,
+            stored.user_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        // 같은 family로 새 리프레시 토큰을 발급하고, 기존 토큰을 사용 처리합니다.
+        let (new_token_id, new_refresh_token) =
+            self.insert_refresh_token(&mut tx, user.id, stored.family_id).await?;
+        sqlx::query!(
+            r#"
+            UPDATE refresh_tokens
+            SET used_at = CURRENT_TIMESTAMP, replaced_by = $2
+            WHERE id = $1
+            "#,
+            stored.id,
+            new_token_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(AuthResponse {
+            token: self.create_token(&user)?,
+            expires_in: self.config.jwt.ttl_secs,
+            refresh_token: new_refresh_token,
+            user,
+        })
+    }
+
+    // 액세스 토큰과 새 family의 리프레시 토큰을 발급합니다.
+    async fn issue_tokens(&self, user: User) -> Result<AuthResponse, AppError> {
+        let token = self.create_token(&user)?;
+
+        let mut tx = self.db.begin().await?;
+        let (_, refresh_token) = self.insert_refresh_token(&mut tx, user.id, Uuid::new_v4()).await?;
+        tx.commit().await?;
+
+        Ok(AuthResponse {
+            token,
+            expires_in: self.config.jwt.ttl_secs,
+            refresh_token,
+            user,
+        })
+    }
+
+    // 새 리프레시 토큰을 생성하여 해시를 저장하고, (토큰 ID, 토큰 원문)을 반환합니다.
+    async fn insert_refresh_token(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        user_id: Uuid,
+        family_id: Uuid,
+    ) -> Result<(Uuid, String), AppError> {
+        let refresh_token = opaque_token::generate();
+        let expires_at = Utc::now() + Duration::seconds(self.config.jwt.refresh_ttl_secs);
+
+        let token_id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO refresh_tokens (user_id, family_id, token_hash, expires_at)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id
+            "#,
+            user_id,
+            family_id,
+            opaque_token::hash(&refresh_token),
+            expires_at
+        )
+        .fetch_one(&mut **tx)
+        .await?;
+
+        Ok((token_id, refresh_token))
+    }
+
+    // JWT 토큰을 생성합니다.
+    // 액세스 토큰의 유효 시간은 설정(jwt.ttl_secs)을 따릅니다.
+    fn create_token(&self, user: &User) -> Result<String, AppError> {
         let now = Utc::now();
         let claims = Claims {
-            sub: user_id,
-            exp: (now + Duration::hours(24)).timestamp(),
+            sub: user.id.to_string(),
+            exp: (now + Duration::seconds(self.config.jwt.ttl_secs)).timestamp(),
+            nbf: now.timestamp(),
             iat: now.timestamp(),
+            iss: self.config.jwt.issuer.clone(),
+            aud: self.config.jwt.audience.clone(),
+            jti: Uuid::new_v4().to_string(),
+            ver: user.token_version,
+            role: user.role,
         };
 
-        let token = encode(
-            &Header::default(),
-            &claims,
-            &EncodingKey::from_secret(JWT_SECRET.as_bytes()),
-        )?;
+        let token = encode(&self.keys.header(), &claims, self.keys.encoding_key())?;
 
         Ok(token)
     }
+
+    // 액세스 토큰을 검증하고, 서버에서 폐기되지 않았는지 확인한 뒤 클레임을 반환합니다.
+    // AuthMiddleware가 인증이 필요한 모든 요청에서 호출합니다.
+    pub async fn authenticate(&self, token: &str) -> Result<Claims, AppError> {
+        let claims = self.verify_token(token)?;
+        let (user_id, jti) = parse_subject(&claims)?;
+
+        let state = match self.sessions.get(user_id) {
+            Some(state) => state,
+            None => {
+                // 읽는 도중 로그아웃되면 오래된 상태를 캐시하지 않도록 읽기 전의 세대를 함께 전달합니다.
+                let generation = self.sessions.generation(user_id);
+                let state = self.load_session_state(user_id).await?;
+                self.sessions.insert(user_id, state.clone(), generation);
+                state
+            }
+        };
+
+        if claims.ver != state.token_version || state.revoked_jtis.contains(&jti) {
+            return Err(AppError::InvalidToken(TokenError::Revoked));
+        }
+        Ok(claims)
+    }
+
+    // 개인 API 토큰을 확인하고, 토큰 주인과 허용된 권한을 반환합니다.
+    // 폐기되었거나 만료된 토큰이면 InvalidToken을 반환합니다.
+    pub async fn authenticate_api_token(&self, token: &str) -> Result<(Actor, Credential), AppError> {
+        self.api_tokens.authenticate(token).await
+    }
+
+    // 현재 액세스 토큰을 폐기합니다.
+    // 리프레시 토큰이 주어지면 해당 로그인에서 이어진 리프레시 토큰도 함께 폐기합니다.
+    pub async fn logout(&self, claims: &Claims, refresh_token: Option<&str>) -> Result<(), AppError> {
+        let (user_id, jti) = parse_subject(claims)?;
+        let expires_at = DateTime::from_timestamp(claims.exp, 0)
+            .ok_or(AppError::InvalidToken(TokenError::InvalidClaims))?;
+
+        let mut tx = self.db.begin().await?;
+
+        // 만료된 폐기 기록은 더 이상 필요하지 않으므로 함께 정리합니다.
+        sqlx::query!("DELETE FROM revoked_tokens WHERE expires_at < CURRENT_TIMESTAMP")
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query!(
+            r#"
+            INSERT INTO revoked_tokens (jti, user_id, expires_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (jti) DO NOTHING
+            "#,
+            jti,
+            user_id,
+            expires_at
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if let Some(refresh_token) = refresh_token {
+            sqlx::query!(
+                r#"
+                UPDATE refresh_tokens
+                SET revoked_at = CURRENT_TIMESTAMP
+                WHERE revoked_at IS NULL
+                  AND family_id = (
+                      SELECT family_id FROM refresh_tokens WHERE token_hash = $1 AND user_id = $2
+                  )
+                "#,
+                opaque_token::hash(refresh_token),
+                user_id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        self.sessions.invalidate(user_id);
+        Ok(())
+    }
+
+    // 사용자의 모든 액세스 토큰, 리프레시 토큰, API 토큰을 폐기합니다.
+    pub async fn logout_all(&self, user_id: Uuid) -> Result<(), AppError> {
+        let mut tx = self.db.begin().await?;
+        revoke_sessions(&mut tx, user_id).await?;
+        tx.commit().await?;
+
+        self.sessions.invalidate(user_id);
+        Ok(())
+    }
+
+    // 비밀번호 재설정 메일을 보냅니다.
+    // 이메일의 가입 여부를 알 수 없도록, 가입하지 않은 이메일이거나 재발송 간격
+    // (security.password_reset_resend_secs)이 지나지 않았어도 항상 성공으로 응답합니다.
+    // 응답 시간으로 가입 여부가 드러나지 않도록 사용자 조회, 토큰 발급, 메일 발송을 모두 백그라운드에서 처리합니다.
+    pub async fn forgot_password(&self, email: &str) -> Result<(), AppError> {
+        let db = self.db.clone();
+        let config = self.config.clone();
+        let mailer = self.mailer.clone();
+        let email = email.to_string();
+        tokio::spawn(async move {
+            if let Err(e) = send_password_reset(&db, &config, mailer.as_ref(), &email).await {
+                log::error!("failed to send password reset email: {}", e);
+            }
+        });
+        Ok(())
+    }
+
+    // 재설정 토큰을 확인하고 새 비밀번호를 설정합니다.
+    // 토큰이 없거나, 만료되었거나, 이미 사용되었으면 BadRequest를 반환합니다.
+    // 비밀번호를 바꾸면 기존 액세스 토큰, 리프레시 토큰, API 토큰을 모두 폐기합니다.
+    pub async fn reset_password(&self, token: &str, password: &str) -> Result<(), AppError> {
+        // 규칙 확인은 토큰을 사용하기 전에 하여, 약한 비밀번호를 보내도 토큰이 사용 처리되지 않게 합니다.
+        validate_password(password, &self.config.security)?;
+
+        let mut tx = self.db.begin().await?;
+
+        // 토큰 사용 처리와 유효성 확인을 한 쿼리로 수행하여, 같은 토큰이 동시에 두 번 사용되지 않게 합니다.
+        // 비밀번호 해시는 비용이 크므로 유효한 토큰을 확인한 뒤에만 계산합니다.
+        let user_id = sqlx::query_scalar!(
+            r#"
+            UPDATE password_reset_tokens
+            SET used_at = CURRENT_TIMESTAMP
+            WHERE token_hash = $1 AND used_at IS NULL AND expires_at > CURRENT_TIMESTAMP
+            RETURNING user_id
+            "#,
+            opaque_token::hash(token)
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::BadRequest("Invalid or expired password reset token".to_string()))?;
+        let password_hash = self.passwords.hash(password).await?;
+
+        sqlx::query!(
+            "UPDATE users SET password_hash = $2, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
+            user_id,
+            password_hash
+        )
+        .execute(&mut *tx)
+        .await?;
+        revoke_sessions(&mut tx, user_id).await?;
+        tx.commit().await?;
+
+        self.sessions.invalidate(user_id);
+        log::info!("user {} reset their password", user_id);
+        Ok(())
+    }
+
+    // 사용자의 역할을 변경합니다.
+    // 기존 액세스 토큰에는 이전 역할이 담겨 있으므로 token_version을 올려 모두 무효화합니다.
+    // 리프레시 토큰은 유지되므로, 클라이언트는 토큰을 갱신하여 새 역할이 담긴 토큰을 받을 수 있습니다.
+    pub async fn set_role(&self, actor: &Actor, user_id: Uuid, role: Role) -> Result<User, AppError> {
+        // 관리자가 실수로 자신의 권한을 잃지 않도록 자신의 역할은 변경할 수 없습니다.
+        if actor.id == user_id {
+            return Err(AppError::Forbidden("You cannot change your own role".to_string()));
+        }
+
+        let user = sqlx::query_as!(
+            User,
+            r#"
+            UPDATE users
+            SET role = $2, token_version = token_version + 1, updated_at = CURRENT_TIMESTAMP
+            WHERE id = $1
+            RETURNING id, username, email, password_hash, token_version, role AS "role: Role", email_verified_at, created_at, updated_at
+            "#,
+            user_id,
+            role as Role
+        )
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        self.sessions.invalidate(user_id);
+        log::info!("user {} changed the role of {} to {:?}", actor.id, user_id, role);
+        Ok(user)
+    }
+
+    // 사용자의 현재 token_version과 폐기된 jti 목록을 조회합니다.
+    // 사용자가 삭제되었으면 토큰을 더 이상 사용할 수 없습니다.
+    async fn load_session_state(&self, user_id: Uuid) -> Result<SessionState, AppError> {
+        let token_version = sqlx::query_scalar!("SELECT token_version FROM users WHERE id = $1", user_id)
+            .fetch_optional(&self.db)
+            .await?
+            .ok_or(AppError::InvalidToken(TokenError::Revoked))?;
+
+        let revoked_jtis = sqlx::query_scalar!(
+            "SELECT jti FROM revoked_tokens WHERE user_id = $1 AND expires_at >= CURRENT_TIMESTAMP",
+            user_id
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(SessionState {
+            token_version,
+            revoked_jtis: revoked_jtis.into_iter().collect::<HashSet<_>>(),
+        })
+    }
+
+    // JWT 토큰을 검증하고 클레임을 반환합니다.
+    // 헤더의 kid로 검증 키를 찾은 뒤 서명과 exp, nbf, iat, iss, aud를 확인하며,
+    // 시간 관련 클레임은 jwt.leeway_secs만큼의 오차를 허용합니다.
+    // 실패하면 원인에 따라 AppError::InvalidToken을 반환합니다.
+    pub fn verify_token(&self, token: &str) -> Result<Claims, AppError> {
+        let jwt = &self.config.jwt;
+        let header = decode_header(token).map_err(|e| AppError::InvalidToken(TokenError::from(&e)))?;
+        // 알 수 없는 kid는 서명을 검증할 수 없는 토큰으로 취급합니다.
+        let (algorithm, key) = self
+            .keys
+            .verifying_key(&header)
+            .ok_or(AppError::InvalidToken(TokenError::BadSignature))?;
+
+        let mut validation = Validation::new(algorithm);
+        validation.set_required_spec_claims(&["exp", "nbf", "iat", "iss", "aud", "sub"]);
+        validation.set_issuer(&[&jwt.issuer]);
+        validation.set_audience(&[&jwt.audience]);
+        validation.validate_nbf = true;
+        validation.leeway = jwt.leeway_secs;
+
+        let claims = decode::<Claims>(token, key, &validation)
+            .map_err(|e| AppError::InvalidToken(TokenError::from(&e)))?
+            .claims;
+
+        // jsonwebtoken은 iat를 검사하지 않으므로, 미래에 발급된 토큰은 직접 거부합니다.
+        if claims.iat > Utc::now().timestamp() + jwt.leeway_secs as i64 {
+            return Err(AppError::InvalidToken(TokenError::NotYetValid));
+        }
+
+        Ok(claims)
+    }
+
+    // 다른 서비스가 액세스 토큰을 검증할 때 사용할 공개 키 목록(JWKS)을 반환합니다.
+    pub fn jwks(&self) -> &JwkSet {
+        self.keys.jwks()
+    }
+}
+
+// 사용자의 모든 세션을 폐기합니다.
+// token_version을 올리면 이전 버전으로 발급된 액세스 토큰이 모두 거부되며, 리프레시 토큰과 개인 API 토큰은 폐기 처리합니다.
+// API 토큰도 함께 폐기해야 탈취된 계정을 되찾은 뒤(비밀번호 재설정 등) 공격자가 만든 토큰이 남지 않습니다.
+// 호출한 쪽에서 트랜잭션을 커밋한 뒤 SessionCache를 무효화해야 합니다.
+pub(crate) async fn revoke_sessions(tx: &mut sqlx::Transaction<'_, sqlx::Postgres>, user_id: Uuid) -> Result<(), AppError> {
+    sqlx::query!(
+        "UPDATE users SET token_version = token_version + 1 WHERE id = $1",
+        user_id
+    )
+    .execute(&mut **tx)
+    .await?;
+    sqlx::query!(
+        r#"
+        UPDATE refresh_tokens
+        SET revoked_at = CURRENT_TIMESTAMP
+        WHERE user_id = $1 AND revoked_at IS NULL
+        "#,
+        user_id
+    )
+    .execute(&mut **tx)
+    .await?;
+    sqlx::query!(
+        r#"
+        UPDATE api_tokens
+        SET revoked_at = CURRENT_TIMESTAMP
+        WHERE user_id = $1 AND revoked_at IS NULL
+        "#,
+        user_id
+    )
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+// 재설정 토큰을 발급하고 메일을 보냅니다. forgot_password가 백그라운드에서 실행합니다.
+// 가입하지 않은 이메일이거나 재발송 간격이 지나지 않았으면 아무것도 하지 않습니다.
+async fn send_password_reset(db: &PgPool, config: &Config, mailer: &dyn Mailer, email: &str) -> Result<(), AppError> {
+    let Some(user) = find_user_by_email(db, email).await? else {
+        return Ok(());
+    };
+
+    let mut tx = db.begin().await?;
+
+    // 같은 사용자의 요청을 직렬화하여 재발송 간격 확인이 동시에 통과되지 않게 합니다.
+    sqlx::query!("SELECT id FROM users WHERE id = $1 FOR UPDATE", user.id)
+        .fetch_one(&mut *tx)
+        .await?;
+    let recently_sent = sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM password_reset_tokens
+            WHERE user_id = $1 AND created_at > $2
+        ) AS "exists!"
+        "#,
+        user.id,
+        Utc::now() - Duration::seconds(config.security.password_reset_resend_secs)
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+    if recently_sent {
+        return Ok(());
+    }
+
+    // 가장 최근에 보낸 토큰만 유효하도록 이전 토큰은 사용 처리합니다.
+    sqlx::query!(
+        "UPDATE password_reset_tokens SET used_at = CURRENT_TIMESTAMP WHERE user_id = $1 AND used_at IS NULL",
+        user.id
+    )
+    .execute(&mut *tx)
+    .await?;
+    let token = opaque_token::generate();
+    sqlx::query!(
+        r#"
+        INSERT INTO password_reset_tokens (user_id, token_hash, expires_at)
+        VALUES ($1, $2, $3)
+        "#,
+        user.id,
+        opaque_token::hash(&token),
+        Utc::now() + Duration::seconds(config.security.password_reset_ttl_secs)
+    )
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    mailer
+        .send(&password_reset_email(config, &user, &token))
+        .await
+        .map_err(|e| AppError::Internal(format!("failed to send password reset email to user {}: {}", user.id, e)))
+}
+
+// 재설정 링크와 토큰이 담긴 메일을 만듭니다.
+fn password_reset_email(config: &Config, user: &User, token: &str) -> Email {
+    let ttl_minutes = (config.security.password_reset_ttl_secs + 59) / 60;
+    Email {
+        to: user.email.clone(),
+        subject: "Reset your password".to_string(),
+        body: format!(
+            "Hi {},\n\n\
+             Someone asked to reset the password for your account. To choose a new password, open the link below:\n\n\
+             {}?token={}\n\n\
+             Or submit this code to POST /api/auth/reset-password: {}\n\n\
+             The link expires in {} minute(s) and can only be used once.\n\
+             If you did not ask for this, you can ignore this email and your password will stay the same.\n",
+            user.username, config.mail.reset_password_url, token, token, ttl_minutes
+        ),
+    }
+}
+
+// 이메일로 사용자를 조회합니다. 대소문자와 앞뒤 공백은 구분하지 않습니다.
+// 정규화하기 전에 가입하여 대소문자만 다른 계정이 여럿이면 먼저 가입한 계정을 반환합니다.
+async fn find_user_by_email(db: &PgPool, email: &str) -> Result<Option<User>, AppError> {
+    let user = sqlx::query_as!(
+        User,
+        r#"
+        SELECT id, username, email, password_hash, token_version, role AS "role: Role", email_verified_at, created_at, updated_at
+        FROM users WHERE LOWER(email) = $1
+        ORDER BY created_at, id
+        LIMIT 1
+        "#,
+        normalize_email(email)
+    )
+    .fetch_optional(db)
+    .await?;
+
+    Ok(user)
+}
+
+// 클레임에서 사용자 ID와 토큰 ID를 꺼냅니다.
+fn parse_subject(claims: &Claims) -> Result<(Uuid, Uuid), AppError> {
+    let user_id = Uuid::parse_str(&claims.sub).map_err(|_| AppError::InvalidToken(TokenError::InvalidClaims))?;
+    let jti = Uuid::parse_str(&claims.jti).map_err(|_| AppError::InvalidToken(TokenError::InvalidClaims))?;
+    Ok((user_id, jti))
+}
+
+// 다른 사용자가 같은 이름(대소문자 구분 없음)을 이미 사용하고 있는지 확인합니다.
+// except_user_id를 지정하면 해당 사용자는 제외합니다 (이름을 바꾸는 사용자 자신).
+pub(crate) async fn username_taken(db: &PgPool, username: &str, except_user_id: Option<Uuid>) -> Result<bool, AppError> {
+    let taken = sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM users
+            WHERE LOWER(username) = LOWER($1) AND ($2::UUID IS NULL OR id <> $2)
+        ) AS "taken!"
+        "#,
+        username.trim(),
+        except_user_id
+    )
+    .fetch_one(db)
+    .await?;
+    Ok(taken)
 }
@@ -1,178 +1,425 @@
 // comment_service.rs
 // 댓글 관련 비즈니스 로직을 처리하는 서비스입니다.
 // 댓글의 CRUD 작업과 대댓글 기능을 처리합니다.
+// 조회 결과에는 users 테이블과 LEFT JOIN한 작성자 요약 정보가 포함됩니다.
+
+use std::sync::Arc;
 
 use sqlx::PgPool;
 use uuid::Uuid;
-use crate::models::comment::{Comment, CreateCommentDto, UpdateCommentDto};
+use crate::config::Config;
+use crate::errors::AppError;
+use crate::models::comment::{
+    CommentTree, CommentTreeParams, CommentTreeRow, CommentView, CommentViewRow, CreateCommentDto, UpdateCommentDto,
+};
+use crate::models::pagination::{Cursor, CursorDirection, CursorPage, CursorParams, Page, PageParams};
+use crate::models::moderation::ModeratorAction;
+use crate::models::user::Actor;
+use crate::policy::{Action, CommentPolicy, CommentResource, Decision, Policy};
+use crate::services::moderation_service::record_action;
+use crate::services::MutationOutcome;
 
 // CommentService는 댓글 관련 기능을 제공하는 서비스 구조체입니다.
 pub struct CommentService {
-    db: PgPool,  // 데이터베이스 연결 풀
+    db: PgPool,           // 데이터베이스 연결 풀
+    config: Arc<Config>,  // 애플리케이션 설정 (대댓글 최대 깊이)
+    policy: CommentPolicy,  // 수정/삭제 권한 정책 (게시판 설정 반영)
 }
 
 impl CommentService {
     // 새로운 CommentService 인스턴스를 생성합니다.
-    pub fn new(db: PgPool) -> Self {
-        Self { db }
+    pub fn new(db: PgPool, config: Arc<Config>) -> Self {
+        let policy = CommentPolicy::new(&config.board);
+        Self { db, config, policy }
     }
 
     // 새 댓글을 생성합니다.
+    // 게시글이 없으면 NotFound를 반환합니다.
+    // 대댓글인 경우 부모 댓글이 같은 게시글에 있어야 하며, 설정된 최대 깊이를 넘을 수 없습니다.
+    // 부모 댓글은 항상 먼저 존재해야 하고 parent_id는 수정할 수 없으므로 순환 참조는 생길 수 없습니다.
     pub async fn create_comment(
         &self,
         post_id: Uuid,
         author_id: Uuid,
         dto: CreateCommentDto,
-    ) -> Result<Comment, sqlx::Error> {
-        // 댓글 저장
+    ) -> Result<CommentView, AppError> {
+        let post_exists = sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM posts WHERE id = $1) AS "exists!""#,
+            post_id
+        )
+        .fetch_one(&self.db)
+        .await?;
+        if !post_exists {
+            return Err(AppError::NotFound("Post not found".to_string()));
+        }
+
+        let depth = match dto.parent_id {
+            None => 1,
+            Some(parent_id) => self.reply_depth(post_id, parent_id).await?,
+        };
+
+        // 댓글을 저장하고 같은 쿼리에서 작성자 정보를 함께 조회합니다.
+        // 검사 이후 게시글이나 부모 댓글이 삭제되면 FK 위반이 발생하며, NotFound로 응답합니다.
         let comment = sqlx::query_as!(
-            Comment,
+            CommentViewRow,
             r#"
-            INSERT INTO comments (content, post_id, author_id, parent_id)
-            VALUES ($1, $2, $3, $4)
-            RETURNING id, content, post_id, author_id, parent_id, created_at, updated_at
+            WITH c AS (
+                INSERT INTO comments (content, post_id, author_id, parent_id, depth)
+                VALUES ($1, $2, $3, $4, $5)
+                RETURNING id, content, post_id, author_id, parent_id, depth, created_at, updated_at
+            )
+            SELECT c.id AS "id!", c.content AS "content!", c.post_id AS "post_id!",
+                   c.author_id AS "author_id!", u.username AS "author_username?",
+                   (SELECT COUNT(*) FROM comments r WHERE r.parent_id = c.id) AS "reply_count!",
+                   c.parent_id, c.depth AS "depth!", c.created_at AS "created_at!", c.updated_at AS "updated_at!"
+            FROM c
+            LEFT JOIN users u ON u.id = c.author_id
             "#,
             dto.content,
             post_id,
             author_id,
-            dto.parent_id
+            dto.parent_id,
+            depth
         )
         .fetch_one(&self.db)
         .await?;
 
-        Ok(comment)
+        Ok(comment.into())
+    }
+
+    // 부모 댓글을 검증하고 새 대댓글의 깊이를 계산합니다.
+    async fn reply_depth(&self, post_id: Uuid, parent_id: Uuid) -> Result<i32, AppError> {
+        let parent = sqlx::query!("SELECT post_id, depth FROM comments WHERE id = $1", parent_id)
+            .fetch_optional(&self.db)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Parent comment not found".to_string()))?;
+
+        if parent.post_id != post_id {
+            return Err(AppError::BadRequest(
+                "Parent comment belongs to a different post".to_string(),
+            ));
+        }
+
+        let max_depth = self.config.comments.max_depth;
+        if parent.depth >= max_depth {
+            return Err(AppError::BadRequest(format!(
+                "Replies cannot be nested more than {} levels deep",
+                max_depth
+            )));
+        }
+
+        Ok(parent.depth + 1)
     }
 
     // 특정 게시글의 댓글 목록을 조회합니다.
+    // 전체 댓글 수를 함께 조회하여 페이지 정보를 계산합니다.
     pub async fn get_post_comments(
         &self,
         post_id: Uuid,
-        page: i64,
-        per_page: i64,
-    ) -> Result<Vec<Comment>, sqlx::Error> {
+        params: PageParams,
+    ) -> Result<Page<CommentView>, AppError> {
         // 페이지네이션 적용하여 댓글 조회
-        let offset = (page - 1) * per_page;
         let comments = sqlx::query_as!(
-            Comment,
+            CommentViewRow,
             r#"
-            SELECT id, content, post_id, author_id, parent_id, created_at, updated_at
-            FROM comments
-            WHERE post_id = $1
-            ORDER BY created_at DESC
+            SELECT c.id, c.content, c.post_id, c.author_id, u.username AS "author_username?",
+                   (SELECT COUNT(*) FROM comments r WHERE r.parent_id = c.id) AS "reply_count!",
+                   c.parent_id, c.depth, c.created_at, c.updated_at
+            FROM comments c
+            LEFT JOIN users u ON u.id = c.author_id
+            WHERE c.post_id = $1
+            ORDER BY c.created_at DESC, c.id DESC
             LIMIT $2 OFFSET $3
             "#,
             post_id,
-            per_page,
-            offset
+            params.per_page,
+            params.offset()
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        let total = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM comments WHERE post_id = $1"#,
+            post_id
+        )
+        .fetch_one(&self.db)
+        .await?;
+
+        let comments = comments.into_iter().map(CommentView::from).collect();
+        Ok(Page::new(comments, total, params).with_next_cursor(comment_cursor))
+    }
+
+    // 특정 게시글의 댓글 목록을 커서 기준으로 조회합니다.
+    // created_at 조건은 idx_comments_created_at 인덱스 범위 검색으로 처리되고,
+    // 같은 시각의 댓글은 id로 순서를 정합니다.
+    pub async fn get_post_comments_by_cursor(
+        &self,
+        post_id: Uuid,
+        params: CursorParams,
+    ) -> Result<CursorPage<CommentView>, AppError> {
+        let comments = match params.direction {
+            CursorDirection::After(cursor) => {
+                sqlx::query_as!(
+                    CommentViewRow,
+                    r#"
+                    SELECT c.id, c.content, c.post_id, c.author_id, u.username AS "author_username?",
+                           (SELECT COUNT(*) FROM comments r WHERE r.parent_id = c.id) AS "reply_count!",
+                           c.parent_id, c.depth, c.created_at, c.updated_at
+                    FROM comments c
+                    LEFT JOIN users u ON u.id = c.author_id
+                    WHERE c.post_id = $1
+                      AND c.created_at <= $2 AND (c.created_at < $2 OR c.id < $3)
+                    ORDER BY c.created_at DESC, c.id DESC
+                    LIMIT $4
+                    "#,
+                    post_id,
+                    cursor.created_at,
+                    cursor.id,
+                    params.fetch_limit()
+                )
+                .fetch_all(&self.db)
+                .await?
+            }
+            CursorDirection::Before(cursor) => {
+                sqlx::query_as!(
+                    CommentViewRow,
+                    r#"
+                    SELECT c.id, c.content, c.post_id, c.author_id, u.username AS "author_username?",
+                           (SELECT COUNT(*) FROM comments r WHERE r.parent_id = c.id) AS "reply_count!",
+                           c.parent_id, c.depth, c.created_at, c.updated_at
+                    FROM comments c
+                    LEFT JOIN users u ON u.id = c.author_id
+                    WHERE c.post_id = $1
+                      AND c.created_at >= $2 AND (c.created_at > $2 OR c.id > $3)
+                    ORDER BY c.created_at ASC, c.id ASC
+                    LIMIT $4
+                    "#,
+                    post_id,
+                    cursor.created_at,
+                    cursor.id,
+                    params.fetch_limit()
+                )
+                .fetch_all(&self.db)
+                .await?
+            }
+        };
+
+        let comments = comments.into_iter().map(CommentView::from).collect();
+        Ok(CursorPage::from_rows(comments, params, comment_cursor))
+    }
+
+    // 게시글의 댓글을 트리 형태로 조회합니다.
+    // WITH RECURSIVE로 한 번의 쿼리에서 최대 깊이까지 내려가며,
+    // 같은 부모 아래에서는 작성 순서대로 limit개까지만 포함합니다.
+    pub async fn get_comment_tree(
+        &self,
+        post_id: Uuid,
+        params: CommentTreeParams,
+    ) -> Result<CommentTree, AppError> {
+        let rows = sqlx::query_as!(
+            CommentTreeRow,
+            r#"
+            WITH RECURSIVE ranked AS (
+                SELECT id, content, post_id, author_id, parent_id, created_at, updated_at,
+                       ROW_NUMBER() OVER (PARTITION BY parent_id ORDER BY created_at ASC, id ASC) AS position,
+                       COUNT(*) OVER (PARTITION BY parent_id) AS sibling_count
+                FROM comments
+                WHERE post_id = $1
+            ),
+            tree AS (
+                SELECT ranked.*, 1 AS depth
+                FROM ranked
+                WHERE parent_id IS NULL AND position <= $3
+                UNION ALL
+                SELECT ranked.*, tree.depth + 1
+                FROM ranked
+                JOIN tree ON ranked.parent_id = tree.id
+                WHERE tree.depth < $2 AND ranked.position <= $3
+            )
+            SELECT c.id AS "id!", c.content AS "content!", c.post_id AS "post_id!",
+                   c.author_id AS "author_id!", u.username AS "author_username?",
+                   (SELECT COUNT(*) FROM comments r WHERE r.parent_id = c.id) AS "reply_count!",
+                   c.parent_id, c.created_at AS "created_at!", c.updated_at AS "updated_at!",
+                   c.depth AS "depth!", c.sibling_count AS "sibling_count!"
+            FROM tree c
+            LEFT JOIN users u ON u.id = c.author_id
+            ORDER BY c.depth, c.position
+            "#,
+            post_id,
+            params.max_depth,
+            params.limit
         )
         .fetch_all(&self.db)
         .await?;
 
-        Ok(comments)
+        Ok(CommentTree::from_rows(rows, params))
     }
 
     // 댓글을 조회합니다.
-    pub async fn get_comment(&self, comment_id: Uuid) -> Result<Option<Comment>, sqlx::Error> {
+    pub async fn get_comment(&self, comment_id: Uuid) -> Result<Option<CommentView>, AppError> {
         let comment = sqlx::query_as!(
-            Comment,
+            CommentViewRow,
             r#"
-            SELECT id, content, post_id, author_id, parent_id, created_at, updated_at
-            FROM comments
-            WHERE id = $1
+            SELECT c.id, c.content, c.post_id, c.author_id, u.username AS "author_username?",
+                   (SELECT COUNT(*) FROM comments r WHERE r.parent_id = c.id) AS "reply_count!",
+                   c.parent_id, c.depth, c.created_at, c.updated_at
+            FROM comments c
+            LEFT JOIN users u ON u.id = c.author_id
+            WHERE c.id = $1
             "#,
             comment_id
         )
         .fetch_optional(&self.db)
         .await?;
 
-        Ok(comment)
+        Ok(comment.map(CommentView::from))
     }
 
     // 댓글을 수정합니다.
-    // 작성자만 수정할 수 있습니다.
+    // 권한은 CommentPolicy로 확인하며, 댓글이 없으면 NotFound, 권한이 없으면 Forbidden을 반환합니다.
+    // 모더레이터 권한으로 다른 사용자의 댓글을 수정하면 모더레이터 작업으로 기록합니다.
     pub async fn update_comment(
         &self,
         comment_id: Uuid,
-        author_id: Uuid,
+        actor: &Actor,
         dto: UpdateCommentDto,
-    ) -> Result<Option<Comment>, sqlx::Error> {
-        // 댓글 존재 여부와 작성자 확인
-        let comment = match sqlx::query_as!(
-            Comment,
-            "SELECT * FROM comments WHERE id = $1",
-            comment_id
-        )
-        .fetch_optional(&self.db)
-        .await?
-        {
-            Some(comment) if comment.author_id == author_id => comment,
-            _ => return Ok(None),
+    ) -> Result<MutationOutcome<CommentView>, AppError> {
+        let mut tx = self.db.begin().await?;
+
+        // 댓글을 잠근 뒤 권한을 확인하여, 확인과 수정 사이에 댓글이 바뀌지 않도록 합니다.
+        let Some(comment) = lock_comment(&mut tx, comment_id).await? else {
+            return Ok(MutationOutcome::NotFound);
         };
+        let decision = self.policy.can(actor, Action::Update, &comment);
+        if decision == Decision::Deny {
+            return Ok(MutationOutcome::Forbidden);
+        }
 
         // 댓글 수정
         let updated = sqlx::query_as!(
-            Comment,
+            CommentViewRow,
             r#"
-            UPDATE comments
-            SET content = $1, updated_at = CURRENT_TIMESTAMP
-            WHERE id = $2
-            RETURNING id, content, post_id, author_id, parent_id, created_at, updated_at
+            WITH c AS (
+                UPDATE comments
+                SET content = $1, updated_at = CURRENT_TIMESTAMP
+                WHERE id = $2
+                RETURNING id, content, post_id, author_id, parent_id, depth, created_at, updated_at
+            )
+            SELECT c.id AS "id!", c.content AS "content!", c.post_id AS "post_id!",
+                   c.author_id AS "author_id!", u.username AS "author_username?",
+                   (SELECT COUNT(*) FROM comments r WHERE r.parent_id = c.id) AS "reply_count!",
+                   c.parent_id, c.depth AS "depth!", c.created_at AS "created_at!", c.updated_at AS "updated_at!"
+            FROM c
+            LEFT JOIN users u ON u.id = c.author_id
             "#,
             dto.content,
             comment_id
         )
-        .fetch_one(&self.db)
+        .fetch_one(&mut *tx)
         .await?;
 
-        Ok(Some(updated))
+        if decision.is_moderation() {
+            record_action(&mut tx, actor.id, ModeratorAction::UpdateComment, comment_id, comment.author_id).await?;
+        }
+        tx.commit().await?;
+
+        Ok(MutationOutcome::Done(updated.into()))
     }
 
     // 댓글을 삭제합니다.
-    // 작성자만 삭제할 수 있습니다.
+    // 권한은 CommentPolicy로 확인하며, 댓글이 없으면 NotFound, 권한이 없으면 Forbidden을 반환합니다.
+    // 게시판 설정에 따라 게시글 작성자도 자기 게시글에 달린 댓글을 삭제할 수 있습니다.
+    // 모더레이터 권한으로 다른 사용자의 댓글을 삭제하면 모더레이터 작업으로 기록합니다.
     pub async fn delete_comment(
         &self,
         comment_id: Uuid,
-        author_id: Uuid,
-    ) -> Result<bool, sqlx::Error> {
-        let result = sqlx::query!(
-            r#"
-            DELETE FROM comments
-            WHERE id = $1 AND author_id = $2
-            "#,
-            comment_id,
-            author_id
-        )
-        .execute(&self.db)
-        .await?;
+        actor: &Actor,
+    ) -> Result<MutationOutcome<()>, AppError> {
+        let mut tx = self.db.begin().await?;
+
+        let Some(comment) = lock_comment(&mut tx, comment_id).await? else {
+            return Ok(MutationOutcome::NotFound);
+        };
+        let decision = self.policy.can(actor, Action::Delete, &comment);
+        if decision == Decision::Deny {
+            return Ok(MutationOutcome::Forbidden);
+        }
+
+        sqlx::query!("DELETE FROM comments WHERE id = $1", comment_id)
+            .execute(&mut *tx)
+            .await?;
 
-        Ok(result.rows_affected() > 0)
+        if decision.is_moderation() {
+            record_action(&mut tx, actor.id, ModeratorAction::DeleteComment, comment_id, comment.author_id).await?;
+        }
+        tx.commit().await?;
+
+        Ok(MutationOutcome::Done(()))
     }
 
     // 대댓글을 조회합니다.
+    // 전체 대댓글 수를 함께 조회하여 페이지 정보를 계산합니다.
     pub async fn get_replies(
         &self,
         parent_id: Uuid,
-        page: i64,
-        per_page: i64,
-    ) -> Result<Vec<Comment>, sqlx::Error> {
+        params: PageParams,
+    ) -> Result<Page<CommentView>, AppError> {
         // 페이지네이션 적용하여 대댓글 조회
-        let offset = (page - 1) * per_page;
         let replies = sqlx::query_as!(
-            Comment,
+            CommentViewRow,
             r#"
-            SELECT id, content, post_id, author_id, parent_id, created_at, updated_at
-            FROM comments
-            WHERE parent_id = $1
-            ORDER BY created_at ASC
+            SELECT c.id, c.content, c.post_id, c.author_id, u.username AS "author_username?",
+                   (SELECT COUNT(*) FROM comments r WHERE r.parent_id = c.id) AS "reply_count!",
+                   c.parent_id, c.depth, c.created_at, c.updated_at
+            FROM comments c
+            LEFT JOIN users u ON u.id = c.author_id
+            WHERE c.parent_id = $1
+            ORDER BY c.created_at ASC, c.id ASC
             LIMIT $2 OFFSET $3
             "#,
             parent_id,
-            per_page,
-            offset
+            params.per_page,
+            params.offset()
         )
         .fetch_all(&self.db)
         .await?;
 
-        Ok(replies)
+        let total = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM comments WHERE parent_id = $1"#,
+            parent_id
+        )
+        .fetch_one(&self.db)
+        .await?;
+
+        let replies = replies.into_iter().map(CommentView::from).collect();
+        Ok(Page::new(replies, total, params))
     }
 }
+
+// 댓글의 정렬 위치를 나타내는 커서를 만듭니다.
+fn comment_cursor(comment: &CommentView) -> Cursor {
+    Cursor::new(comment.created_at, comment.id)
+}
+
+// 댓글을 잠그고 권한 판단에 필요한 정보(작성자, 게시글 작성자)를 조회합니다.
+// 댓글이 없으면 None을 반환합니다.
+async fn lock_comment(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    comment_id: Uuid,
+) -> Result<Option<CommentResource>, AppError> {
+    let comment = sqlx::query_as!(
+        CommentResource,
+        r#"
+        SELECT c.author_id, p.author_id AS post_author_id
+        FROM comments c
+        JOIN posts p ON p.id = c.post_id
+        WHERE c.id = $1
+        FOR UPDATE OF c
+        "#,
+        comment_id
+    )
+    .fetch_optional(&mut **tx)
+    .await?;
+
+    Ok(comment)
+}
@@ -0,0 +1,165 @@
+// email_verification_service.rs
+// 가입한 이메일 주소의 소유를 확인하는 서비스입니다.
+// 가입하면 인증 토큰을 메일로 보내고, 사용자가 토큰을 제출하면 users.email_verified_at을 기록합니다.
+// 이메일 인증을 마치지 않은 사용자는 게시글을 작성할 수 없습니다 (PostService::create_post).
+//
+// 인증 토큰은 리프레시 토큰과 같이 원문 대신 해시만 저장하며, 한 번만 사용할 수 있습니다.
+// 토큰을 다시 발급하면 이전에 보낸 토큰은 더 이상 사용할 수 없습니다.
+
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::errors::AppError;
+use crate::mail::{Email, Mailer};
+use crate::models::user::{Role, User};
+use crate::services::opaque_token;
+
+// EmailVerificationService는 인증 메일 발송과 이메일 인증 기능을 제공합니다.
+pub struct EmailVerificationService {
+    db: PgPool,               // 데이터베이스 연결 풀
+    config: Arc<Config>,      // 애플리케이션 설정 (토큰 유효 시간, 인증 링크)
+    mailer: Arc<dyn Mailer>,  // 인증 메일 발송에 사용할 Mailer
+}
+
+impl EmailVerificationService {
+    // 새로운 EmailVerificationService 인스턴스를 생성합니다.
+    pub fn new(db: PgPool, config: Arc<Config>, mailer: Arc<dyn Mailer>) -> Self {
+        Self { db, config, mailer }
+    }
+
+    // 새 인증 토큰을 발급하여 사용자의 현재 이메일로 보냅니다.
+    // 아직 사용하지 않은 이전 토큰은 모두 사용 처리하여, 가장 최근에 보낸 토큰만 유효하게 합니다.
+    pub async fn send_verification(&self, user: &User) -> Result<(), AppError> {
+        let token = opaque_token::generate();
+        let expires_at = Utc::now() + Duration::seconds(self.config.security.email_verification_ttl_secs);
+
+        let mut tx = self.db.begin().await?;
+        sqlx::query!(
+            r#"
+            UPDATE email_verification_tokens
+            SET used_at = CURRENT_TIMESTAMP
+            WHERE user_id = $1 AND used_at IS NULL
+            "#,
+            user.id
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            r#"
+            INSERT INTO email_verification_tokens (user_id, email, token_hash, expires_at)
+            VALUES ($1, $2, $3, $4)
+            "#,
+            user.id,
+            user.email,
+            opaque_token::hash(&token),
+            expires_at
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        self.mailer.send(&self.verification_email(user, &token)).await?;
+        Ok(())
+    }
+
+    // 인증 메일을 다시 보냅니다.
+    // 이미 인증된 사용자는 Conflict, 재발송 간격(security.email_verification_resend_secs)이 지나지 않았으면
+    // RateLimited를 반환합니다.
+    pub async fn resend_verification(&self, user_id: Uuid) -> Result<(), AppError> {
+        let user = sqlx::query_as!(
+            User,
+            r#"
+            SELECT id, username, email, password_hash, token_version, role AS "role: Role", email_verified_at, created_at, updated_at
+            FROM users WHERE id = $1
+            "#,
+            user_id
+        )
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        if user.email_verified_at.is_some() {
+            return Err(AppError::Conflict("Email address is already verified".to_string()));
+        }
+
+        let last_sent_at = sqlx::query_scalar!(
+            "SELECT MAX(created_at) FROM email_verification_tokens WHERE user_id = $1",
+            user_id
+        )
+        .fetch_one(&self.db)
+        .await?;
+        if let Some(last_sent_at) = last_sent_at {
+            let wait = Duration::seconds(self.config.security.email_verification_resend_secs) - (Utc::now() - last_sent_at);
+            if wait > Duration::zero() {
+                return Err(AppError::RateLimited {
+                    message: "A verification email was sent recently, please try again later".to_string(),
+                    retry_after: Some(wait.num_seconds().max(1) as u64),
+                });
+            }
+        }
+
+        self.send_verification(&user).await
+    }
+
+    // 인증 토큰을 확인하고 사용자의 이메일을 인증된 것으로 기록합니다.
+    // 토큰이 없거나, 만료되었거나, 이미 사용되었거나, 발급 후 이메일이 바뀐 경우 BadRequest를 반환합니다.
+    pub async fn verify_email(&self, token: &str) -> Result<User, AppError> {
+        let mut tx = self.db.begin().await?;
+
+        // 토큰 사용 처리와 유효성 확인을 한 쿼리로 수행하여, 같은 토큰이 동시에 두 번 사용되지 않게 합니다.
+        let user_id = sqlx::query_scalar!(
+            r#"
+            UPDATE email_verification_tokens t
+            SET used_at = CURRENT_TIMESTAMP
+            FROM users u
+            WHERE t.token_hash = $1
+              AND t.used_at IS NULL
+              AND t.expires_at > CURRENT_TIMESTAMP
+              AND u.id = t.user_id
+              AND u.email = t.email
+            RETURNING t.user_id
+            "#,
+            opaque_token::hash(token)
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::BadRequest("Invalid or expired verification token".to_string()))?;
+
+        let user = sqlx::query_as!(
+            User,
+            r#"
+            UPDATE users
+            SET email_verified_at = COALESCE(email_verified_at, CURRENT_TIMESTAMP), updated_at = CURRENT_TIMESTAMP
+            WHERE id = $1
+            RETURNING id, username, email, password_hash, token_version, role AS "role: Role", email_verified_at, created_at, updated_at
+            "#,
+            user_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        log::info!("user {} verified email address", user.id);
+        Ok(user)
+    }
+
+    // 인증 링크와 토큰이 담긴 메일을 만듭니다.
+    fn verification_email(&self, user: &User, token: &str) -> Email {
+        let ttl_hours = (self.config.security.email_verification_ttl_secs + 3599) / 3600;
+        Email {
+            to: user.email.clone(),
+            subject: "Verify your email address".to_string(),
+            body: format!(
+                "Hi {},\n\n\
+                 Please confirm your email address by opening the link below:\n\n\
+                 {}?token={}\n\n\
+                 Or submit this code to POST /api/auth/verify-email: {}\n\n\
+                 The link expires in {} hour(s). If you did not sign up, you can ignore this email.\n",
+                user.username, self.config.mail.verify_email_url, token, token, ttl_hours
+            ),
+        }
+    }
+}
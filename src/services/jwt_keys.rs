@@ -0,0 +1,258 @@
+// jwt_keys.rs
+// 액세스 토큰의 서명 키와 검증 키를 관리합니다.
+// jwt.keys가 비어 있으면 jwt.secret으로 HS256 서명하고, 설정되어 있으면 PEM 파일에서 읽은
+// RS256/ES256/EdDSA 키로 서명하며 토큰 헤더에 kid를 기록합니다.
+// 검증할 때는 헤더의 kid로 키를 찾으므로, 키를 교체하는 동안 여러 키를 함께 허용할 수 있습니다.
+// 공개 키는 JWKS 형식으로 제공되어 다른 서비스가 비밀 값 없이 토큰을 검증할 수 있습니다.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use jsonwebtoken::jwk::{
+    AlgorithmParameters, CommonParameters, EllipticCurve, EllipticCurveKeyParameters, EllipticCurveKeyType,
+    Jwk, JwkSet, KeyAlgorithm, OctetKeyPairParameters, OctetKeyPairType, PublicKeyUse, RSAKeyParameters,
+    RSAKeyType,
+};
+use jsonwebtoken::{crypto, Algorithm, DecodingKey, EncodingKey, Header};
+use simple_asn1::ASN1Block;
+use std::collections::HashMap;
+use std::fs;
+
+use crate::config::{ConfigError, JwtConfig, JwtKeyConfig};
+
+// 새 토큰 서명에 사용하는 키입니다.
+struct SigningKey {
+    kid: Option<String>,  // HS256 공유 비밀 키는 kid 없이 서명합니다
+    algorithm: Algorithm,
+    key: EncodingKey,
+}
+
+// 토큰 검증에 사용하는 키입니다.
+struct VerifyingKey {
+    algorithm: Algorithm,
+    key: DecodingKey,
+}
+
+// JwtKeys는 서명 키 하나와 kid별 검증 키, 그리고 공개할 JWKS를 가지고 있습니다.
+pub struct JwtKeys {
+    signing: SigningKey,
+    verifying: HashMap<Option<String>, VerifyingKey>,
+    jwks: JwkSet,
+}
+
+impl JwtKeys {
+    // 설정에서 키를 읽어옵니다.
+    // 키 파일을 읽을 수 없거나, 개인 키와 공개 키가 짝이 맞지 않으면 에러를 반환합니다.
+    pub fn from_config(config: &JwtConfig) -> Result<Self, ConfigError> {
+        if config.keys.is_empty() {
+            return Ok(Self::from_secret(&config.secret));
+        }
+
+        let mut signing = None;
+        let mut verifying = HashMap::new();
+        let mut jwks = Vec::new();
+
+        for key_config in &config.keys {
+            let key_error = |message: String| ConfigError::Key {
+                kid: key_config.kid.clone(),
+                message,
+            };
+
+            let public_pem = read_pem_file(&key_config.public_key_file).map_err(key_error)?;
+            let decoding_key = decoding_key(key_config.algorithm, &public_pem).map_err(key_error)?;
+            jwks.push(public_jwk(key_config, &public_pem).map_err(key_error)?);
+
+            if key_config.kid == config.signing_kid {
+                if let Some(path) = &key_config.private_key_file {
+                    let private_pem = read_pem_file(path).map_err(key_error)?;
+                    let encoding_key = encoding_key(key_config.algorithm, &private_pem).map_err(key_error)?;
+                    check_key_pair(key_config.algorithm, &encoding_key, &decoding_key).map_err(key_error)?;
+                    signing = Some(SigningKey {
+                        kid: Some(key_config.kid.clone()),
+                        algorithm: key_config.algorithm,
+                        key: encoding_key,
+                    });
+                }
+            }
+
+            verifying.insert(
+                Some(key_config.kid.clone()),
+                VerifyingKey {
+                    algorithm: key_config.algorithm,
+                    key: decoding_key,
+                },
+            );
+        }
+
+        let signing = signing.ok_or_else(|| ConfigError::Key {
+            kid: config.signing_kid.clone(),
+            message: "jwt.signing_kid must refer to a key with a private_key_file".to_string(),
+        })?;
+
+        Ok(Self {
+            signing,
+            verifying,
+            jwks: JwkSet { keys: jwks },
+        })
+    }
+
+    // 공유 비밀 키 하나로 HS256 서명과 검증을 모두 수행합니다.
+    // 비밀 키는 공개할 수 없으므로 JWKS는 비어 있습니다.
+    fn from_secret(secret: &str) -> Self {
+        let verifying = VerifyingKey {
+            algorithm: Algorithm::HS256,
+            key: DecodingKey::from_secret(secret.as_bytes()),
+        };
+
+        Self {
+            signing: SigningKey {
+                kid: None,
+                algorithm: Algorithm::HS256,
+                key: EncodingKey::from_secret(secret.as_bytes()),
+            },
+            verifying: HashMap::from([(None, verifying)]),
+            jwks: JwkSet { keys: Vec::new() },
+        }
+    }
+
+    // 새 토큰에 사용할 헤더를 반환합니다 (알고리즘과 kid 포함).
+    pub fn header(&self) -> Header {
+        let mut header = Header::new(self.signing.algorithm);
+        header.kid = self.signing.kid.clone();
+        header
+    }
+
+    // 새 토큰 서명에 사용할 키를 반환합니다.
+    pub fn encoding_key(&self) -> &EncodingKey {
+        &self.signing.key
+    }
+
+    // 토큰 헤더의 kid에 해당하는 검증 키와 그 키의 알고리즘을 찾습니다.
+    // 헤더의 alg가 아닌 키에 설정된 알고리즘으로 검증해야 알고리즘 혼동 공격을 막을 수 있습니다.
+    pub fn verifying_key(&self, header: &Header) -> Option<(Algorithm, &DecodingKey)> {
+        self.verifying
+            .get(&header.kid)
+            .map(|verifying| (verifying.algorithm, &verifying.key))
+    }
+
+    // 다른 서비스에 공개할 검증 키 목록입니다.
+    pub fn jwks(&self) -> &JwkSet {
+        &self.jwks
+    }
+}
+
+// PEM 파일을 읽습니다.
+fn read_pem_file(path: &str) -> Result<Vec<u8>, String> {
+    fs::read(path).map_err(|e| format!("{}: {}", path, e))
+}
+
+fn encoding_key(algorithm: Algorithm, pem: &[u8]) -> Result<EncodingKey, String> {
+    let key = match algorithm {
+        Algorithm::RS256 => EncodingKey::from_rsa_pem(pem),
+        Algorithm::ES256 => EncodingKey::from_ec_pem(pem),
+        Algorithm::EdDSA => EncodingKey::from_ed_pem(pem),
+        other => return Err(format!("unsupported algorithm {:?}", other)),
+    };
+    key.map_err(|e| format!("invalid private key: {}", e))
+}
+
+fn decoding_key(algorithm: Algorithm, pem: &[u8]) -> Result<DecodingKey, String> {
+    let key = match algorithm {
+        Algorithm::RS256 => DecodingKey::from_rsa_pem(pem),
+        Algorithm::ES256 => DecodingKey::from_ec_pem(pem),
+        Algorithm::EdDSA => DecodingKey::from_ed_pem(pem),
+        other => return Err(format!("unsupported algorithm {:?}", other)),
+    };
+    key.map_err(|e| format!("invalid public key: {}", e))
+}
+
+// 개인 키로 서명한 값을 공개 키로 검증하여 두 키가 같은 쌍인지 확인합니다.
+fn check_key_pair(algorithm: Algorithm, encoding_key: &EncodingKey, decoding_key: &DecodingKey) -> Result<(), String> {
+    const PROBE: &[u8] = b"jwt key pair check";
+
+    let signature = crypto::sign(PROBE, encoding_key, algorithm).map_err(|e| e.to_string())?;
+    match crypto::verify(&signature, PROBE, decoding_key, algorithm) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err("private key does not match public key".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+// 공개 키 PEM(SubjectPublicKeyInfo)에서 JWK를 만듭니다.
+fn public_jwk(key_config: &JwtKeyConfig, pem: &[u8]) -> Result<Jwk, String> {
+    let key_bits = subject_public_key(pem)?;
+
+    let (key_algorithm, algorithm) = match key_config.algorithm {
+        Algorithm::RS256 => (KeyAlgorithm::RS256, rsa_parameters(&key_bits)?),
+        Algorithm::ES256 => (KeyAlgorithm::ES256, p256_parameters(&key_bits)?),
+        Algorithm::EdDSA => (KeyAlgorithm::EdDSA, ed25519_parameters(&key_bits)?),
+        other => return Err(format!("unsupported algorithm {:?}", other)),
+    };
+
+    Ok(Jwk {
+        common: CommonParameters {
+            public_key_use: Some(PublicKeyUse::Signature),
+            key_algorithm: Some(key_algorithm),
+            key_id: Some(key_config.kid.clone()),
+            ..Default::default()
+        },
+        algorithm,
+    })
+}
+
+// SubjectPublicKeyInfo의 subjectPublicKey 비트열을 꺼냅니다.
+fn subject_public_key(pem: &[u8]) -> Result<Vec<u8>, String> {
+    let pem = pem::parse(pem).map_err(|e| format!("invalid PEM: {}", e))?;
+    if pem.tag() != "PUBLIC KEY" {
+        return Err(format!("expected a PUBLIC KEY PEM block, found {}", pem.tag()));
+    }
+
+    let blocks = simple_asn1::from_der(pem.contents()).map_err(|e| format!("invalid public key: {}", e))?;
+    match blocks.first() {
+        Some(ASN1Block::Sequence(_, items)) => match items.get(1) {
+            Some(ASN1Block::BitString(_, _, bits)) => Ok(bits.clone()),
+            _ => Err("invalid public key: missing subjectPublicKey".to_string()),
+        },
+        _ => Err("invalid public key: expected SubjectPublicKeyInfo".to_string()),
+    }
+}
+
+// RSAPublicKey ::= SEQUENCE { modulus INTEGER, publicExponent INTEGER }
+fn rsa_parameters(key_bits: &[u8]) -> Result<AlgorithmParameters, String> {
+    let blocks = simple_asn1::from_der(key_bits).map_err(|e| format!("invalid RSA public key: {}", e))?;
+    match blocks.first() {
+        Some(ASN1Block::Sequence(_, items)) => match items.as_slice() {
+            [ASN1Block::Integer(_, n), ASN1Block::Integer(_, e)] => Ok(AlgorithmParameters::RSA(RSAKeyParameters {
+                key_type: RSAKeyType::RSA,
+                n: URL_SAFE_NO_PAD.encode(n.to_bytes_be().1),
+                e: URL_SAFE_NO_PAD.encode(e.to_bytes_be().1),
+            })),
+            _ => Err("invalid RSA public key".to_string()),
+        },
+        _ => Err("invalid RSA public key".to_string()),
+    }
+}
+
+// 비압축 형식의 P-256 공개 키입니다: 0x04 || X (32바이트) || Y (32바이트)
+fn p256_parameters(key_bits: &[u8]) -> Result<AlgorithmParameters, String> {
+    match key_bits {
+        [0x04, point @ ..] if point.len() == 64 => {
+            Ok(AlgorithmParameters::EllipticCurve(EllipticCurveKeyParameters {
+                key_type: EllipticCurveKeyType::EC,
+                curve: EllipticCurve::P256,
+                x: URL_SAFE_NO_PAD.encode(&point[..32]),
+                y: URL_SAFE_NO_PAD.encode(&point[32..]),
+            }))
+        }
+        _ => Err("ES256 keys must be uncompressed P-256 public keys".to_string()),
+    }
+}
+
+fn ed25519_parameters(key_bits: &[u8]) -> Result<AlgorithmParameters, String> {
+    if key_bits.len() != 32 {
+        return Err("EdDSA keys must be Ed25519 public keys".to_string());
+    }
+    Ok(AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+        key_type: OctetKeyPairType::OctetKeyPair,
+        curve: EllipticCurve::Ed25519,
+        x: URL_SAFE_NO_PAD.encode(key_bits),
+    }))
+}
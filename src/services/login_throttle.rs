@@ -0,0 +1,296 @@
+// login_throttle.rs
+// 비밀번호 대입 공격을 막기 위해 로그인 실패를 계정과 클라이언트 IP별로 기록하고 제한합니다.
+//
+// - 허용된 횟수(free_attempts)를 넘겨 연속으로 실패하면, 다음 시도까지 기다려야 하는 시간이
+//   login_backoff_base_secs부터 실패할 때마다 두 배로 늘어납니다 (최대 login_backoff_max_secs).
+// - 연속 실패가 lockout_threshold에 도달하면 login_lockout_secs 동안 잠그고 auth_audit_log에 기록합니다.
+// - 마지막 실패 후 login_failure_window_secs가 지나면 실패 횟수를 처음부터 다시 셉니다.
+//
+// 계정은 가입 여부와 관계없이 정규화한 이메일(normalize_email)로 식별하므로, 제한 여부로 가입 여부가 드러나지 않습니다.
+// 로그인에 성공하면 계정의 실패 기록은 지우지만, 공격자가 자신의 계정으로 초기화할 수 없도록 IP 기록은 유지합니다.
+//
+// 동시에 보낸 요청이 모두 확인을 통과하여 제한보다 많이 시도하지 않도록, 비밀번호를 확인하기 전에
+// reserve로 행을 잠근 채 제한을 확인하고 시도를 실패로 미리 기록합니다.
+// 확인 결과에 따라 record_failure(실패), record_success(성공), cancel(2단계 인증으로 넘어감)로 마무리합니다.
+
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+use std::net::IpAddr;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::config::{Config, SecurityConfig};
+use crate::errors::AppError;
+use crate::models::user::normalize_email;
+
+// 실패 기록의 단위입니다.
+#[derive(Debug, Clone, Copy)]
+enum Scope {
+    Account,  // 이메일
+    Ip,       // 클라이언트 IP
+}
+
+impl Scope {
+    fn as_str(self) -> &'static str {
+        match self {
+            Scope::Account => "account",
+            Scope::Ip => "ip",
+        }
+    }
+
+    // (대기 없이 허용하는 실패 횟수, 잠그는 실패 횟수)
+    fn limits(self, config: &SecurityConfig) -> (i32, i32) {
+        match self {
+            Scope::Account => (config.login_account_free_attempts, config.login_account_lockout_threshold),
+            Scope::Ip => (config.login_ip_free_attempts, config.login_ip_lockout_threshold),
+        }
+    }
+
+    fn lock_event(self) -> &'static str {
+        match self {
+            Scope::Account => "account_locked",
+            Scope::Ip => "ip_locked",
+        }
+    }
+}
+
+// 연속 실패 횟수에 따라 다음 시도까지 기다려야 하는 시간(초)을 계산합니다.
+// free_attempts번까지는 기다리지 않으며, 그 이후로는 base_secs부터 두 배씩 늘어나 max_secs를 넘지 않습니다.
+pub fn backoff_secs(failed_count: i32, free_attempts: i32, base_secs: i64, max_secs: i64) -> i64 {
+    if failed_count <= free_attempts {
+        return 0;
+    }
+    let exponent = (failed_count - free_attempts - 1).min(32) as u32;
+    base_secs.saturating_mul(1i64 << exponent).min(max_secs)
+}
+
+// LoginThrottle은 로그인 실패 기록과 제한 여부 확인을 담당합니다.
+pub struct LoginThrottle {
+    db: PgPool,           // 데이터베이스 연결 풀
+    config: Arc<Config>,  // 애플리케이션 설정 (security.login_*)
+}
+
+impl LoginThrottle {
+    pub fn new(db: PgPool, config: Arc<Config>) -> Self {
+        Self { db, config }
+    }
+
+    // 로그인 시도를 예약합니다.
+    // 계정이나 IP가 잠겨 있거나 대기 시간이 지나지 않았으면 아무것도 기록하지 않고 RateLimited를 반환합니다.
+    // 그렇지 않으면 시도를 실패로 미리 세고, 잠금 기준에 도달하면 바로 잠가 동시에 들어온 다음 요청을 막습니다.
+    // 비밀번호를 확인하기 전에 호출하여, 제한된 동안에는 비밀번호 검증을 수행하지 않습니다.
+    pub async fn reserve(&self, email: &str, client_ip: Option<IpAddr>) -> Result<Attempt, AppError> {
+        let security = &self.config.security;
+        let window = Duration::seconds(security.login_failure_window_secs);
+        let subjects = subjects(email, client_ip);
+        let mut tx = self.db.begin().await?;
+
+        let mut wait = Duration::zero();
+        for (scope, subject) in &subjects {
+            // 처음 시도하는 대상도 잠글 수 있도록 빈 기록을 먼저 만듭니다. 제한되면 롤백되어 남지 않습니다.
+            sqlx::query!(
+                r#"
+                INSERT INTO login_throttles (scope, subject, failed_count, last_failed_at)
+                VALUES ($1, $2, 0, CURRENT_TIMESTAMP)
+                ON CONFLICT (scope, subject) DO NOTHING
+                "#,
+                scope.as_str(),
+                subject
+            )
+            .execute(&mut *tx)
+            .await?;
+            let row = sqlx::query!(
+                r#"
+                SELECT failed_count, last_failed_at, locked_until
+                FROM login_throttles
+                WHERE scope = $1 AND subject = $2
+                FOR UPDATE
+                "#,
+                scope.as_str(),
+                subject
+            )
+            .fetch_one(&mut *tx)
+            .await?;
+            // 다른 요청이 행을 잠그고 있었다면 기다린 뒤의 시각을 기준으로 계산합니다.
+            let now = Utc::now();
+
+            if let Some(locked_until) = row.locked_until {
+                wait = wait.max(locked_until - now);
+            }
+            // 방금 만든 빈 기록은 실패가 없으므로 대기 시간을 계산하지 않습니다.
+            if row.failed_count > 0 && row.last_failed_at + window > now {
+                let (free_attempts, _) = scope.limits(security);
+                let backoff = backoff_secs(
+                    row.failed_count,
+                    free_attempts,
+                    security.login_backoff_base_secs,
+                    security.login_backoff_max_secs,
+                );
+                wait = wait.max(row.last_failed_at + Duration::seconds(backoff) - now);
+            }
+        }
+
+        if wait > Duration::zero() {
+            tx.rollback().await?;
+            return Err(AppError::RateLimited {
+                message: "Too many failed login attempts, please try again later".to_string(),
+                retry_after: Some(((wait.num_milliseconds() + 999) / 1000).max(1) as u64),
+            });
+        }
+
+        let mut reserved = Vec::with_capacity(subjects.len());
+        for (scope, subject) in subjects {
+            // 마지막 실패 후 기록 유지 시간이 지났으면 1부터 다시 셉니다.
+            let failed_count = sqlx::query_scalar!(
+                r#"
+                UPDATE login_throttles
+                SET failed_count = CASE
+                        WHEN last_failed_at < CURRENT_TIMESTAMP - make_interval(secs => $3)
+                        THEN 1
+                        ELSE failed_count + 1
+                    END,
+                    last_failed_at = CURRENT_TIMESTAMP
+                WHERE scope = $1 AND subject = $2
+                RETURNING failed_count
+                "#,
+                scope.as_str(),
+                subject,
+                security.login_failure_window_secs as f64
+            )
+            .fetch_one(&mut *tx)
+            .await?;
+
+            let (_, lockout_threshold) = scope.limits(security);
+            let locked_until = (failed_count >= lockout_threshold)
+                .then(|| Utc::now() + Duration::seconds(security.login_lockout_secs));
+            if let Some(locked_until) = locked_until {
+                sqlx::query!(
+                    "UPDATE login_throttles SET locked_until = $3 WHERE scope = $1 AND subject = $2",
+                    scope.as_str(),
+                    subject,
+                    locked_until
+                )
+                .execute(&mut *tx)
+                .await?;
+            }
+            reserved.push(Reserved { scope, subject, failed_count, locked_until });
+        }
+
+        tx.commit().await?;
+        Ok(Attempt {
+            email: email.to_string(),
+            client_ip,
+            reserved,
+        })
+    }
+
+    // 예약한 시도가 실패했음을 확정합니다. 실패 횟수는 reserve에서 이미 셌습니다.
+    // 이 시도로 잠금 기준에 도달했으면 감사 로그에 남깁니다.
+    // user_id는 이메일에 해당하는 사용자가 있을 때만 전달합니다.
+    pub async fn record_failure(&self, attempt: Attempt, user_id: Option<Uuid>) -> Result<(), AppError> {
+        let mut tx = self.db.begin().await?;
+
+        for reserved in &attempt.reserved {
+            let Some(locked_until) = reserved.locked_until else {
+                continue;
+            };
+            sqlx::query!(
+                r#"
+                INSERT INTO auth_audit_log (event, user_id, email, ip_address, details)
+                VALUES ($1, $2, $3, $4, $5)
+                "#,
+                reserved.scope.lock_event(),
+                user_id,
+                attempt.email,
+                attempt.client_ip.map(|ip| ip.to_string()),
+                format!(
+                    "{} consecutive failed login attempts, locked until {}",
+                    reserved.failed_count,
+                    locked_until.to_rfc3339()
+                )
+            )
+            .execute(&mut *tx)
+            .await?;
+            log::warn!(
+                "{} {} locked after {} failed login attempts",
+                reserved.scope.as_str(),
+                reserved.subject,
+                reserved.failed_count
+            );
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    // 예약한 시도가 성공했으면 계정의 실패 기록을 지우고, IP는 미리 센 시도만 되돌립니다.
+    pub async fn record_success(&self, attempt: Attempt) -> Result<(), AppError> {
+        self.release(attempt, true).await
+    }
+
+    // 실패도 성공도 아닌 시도(비밀번호는 맞았지만 2단계 인증이 남은 경우)의 예약을 되돌립니다.
+    pub async fn cancel(&self, attempt: Attempt) -> Result<(), AppError> {
+        self.release(attempt, false).await
+    }
+
+    // 미리 센 시도를 되돌립니다. 이 시도로 잠갔다면 잠금도 풉니다.
+    // clear_account가 true이면 계정의 실패 기록을 모두 지웁니다.
+    async fn release(&self, attempt: Attempt, clear_account: bool) -> Result<(), AppError> {
+        let mut tx = self.db.begin().await?;
+
+        for reserved in &attempt.reserved {
+            if clear_account && matches!(reserved.scope, Scope::Account) {
+                sqlx::query!(
+                    "DELETE FROM login_throttles WHERE scope = $1 AND subject = $2",
+                    reserved.scope.as_str(),
+                    reserved.subject
+                )
+                .execute(&mut *tx)
+                .await?;
+                continue;
+            }
+            sqlx::query!(
+                r#"
+                UPDATE login_throttles
+                SET failed_count = GREATEST(failed_count - 1, 0),
+                    locked_until = CASE WHEN $3 THEN NULL ELSE locked_until END
+                WHERE scope = $1 AND subject = $2
+                "#,
+                reserved.scope.as_str(),
+                reserved.subject,
+                reserved.locked_until.is_some()
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+// reserve로 예약한 로그인 시도입니다.
+// record_failure, record_success, cancel 중 하나로 마무리하며, 마무리하지 않으면 실패로 남습니다.
+#[must_use]
+pub struct Attempt {
+    email: String,               // 시도한 이메일 (감사 로그용)
+    client_ip: Option<IpAddr>,   // 클라이언트 IP
+    reserved: Vec<Reserved>,     // 대상별로 미리 센 실패
+}
+
+// 한 대상(계정 또는 IP)에 미리 센 실패입니다.
+struct Reserved {
+    scope: Scope,
+    subject: String,
+    failed_count: i32,                       // 이 시도를 포함한 연속 실패 횟수
+    locked_until: Option<DateTime<Utc>>,     // 이 시도로 잠갔다면 잠금 해제 시각
+}
+
+// 실패를 기록하고 확인할 대상 목록입니다. IP를 알 수 없으면 계정만 확인합니다.
+fn subjects(email: &str, client_ip: Option<IpAddr>) -> Vec<(Scope, String)> {
+    let mut subjects = vec![(Scope::Account, normalize_email(email))];
+    if let Some(ip) = client_ip {
+        subjects.push((Scope::Ip, ip.to_string()));
+    }
+    subjects
+}
@@ -0,0 +1,255 @@
+// mfa_service.rs
+// 2단계 인증(TOTP) 등록과 인증 코드 확인을 처리하는 서비스입니다.
+//
+// 등록은 두 단계로 이루어집니다.
+// 1. begin_totp_enrollment: 비밀 키를 만들어 암호화하여 저장하고, 인증 앱에 등록할 URI를 반환합니다.
+// 2. confirm_totp: 인증 앱이 만든 코드를 확인한 뒤 2단계 인증을 켜고 복구 코드를 발급합니다.
+// 2단계 인증을 켠 사용자는 로그인할 때 비밀번호와 함께 인증 코드나 복구 코드를 제출해야 합니다 (AuthService::verify_mfa).
+//
+// 한 번 사용한 TOTP 코드는 같은 시간 단계 안에서도 다시 사용할 수 없고, 복구 코드는 각각 한 번만 사용할 수 있습니다.
+// 2단계 인증을 끌 때는 현재 비밀번호와 코드를 확인하며, 틀리면 로그인 실패와 같은 계정 기록(LoginThrottle)에 남습니다.
+
+use chrono::Utc;
+use data_encoding::BASE32_NOPAD;
+use rand::{rngs::OsRng, RngCore};
+use sqlx::PgPool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::errors::AppError;
+use crate::models::mfa::{DisableTotpDto, RecoveryCodes, TotpEnrollment};
+use crate::services::login_throttle::LoginThrottle;
+use crate::services::opaque_token;
+use crate::services::password_hasher::PasswordHasher;
+use crate::services::secret_box::SecretBox;
+use crate::services::totp;
+
+// MfaService는 TOTP 등록, 해제와 인증 코드 확인 기능을 제공합니다.
+pub struct MfaService {
+    db: PgPool,                  // 데이터베이스 연결 풀
+    config: Arc<Config>,         // 애플리케이션 설정 (mfa.*)
+    secrets: Option<SecretBox>,  // TOTP 비밀 키 암호화 (mfa.encryption_key가 없으면 None)
+    passwords: PasswordHasher,   // 2단계 인증 해제 시 현재 비밀번호 확인
+    throttle: LoginThrottle,     // 2단계 인증 해제 시 실패 기록 및 제한
+}
+
+impl MfaService {
+    // 새로운 MfaService 인스턴스를 생성합니다.
+    pub fn new(db: PgPool, config: Arc<Config>) -> Self {
+        let secrets = config.mfa.encryption_key_bytes().map(|key| SecretBox::new(&key));
+        let passwords = PasswordHasher::new(&config.security);
+        let throttle = LoginThrottle::new(db.clone(), config.clone());
+        Self { db, config, secrets, passwords, throttle }
+    }
+
+    fn secrets(&self) -> Result<&SecretBox, AppError> {
+        self.secrets
+            .as_ref()
+            .ok_or_else(|| AppError::Internal("mfa.encryption_key is not configured".to_string()))
+    }
+
+    // 사용자가 2단계 인증을 사용하고 있는지 확인합니다.
+    pub async fn is_enabled(&self, user_id: Uuid) -> Result<bool, AppError> {
+        let enabled = sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM user_totp WHERE user_id = $1 AND confirmed_at IS NOT NULL) AS "enabled!""#,
+            user_id
+        )
+        .fetch_one(&self.db)
+        .await?;
+        Ok(enabled)
+    }
+
+    // TOTP 등록을 시작합니다.
+    // 새 비밀 키를 만들어 저장하며, 확인을 마치지 않은 이전 등록은 새 키로 대체됩니다.
+    // 이미 2단계 인증을 사용 중이면 Conflict를 반환합니다.
+    pub async fn begin_totp_enrollment(&self, user_id: Uuid) -> Result<TotpEnrollment, AppError> {
+        let secrets = self.secrets()?;
+        let email = sqlx::query_scalar!("SELECT email FROM users WHERE id = $1", user_id)
+            .fetch_optional(&self.db)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        let secret = totp::generate_secret();
+        let ciphertext = secrets.seal(&secret, user_id.as_bytes())?;
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO user_totp (user_id, secret_ciphertext)
+            VALUES ($1, $2)
+            ON CONFLICT (user_id) DO UPDATE
+            SET secret_ciphertext = EXCLUDED.secret_ciphertext, last_used_step = NULL, created_at = CURRENT_TIMESTAMP
+            WHERE user_totp.confirmed_at IS NULL
+            "#,
+            user_id,
+            ciphertext
+        )
+        .execute(&self.db)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::Conflict("Two-factor authentication is already enabled".to_string()));
+        }
+
+        Ok(TotpEnrollment {
+            secret: totp::encode_secret(&secret),
+            provisioning_uri: totp::provisioning_uri(&self.config.mfa.issuer, &email, &secret),
+        })
+    }
+
+    // 인증 앱이 만든 코드를 확인하고 2단계 인증을 켭니다.
+    // 코드가 틀리면 code 필드의 Validation 에러를 반환합니다.
+    pub async fn confirm_totp(&self, user_id: Uuid, code: &str) -> Result<RecoveryCodes, AppError> {
+        let secrets = self.secrets()?;
+        let mut tx = self.db.begin().await?;
+
+        let enrollment = sqlx::query!(
+            "SELECT secret_ciphertext, confirmed_at FROM user_totp WHERE user_id = $1 FOR UPDATE",
+            user_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::BadRequest("Start two-factor authentication enrollment first".to_string()))?;
+        if enrollment.confirmed_at.is_some() {
+            return Err(AppError::Conflict("Two-factor authentication is already enabled".to_string()));
+        }
+
+        let secret = secrets.open(&enrollment.secret_ciphertext, user_id.as_bytes())?;
+        let step = totp::verify(&secret, code, Utc::now().timestamp(), self.config.mfa.totp_skew_steps)
+            .ok_or_else(|| AppError::validation("code", "code is incorrect"))?;
+
+        sqlx::query!(
+            "UPDATE user_totp SET confirmed_at = CURRENT_TIMESTAMP, last_used_step = $2 WHERE user_id = $1",
+            user_id,
+            step
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        // 이전에 발급한 복구 코드는 모두 지우고 새로 발급합니다.
+        sqlx::query!("DELETE FROM mfa_recovery_codes WHERE user_id = $1", user_id)
+            .execute(&mut *tx)
+            .await?;
+        let recovery_codes: Vec<String> = (0..self.config.mfa.recovery_code_count)
+            .map(|_| generate_recovery_code())
+            .collect();
+        for code in &recovery_codes {
+            sqlx::query!(
+                "INSERT INTO mfa_recovery_codes (user_id, code_hash) VALUES ($1, $2)",
+                user_id,
+                opaque_token::hash(&normalize_recovery_code(code))
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        log::info!("user {} enabled two-factor authentication", user_id);
+        Ok(RecoveryCodes { recovery_codes })
+    }
+
+    // 2단계 인증을 끕니다. 현재 비밀번호와 인증 코드(또는 복구 코드)를 확인한 뒤 비밀 키와 복구 코드를 모두 지웁니다.
+    // 탈취한 액세스 토큰으로 코드를 대입해 볼 수 없도록, 확인은 /mfa/verify와 같은 계정 단위 실패 제한을 거칩니다.
+    // 틀리면 current_password 또는 code 필드의 Validation 에러를, 제한 중이면 RateLimited를 반환합니다.
+    pub async fn disable_totp(&self, user_id: Uuid, dto: &DisableTotpDto) -> Result<(), AppError> {
+        if !self.is_enabled(user_id).await? {
+            return Err(AppError::BadRequest("Two-factor authentication is not enabled".to_string()));
+        }
+        let user = sqlx::query!("SELECT email, password_hash FROM users WHERE id = $1", user_id)
+            .fetch_optional(&self.db)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        let attempt = self.throttle.reserve(&user.email, None).await?;
+        if !self.passwords.verify(&dto.current_password, &user.password_hash).await? {
+            self.throttle.record_failure(attempt, Some(user_id)).await?;
+            return Err(AppError::validation("current_password", "current password is incorrect"));
+        }
+        if !self.verify_code(user_id, &dto.code).await? {
+            self.throttle.record_failure(attempt, Some(user_id)).await?;
+            return Err(AppError::validation("code", "code is incorrect"));
+        }
+        self.throttle.record_success(attempt).await?;
+
+        let mut tx = self.db.begin().await?;
+        sqlx::query!("DELETE FROM user_totp WHERE user_id = $1", user_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query!("DELETE FROM mfa_recovery_codes WHERE user_id = $1", user_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query!("DELETE FROM mfa_challenges WHERE user_id = $1", user_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        log::info!("user {} disabled two-factor authentication", user_id);
+        Ok(())
+    }
+
+    // 인증 앱의 코드나 복구 코드를 확인합니다.
+    // 확인된 코드는 사용 처리되어, 같은 코드로 다시 확인할 수 없습니다.
+    // 2단계 인증을 사용하지 않는 사용자는 항상 false를 반환합니다.
+    pub async fn verify_code(&self, user_id: Uuid, code: &str) -> Result<bool, AppError> {
+        let Some(stored) = sqlx::query!(
+            "SELECT secret_ciphertext FROM user_totp WHERE user_id = $1 AND confirmed_at IS NOT NULL",
+            user_id
+        )
+        .fetch_optional(&self.db)
+        .await?
+        else {
+            return Ok(false);
+        };
+
+        let code = code.trim();
+        if code.len() == totp::DIGITS && code.bytes().all(|b| b.is_ascii_digit()) {
+            let secret = self.secrets()?.open(&stored.secret_ciphertext, user_id.as_bytes())?;
+            let Some(step) = totp::verify(&secret, code, Utc::now().timestamp(), self.config.mfa.totp_skew_steps) else {
+                return Ok(false);
+            };
+            // 이미 사용한 시간 단계(또는 그 이전)의 코드는 거부하여 같은 코드가 두 번 사용되지 않게 합니다.
+            let result = sqlx::query!(
+                r#"
+                UPDATE user_totp SET last_used_step = $2
+                WHERE user_id = $1 AND (last_used_step IS NULL OR last_used_step < $2)
+                "#,
+                user_id,
+                step
+            )
+            .execute(&self.db)
+            .await?;
+            return Ok(result.rows_affected() == 1);
+        }
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE mfa_recovery_codes SET used_at = CURRENT_TIMESTAMP
+            WHERE user_id = $1 AND code_hash = $2 AND used_at IS NULL
+            "#,
+            user_id,
+            opaque_token::hash(&normalize_recovery_code(code))
+        )
+        .execute(&self.db)
+        .await?;
+        if result.rows_affected() == 1 {
+            log::info!("user {} used a recovery code", user_id);
+            return Ok(true);
+        }
+        Ok(false)
+    }
+}
+
+// 읽고 입력하기 쉬운 "xxxxx-xxxxx" 형식의 복구 코드를 만듭니다 (소문자 base32 10자, 50비트).
+fn generate_recovery_code() -> String {
+    let mut bytes = [0u8; 7];
+    OsRng.fill_bytes(&mut bytes);
+    let encoded = BASE32_NOPAD.encode(&bytes).to_lowercase();
+    format!("{}-{}", &encoded[..5], &encoded[5..10])
+}
+
+// 사용자가 하이픈이나 공백을 빼거나 대문자로 입력해도 같은 코드로 취급합니다.
+fn normalize_recovery_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
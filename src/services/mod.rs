@@ -1,3 +1,25 @@
 pub mod post_service;
 pub mod comment_service;
 pub mod auth_service;
+pub mod user_service;
+pub mod email_verification_service;
+pub mod jwt_keys;
+pub mod opaque_token;
+pub mod password_hasher;
+pub mod session_cache;
+pub mod login_throttle;
+pub mod mfa_service;
+pub mod oidc_service;
+pub mod api_token_service;
+pub mod secret_box;
+pub mod totp;
+pub mod moderation_service;
+
+// 작성자 권한 확인이 필요한 수정/삭제 작업의 결과입니다.
+// 리소스가 없는 경우(404)와 권한이 없는 경우(403)를 구분하기 위해 사용합니다.
+#[derive(Debug)]
+pub enum MutationOutcome<T> {
+    Done(T),    // 작업이 성공적으로 수행됨
+    NotFound,   // 대상 리소스가 존재하지 않음
+    Forbidden,  // 리소스는 존재하지만 현재 사용자에게 권한이 없음
+}
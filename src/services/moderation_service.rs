@@ -0,0 +1,71 @@
+// moderation_service.rs
+// 모더레이터 작업 기록을 저장하고 조회하는 서비스입니다.
+// 기록은 게시글, 댓글 수정/삭제와 같은 트랜잭션에서 저장되므로, 작업이 성공하면 기록도 반드시 남습니다.
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::errors::AppError;
+use crate::models::moderation::{ModeratorAction, ModeratorActionLog};
+use crate::models::pagination::{Page, PageParams};
+
+// ModerationService는 모더레이터 작업 기록 조회 기능을 제공합니다.
+pub struct ModerationService {
+    db: PgPool,  // 데이터베이스 연결 풀
+}
+
+impl ModerationService {
+    // 새로운 ModerationService 인스턴스를 생성합니다.
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    // 모더레이터 작업 기록을 최신순으로 조회합니다.
+    pub async fn get_actions(&self, params: PageParams) -> Result<Page<ModeratorActionLog>, AppError> {
+        let actions = sqlx::query_as!(
+            ModeratorActionLog,
+            r#"
+            SELECT id, moderator_id, action AS "action: ModeratorAction", target_id, target_author_id, created_at
+            FROM moderator_actions
+            ORDER BY created_at DESC, id DESC
+            LIMIT $1 OFFSET $2
+            "#,
+            params.per_page,
+            params.offset()
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        let total = sqlx::query_scalar!(r#"SELECT COUNT(*) AS "count!" FROM moderator_actions"#)
+            .fetch_one(&self.db)
+            .await?;
+
+        Ok(Page::new(actions, total, params))
+    }
+}
+
+// 모더레이터 작업을 기록합니다.
+// 대상 게시글이나 댓글을 변경한 트랜잭션 안에서 호출해야 합니다.
+pub async fn record_action(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    moderator_id: Uuid,
+    action: ModeratorAction,
+    target_id: Uuid,
+    target_author_id: Uuid,
+) -> Result<(), AppError> {
+    sqlx::query!(
+        r#"
+        INSERT INTO moderator_actions (moderator_id, action, target_id, target_author_id)
+        VALUES ($1, $2, $3, $4)
+        "#,
+        moderator_id,
+        action as ModeratorAction,
+        target_id,
+        target_author_id
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    log::info!("moderator {} performed {:?} on {}", moderator_id, action, target_id);
+    Ok(())
+}
@@ -0,0 +1,444 @@
+// oidc_service.rs
+// 외부 ID 공급자(OIDC)로 로그인하는 기능을 제공하는 서비스입니다.
+// 인가 코드(authorization code) 방식에 PKCE(RFC 7636)를 함께 사용합니다.
+//
+// 1. authorize: state, nonce, code_verifier를 만들어 저장하고 공급자의 로그인 페이지 주소를 반환합니다.
+// 2. 사용자가 공급자에서 로그인하면, 공급자는 redirect_uri로 code와 state를 돌려줍니다.
+// 3. complete: state를 확인한 뒤 code와 code_verifier로 ID 토큰을 받고, 서명, 발급자, 수신자, nonce를 검증합니다.
+//
+// 공급자의 계정(provider, subject)은 user_identities로 사용자와 연결됩니다.
+// 처음 로그인할 때 공급자가 확인한 이메일과 같은 계정이 있으면 연결하고, 없으면 새 계정을 만듭니다.
+// 이메일 인증을 마치지 않은 계정에는 연결하지 않습니다. 다른 사람의 이메일로 먼저 가입해 둔 계정이
+// 나중에 그 이메일의 주인이 로그인할 때 넘어가지 않도록 하기 위해서입니다.
+//
+// 공급자의 엔드포인트는 discovery 문서(oidc.discovery_url)에서 읽어 캐시합니다.
+// ID 토큰의 kid가 캐시된 JWKS에 없으면 공급자가 키를 교체한 것으로 보고 JWKS를 다시 받아옵니다.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{Duration, Utc};
+use jsonwebtoken::jwk::{AlgorithmParameters, EllipticCurve, Jwk, JwkSet};
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use reqwest::{header, Url};
+use serde::{Deserialize, Deserializer};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::sync::{Arc, RwLock};
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::errors::AppError;
+use crate::models::oidc::{OidcAuthorization, OidcCallbackDto};
+use crate::models::user::{normalize_email, Role, User, DELETED_USERNAME};
+use crate::services::auth_service::username_taken;
+use crate::services::opaque_token;
+use crate::services::password_hasher::PasswordHasher;
+
+// discovery 문서에서 사용하는 항목입니다.
+#[derive(Debug, Clone, Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+// 토큰 엔드포인트의 응답입니다. 공급자의 액세스 토큰은 사용하지 않습니다.
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+// ID 토큰에서 사용하는 클레임입니다. iss, aud, exp는 Validation에서 검증합니다.
+#[derive(Debug, Deserialize)]
+struct IdTokenClaims {
+    sub: String,
+    nonce: Option<String>,
+    email: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_flag")]
+    email_verified: bool,
+    preferred_username: Option<String>,
+    name: Option<String>,
+}
+
+// OidcService는 OIDC 로그인 시작과 완료 기능을 제공합니다.
+pub struct OidcService {
+    db: PgPool,                                  // 데이터베이스 연결 풀
+    config: Arc<Config>,                         // 애플리케이션 설정 (oidc.*)
+    http: reqwest::Client,                       // 공급자 요청에 사용할 HTTP 클라이언트
+    metadata: RwLock<Option<ProviderMetadata>>,  // 캐시된 discovery 문서
+    jwks: RwLock<Option<JwkSet>>,                // 캐시된 공급자의 ID 토큰 검증 키
+}
+
+impl OidcService {
+    // 새로운 OidcService 인스턴스를 생성합니다.
+    // 공급자에는 처음 로그인할 때 연결하므로, 서버 시작 시 공급자가 응답하지 않아도 됩니다.
+    pub fn new(db: PgPool, config: Arc<Config>) -> Self {
+        let http = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(config.oidc.http_timeout_secs))
+            .build()
+            .expect("Failed to create HTTP client");
+        Self {
+            db,
+            config,
+            http,
+            metadata: RwLock::new(None),
+            jwks: RwLock::new(None),
+        }
+    }
+
+    fn ensure_enabled(&self) -> Result<(), AppError> {
+        if !self.config.oidc.is_enabled() {
+            return Err(AppError::NotFound("OIDC login is not configured".to_string()));
+        }
+        Ok(())
+    }
+
+    // OIDC 로그인을 시작합니다.
+    // state, nonce, code_verifier를 저장하고, code_challenge를 담은 공급자의 로그인 페이지 주소를 반환합니다.
+    pub async fn authorize(&self) -> Result<OidcAuthorization, AppError> {
+        self.ensure_enabled()?;
+        let metadata = self.metadata().await?;
+        let oidc = &self.config.oidc;
+
+        let state = opaque_token::generate();
+        let nonce = opaque_token::generate();
+        let code_verifier = opaque_token::generate();
+        let expires_at = Utc::now() + Duration::seconds(oidc.state_ttl_secs);
+
+        let mut tx = self.db.begin().await?;
+        // 만료된 로그인 요청은 더 이상 필요하지 않으므로 함께 정리합니다.
+        sqlx::query!("DELETE FROM oidc_login_states WHERE expires_at < CURRENT_TIMESTAMP")
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query!(
+            r#"
+            INSERT INTO oidc_login_states (state_hash, code_verifier, nonce, expires_at)
+            VALUES ($1, $2, $3, $4)
+            "#,
+            opaque_token::hash(&state),
+            code_verifier,
+            nonce,
+            expires_at
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        let url = Url::parse_with_params(
+            &metadata.authorization_endpoint,
+            &[
+                ("response_type", "code"),
+                ("client_id", oidc.client_id.as_str()),
+                ("redirect_uri", oidc.redirect_uri.as_str()),
+                ("scope", oidc.scopes.as_str()),
+                ("state", state.as_str()),
+                ("nonce", nonce.as_str()),
+                ("code_challenge", pkce_challenge(&code_verifier).as_str()),
+                ("code_challenge_method", "S256"),
+            ],
+        )
+        .map_err(|e| AppError::Internal(format!("invalid authorization_endpoint: {}", e)))?;
+
+        Ok(OidcAuthorization {
+            authorization_url: url.into(),
+            expires_in: oidc.state_ttl_secs,
+        })
+    }
+
+    // 공급자가 돌려준 code와 state로 로그인을 마치고, 연결된 사용자를 반환합니다.
+    // 처음 로그인하는 공급자 계정이면 사용자와 연결하거나 새 사용자를 만듭니다.
+    // 토큰 발급(2단계 인증 포함)은 AuthService::sign_in에서 처리합니다.
+    pub async fn complete(&self, dto: &OidcCallbackDto) -> Result<User, AppError> {
+        self.ensure_enabled()?;
+
+        // state는 한 번만 사용할 수 있습니다. 공급자에 요청하기 전에 사용 처리하여 같은 code의 재전송을 막습니다.
+        let login = sqlx::query!(
+            r#"
+            UPDATE oidc_login_states
+            SET used_at = CURRENT_TIMESTAMP
+            WHERE state_hash = $1 AND used_at IS NULL AND expires_at > CURRENT_TIMESTAMP
+            RETURNING code_verifier, nonce
+            "#,
+            opaque_token::hash(&dto.state)
+        )
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| AppError::Unauthorized("Invalid or expired login state".to_string()))?;
+
+        let metadata = self.metadata().await?;
+        let id_token = self.exchange_code(&metadata, &dto.code, &login.code_verifier).await?;
+        let claims = self.verify_id_token(&metadata, &id_token, &login.nonce).await?;
+        self.find_or_create_user(&claims).await
+    }
+
+    // 인가 코드와 code_verifier를 공급자의 토큰 엔드포인트로 보내 ID 토큰을 받습니다.
+    // 클라이언트 시크릿이 있으면 HTTP Basic 인증(client_secret_basic)을 사용합니다.
+    async fn exchange_code(&self, metadata: &ProviderMetadata, code: &str, code_verifier: &str) -> Result<String, AppError> {
+        let oidc = &self.config.oidc;
+        let mut form = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", oidc.redirect_uri.as_str()),
+            ("code_verifier", code_verifier),
+        ];
+        let mut request = self.http.post(&metadata.token_endpoint).header(header::ACCEPT, "application/json");
+        if oidc.client_secret.is_empty() {
+            form.push(("client_id", oidc.client_id.as_str()));
+        } else {
+            request = request.basic_auth(&oidc.client_id, Some(&oidc.client_secret));
+        }
+
+        let response = request.form(&form).send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            log::warn!("OIDC token request failed with {}: {}", status, body);
+            return Err(AppError::Unauthorized("The identity provider rejected the authorization code".to_string()));
+        }
+        let tokens: TokenResponse = response.json().await?;
+        Ok(tokens.id_token)
+    }
+
+    // ID 토큰의 서명과 iss, aud, exp, nonce를 검증하고 클레임을 반환합니다.
+    // 공급자의 키에 맞는 비대칭 알고리즘만 허용하여, 헤더의 alg를 바꾸는 알고리즘 혼동 공격을 막습니다.
+    async fn verify_id_token(&self, metadata: &ProviderMetadata, id_token: &str, nonce: &str) -> Result<IdTokenClaims, AppError> {
+        let invalid = |reason: String| {
+            log::warn!("rejected OIDC ID token: {}", reason);
+            AppError::Unauthorized("Invalid ID token".to_string())
+        };
+
+        let header = decode_header(id_token).map_err(|e| invalid(e.to_string()))?;
+        let jwk = self
+            .find_jwk(metadata, header.kid.as_deref())
+            .await?
+            .ok_or_else(|| invalid(format!("unknown signing key {:?}", header.kid)))?;
+        if !key_supports(&jwk.algorithm, header.alg) {
+            return Err(invalid(format!("{:?} does not match the signing key", header.alg)));
+        }
+        let key = DecodingKey::from_jwk(&jwk).map_err(|e| invalid(e.to_string()))?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&metadata.issuer]);
+        validation.set_audience(&[&self.config.oidc.client_id]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+        validation.leeway = self.config.jwt.leeway_secs;
+
+        let claims = decode::<IdTokenClaims>(id_token, &key, &validation)
+            .map_err(|e| invalid(e.to_string()))?
+            .claims;
+        if claims.nonce.as_deref() != Some(nonce) {
+            return Err(invalid("nonce does not match".to_string()));
+        }
+        Ok(claims)
+    }
+
+    // 공급자 계정과 연결된 사용자를 찾습니다. 연결된 사용자가 없으면 이메일이 같은 사용자와 연결하거나 새로 만듭니다.
+    async fn find_or_create_user(&self, claims: &IdTokenClaims) -> Result<User, AppError> {
+        let provider = &self.config.oidc.provider;
+        let mut tx = self.db.begin().await?;
+
+        let linked_user_id = sqlx::query_scalar!(
+            r#"
+            UPDATE user_identities
+            SET last_login_at = CURRENT_TIMESTAMP, email = COALESCE($3, email)
+            WHERE provider = $1 AND subject = $2
+            RETURNING user_id
+            "#,
+            provider,
+            claims.sub,
+            claims.email
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+        if let Some(user_id) = linked_user_id {
+            let user = sqlx::query_as!(
+                User,
+                r#"
+                SELECT id, username, email, password_hash, token_version, role AS "role: Role", email_verified_at, created_at, updated_at
+                FROM users WHERE id = $1
+                "#,
+                user_id
+            )
+            .fetch_one(&mut *tx)
+            .await?;
+            tx.commit().await?;
+            return Ok(user);
+        }
+
+        // 공급자가 확인하지 않은 이메일로는 계정을 만들거나 연결하지 않습니다.
+        let email = claims
+            .email
+            .as_deref()
+            .filter(|_| claims.email_verified)
+            .map(normalize_email)
+            .ok_or_else(|| AppError::BadRequest("The identity provider did not return a verified email address".to_string()))?;
+
+        let existing = sqlx::query_as!(
+            User,
+            r#"
+            SELECT id, username, email, password_hash, token_version, role AS "role: Role", email_verified_at, created_at, updated_at
+            FROM users WHERE LOWER(email) = $1
+            ORDER BY created_at, id
+            LIMIT 1
+            FOR UPDATE
+            "#,
+            email
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+        let user = match existing {
+            Some(user) if user.email_verified_at.is_none() => {
+                return Err(AppError::Conflict(
+                    "An account with this email already exists. Sign in with your password and verify your email first".to_string(),
+                ));
+            }
+            Some(user) => {
+                log::info!("linked {} identity to existing user {}", provider, user.id);
+                user
+            }
+            None => {
+                let user = self.create_user(&mut tx, claims, &email).await?;
+                log::info!("created user {} from {} identity", user.id, provider);
+                user
+            }
+        };
+
+        sqlx::query!(
+            r#"
+            INSERT INTO user_identities (user_id, provider, subject, email)
+            VALUES ($1, $2, $3, $4)
+            "#,
+            user.id,
+            provider,
+            claims.sub,
+            email
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(user)
+    }
+
+    // 공급자 계정으로 새 사용자를 만듭니다.
+    // 공급자가 확인한 이메일이므로 인증된 상태로 만들고, 비밀번호는 아무도 모르는 값으로 설정합니다
+    // (비밀번호 로그인이 필요하면 비밀번호 재설정을 사용합니다).
+    async fn create_user(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        claims: &IdTokenClaims,
+        email: &str,
+    ) -> Result<User, AppError> {
+        let user_id = Uuid::new_v4();
+        let mut username = username_from_claims(claims, email);
+        // 이미 사용 중인 이름이면 사용자 ID의 앞부분을 붙입니다 (중복 이름을 정리한 마이그레이션과 같은 방식).
+        if username_taken(&self.db, &username, None).await? {
+            username = format!("{}_{}", username, &user_id.to_string()[..8]);
+        }
+        let password_hash = PasswordHasher::new(&self.config.security).hash(&opaque_token::generate()).await?;
+
+        let user = sqlx::query_as!(
+            User,
+            r#"
+            INSERT INTO users (id, username, email, password_hash, email_verified_at)
+            VALUES ($1, $2, $3, $4, CURRENT_TIMESTAMP)
+            RETURNING id, username, email, password_hash, token_version, role AS "role: Role", email_verified_at, created_at, updated_at
+            "#,
+            user_id,
+            username,
+            email,
+            password_hash
+        )
+        .fetch_one(&mut **tx)
+        .await?;
+        Ok(user)
+    }
+
+    // discovery 문서를 반환합니다. 처음 사용할 때 공급자에서 받아와 캐시합니다.
+    async fn metadata(&self) -> Result<ProviderMetadata, AppError> {
+        let cached = self.metadata.read().unwrap().clone();
+        if let Some(metadata) = cached {
+            return Ok(metadata);
+        }
+
+        let metadata: ProviderMetadata = self
+            .http
+            .get(&self.config.oidc.discovery_url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        *self.metadata.write().unwrap() = Some(metadata.clone());
+        Ok(metadata)
+    }
+
+    // kid에 해당하는 공급자의 검증 키를 찾습니다. 캐시에 없으면 JWKS를 다시 받아옵니다.
+    async fn find_jwk(&self, metadata: &ProviderMetadata, kid: Option<&str>) -> Result<Option<Jwk>, AppError> {
+        let cached = self.jwks.read().unwrap().as_ref().and_then(|jwks| select_jwk(jwks, kid));
+        if cached.is_some() {
+            return Ok(cached);
+        }
+
+        let jwks: JwkSet = self
+            .http
+            .get(&metadata.jwks_uri)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let found = select_jwk(&jwks, kid);
+        *self.jwks.write().unwrap() = Some(jwks);
+        Ok(found)
+    }
+}
+
+// PKCE code_challenge를 계산합니다 (S256: code_verifier의 SHA-256을 base64url로 인코딩).
+pub fn pkce_challenge(code_verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+}
+
+// kid로 키를 찾습니다. 토큰에 kid가 없으면 키가 하나뿐일 때만 그 키를 사용합니다.
+fn select_jwk(jwks: &JwkSet, kid: Option<&str>) -> Option<Jwk> {
+    match kid {
+        Some(kid) => jwks.find(kid).cloned(),
+        None if jwks.keys.len() == 1 => jwks.keys.first().cloned(),
+        None => None,
+    }
+}
+
+// 키의 종류에 맞는 알고리즘인지 확인합니다. 대칭 키(oct)는 허용하지 않습니다.
+fn key_supports(key: &AlgorithmParameters, algorithm: Algorithm) -> bool {
+    use Algorithm::*;
+
+    match key {
+        AlgorithmParameters::RSA(_) => matches!(algorithm, RS256 | RS384 | RS512 | PS256 | PS384 | PS512),
+        AlgorithmParameters::EllipticCurve(params) => matches!(
+            (&params.curve, algorithm),
+            (EllipticCurve::P256, ES256) | (EllipticCurve::P384, ES384)
+        ),
+        AlgorithmParameters::OctetKeyPair(_) => algorithm == EdDSA,
+        AlgorithmParameters::OctetKey(_) => false,
+    }
+}
+
+// 공급자가 알려준 이름으로 사용자 이름을 정합니다. 쓸 수 있는 이름이 없으면 이메일의 @ 앞부분을 사용합니다.
+fn username_from_claims(claims: &IdTokenClaims, email: &str) -> String {
+    let local_part = email.split('@').next().unwrap_or_default();
+    [claims.preferred_username.as_deref(), claims.name.as_deref(), Some(local_part)]
+        .into_iter()
+        .flatten()
+        .map(|name| name.trim().chars().filter(|c| !c.is_control()).take(40).collect::<String>())
+        .find(|name| name.chars().count() >= 3 && !name.eq_ignore_ascii_case(DELETED_USERNAME))
+        .unwrap_or_else(|| "user".to_string())
+}
+
+// 일부 공급자는 email_verified를 문자열("true")로 보냅니다.
+fn deserialize_flag<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+    Ok(match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::Bool(value) => value,
+        serde_json::Value::String(value) => value == "true",
+        _ => false,
+    })
+}
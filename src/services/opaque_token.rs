@@ -0,0 +1,23 @@
+// opaque_token.rs
+// 리프레시 토큰처럼 클라이언트에 전달되는 불투명(opaque) 토큰을 생성하고 해시합니다.
+// 데이터베이스에는 원문 대신 SHA-256 해시만 저장하므로, 저장소가 유출되어도 토큰을 사용할 수 없습니다.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use rand::{rngs::OsRng, RngCore};
+use sha2::{Digest, Sha256};
+
+// 토큰 원문의 바이트 수입니다 (256비트).
+const TOKEN_BYTES: usize = 32;
+
+// 암호학적으로 안전한 난수로 새 토큰을 생성합니다.
+pub fn generate() -> String {
+    let mut bytes = [0u8; TOKEN_BYTES];
+    OsRng.fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+// 토큰의 SHA-256 해시를 16진수 문자열로 반환합니다.
+// 토큰 원문 자체가 충분한 엔트로피를 가지므로 솔트나 느린 해시가 필요하지 않습니다.
+pub fn hash(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
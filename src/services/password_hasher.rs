@@ -0,0 +1,139 @@
+// password_hasher.rs
+// 비밀번호를 해시하고 확인합니다.
+//
+// 새 비밀번호는 설정(security.password_hash_algorithm)에 따라 Argon2id 또는 bcrypt로 해시합니다.
+// 저장된 해시는 접두사($argon2id$, $2b$ 등)로 알고리즘을 구분하므로, 설정을 바꿔도 기존 해시로 로그인할 수 있습니다.
+// 로그인에 성공했을 때 needs_rehash가 true이면 현재 설정으로 다시 해시하여 저장합니다 (AuthService::login_from).
+//
+// 해시 계산은 의도적으로 느리므로, Actix 워커를 막지 않도록 블로킹 스레드 풀에서 실행합니다.
+
+use argon2::password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher as _, PasswordVerifier, SaltString};
+use argon2::{Argon2, Params, Version};
+
+use crate::config::{PasswordAlgorithm, SecurityConfig};
+use crate::errors::AppError;
+
+// bcrypt 해시의 접두사입니다 ($2a$, $2b$, $2x$, $2y$).
+const BCRYPT_PREFIXES: [&str; 4] = ["$2a$", "$2b$", "$2x$", "$2y$"];
+
+// Argon2id 해시의 접두사입니다.
+const ARGON2ID_PREFIX: &str = "$argon2id$";
+
+// 저장된 해시의 알고리즘을 접두사로 구분합니다.
+pub fn algorithm_of(hash: &str) -> Option<PasswordAlgorithm> {
+    if hash.starts_with(ARGON2ID_PREFIX) {
+        Some(PasswordAlgorithm::Argon2id)
+    } else if BCRYPT_PREFIXES.iter().any(|prefix| hash.starts_with(prefix)) {
+        Some(PasswordAlgorithm::Bcrypt)
+    } else {
+        None
+    }
+}
+
+// PasswordHasher는 설정된 알고리즘과 비용으로 비밀번호를 해시하고 확인합니다.
+// 설정 값만 가지므로 서비스마다 복제해서 사용합니다.
+#[derive(Debug, Clone)]
+pub struct PasswordHasher {
+    algorithm: PasswordAlgorithm,  // 새로 해시할 때 사용할 알고리즘
+    bcrypt_cost: u32,              // bcrypt 비용
+    argon2_params: Params,         // Argon2id 매개변수 (메모리, 반복 횟수, 병렬 처리 수)
+}
+
+impl PasswordHasher {
+    // 보안 설정으로 PasswordHasher를 생성합니다.
+    // Argon2 매개변수가 올바르지 않으면 패닉하므로, 서버 시작 시 Config::validate로 먼저 확인해야 합니다.
+    pub fn new(security: &SecurityConfig) -> Self {
+        let argon2_params = Params::new(
+            security.argon2_memory_kib,
+            security.argon2_iterations,
+            security.argon2_parallelism,
+            None,
+        )
+        .unwrap_or_else(|e| panic!("invalid Argon2 parameters: {}", e));
+        Self {
+            algorithm: security.password_hash_algorithm,
+            bcrypt_cost: security.bcrypt_cost,
+            argon2_params,
+        }
+    }
+
+    // 현재 설정의 알고리즘으로 비밀번호를 해시합니다.
+    pub async fn hash(&self, password: &str) -> Result<String, AppError> {
+        let hasher = self.clone();
+        let password = password.to_string();
+        run_blocking(move || hasher.hash_blocking(&password)).await
+    }
+
+    // 비밀번호가 저장된 해시와 일치하는지 확인합니다. 해시의 알고리즘은 접두사로 구분합니다.
+    // 알 수 없는 형식의 해시이면 Internal 에러를 반환합니다.
+    pub async fn verify(&self, password: &str, hash: &str) -> Result<bool, AppError> {
+        let hasher = self.clone();
+        let password = password.to_string();
+        let hash = hash.to_string();
+        run_blocking(move || hasher.verify_blocking(&password, &hash)).await
+    }
+
+    // 저장된 해시를 현재 설정으로 다시 해시해야 하는지 확인합니다.
+    // 알고리즘이 다르거나, bcrypt 비용 또는 Argon2 매개변수가 현재 설정과 다르면 true를 반환합니다.
+    pub fn needs_rehash(&self, hash: &str) -> bool {
+        match (self.algorithm, algorithm_of(hash)) {
+            (PasswordAlgorithm::Bcrypt, Some(PasswordAlgorithm::Bcrypt)) => hash
+                .parse::<bcrypt::HashParts>()
+                .map_or(true, |parts| parts.get_cost() != self.bcrypt_cost),
+            (PasswordAlgorithm::Argon2id, Some(PasswordAlgorithm::Argon2id)) => {
+                let Ok(parsed) = PasswordHash::new(hash) else {
+                    return true;
+                };
+                let current_version = parsed.version == Some(Version::V0x13.into());
+                let same_params = Params::try_from(&parsed).is_ok_and(|params| {
+                    params.m_cost() == self.argon2_params.m_cost()
+                        && params.t_cost() == self.argon2_params.t_cost()
+                        && params.p_cost() == self.argon2_params.p_cost()
+                });
+                !(current_version && same_params)
+            }
+            _ => true,
+        }
+    }
+
+    fn argon2(&self) -> Argon2<'static> {
+        Argon2::new(argon2::Algorithm::Argon2id, Version::V0x13, self.argon2_params.clone())
+    }
+
+    fn hash_blocking(&self, password: &str) -> Result<String, AppError> {
+        match self.algorithm {
+            PasswordAlgorithm::Bcrypt => Ok(bcrypt::hash(password.as_bytes(), self.bcrypt_cost)?),
+            PasswordAlgorithm::Argon2id => {
+                let salt = SaltString::generate(&mut OsRng);
+                Ok(self.argon2().hash_password(password.as_bytes(), &salt)?.to_string())
+            }
+        }
+    }
+
+    fn verify_blocking(&self, password: &str, hash: &str) -> Result<bool, AppError> {
+        match algorithm_of(hash) {
+            Some(PasswordAlgorithm::Bcrypt) => Ok(bcrypt::verify(password.as_bytes(), hash)?),
+            // 해시에 기록된 매개변수로 확인하므로, 설정이 바뀌기 전에 만든 해시도 확인할 수 있습니다.
+            Some(PasswordAlgorithm::Argon2id) => {
+                let parsed = PasswordHash::new(hash)?;
+                match self.argon2().verify_password(password.as_bytes(), &parsed) {
+                    Ok(()) => Ok(true),
+                    Err(argon2::password_hash::Error::Password) => Ok(false),
+                    Err(e) => Err(e.into()),
+                }
+            }
+            None => Err(AppError::Internal("Unsupported password hash format".to_string())),
+        }
+    }
+}
+
+// 해시 계산을 블로킹 스레드 풀에서 실행합니다.
+async fn run_blocking<T, F>(f: F) -> Result<T, AppError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, AppError> + Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| AppError::Internal(format!("password hashing task failed: {}", e)))?
+}
@@ -1,31 +1,59 @@
 // post_service.rs
 // 게시글 관련 비즈니스 로직을 처리하는 서비스입니다.
 // 게시글의 CRUD 작업과 페이지네이션을 처리합니다.
+// 조회 결과에는 users 테이블과 LEFT JOIN한 작성자 요약 정보가 포함됩니다.
 
 use sqlx::PgPool;
 use uuid::Uuid;
-use crate::models::post::{Post, CreatePostDto, UpdatePostDto};
+use crate::errors::AppError;
+use crate::models::pagination::{Cursor, CursorDirection, CursorPage, CursorParams, Page, PageParams};
+use crate::models::post::{CreatePostDto, PostView, PostViewRow, UpdatePostDto};
+use crate::models::moderation::ModeratorAction;
+use crate::models::user::Actor;
+use crate::policy::{Action, Decision, Policy, PostPolicy, PostResource};
+use crate::services::moderation_service::record_action;
+use crate::services::MutationOutcome;
 
 // PostService는 게시글 관련 기능을 제공하는 서비스 구조체입니다.
 pub struct PostService {
-    db: PgPool,  // 데이터베이스 연결 풀
+    db: PgPool,          // 데이터베이스 연결 풀
+    policy: PostPolicy,  // 수정/삭제 권한 정책
 }
 
 impl PostService {
     // 새로운 PostService 인스턴스를 생성합니다.
     pub fn new(db: PgPool) -> Self {
-        Self { db }
+        Self { db, policy: PostPolicy }
     }
 
     // 새 게시글을 생성합니다.
-    pub async fn create_post(&self, dto: CreatePostDto, author_id: Uuid) -> Result<Post, sqlx::Error> {
-        // 게시글을 데이터베이스에 저장
+    // 이메일 인증을 마치지 않은 사용자는 게시글을 작성할 수 없습니다.
+    pub async fn create_post(&self, dto: CreatePostDto, author_id: Uuid) -> Result<PostView, AppError> {
+        let verified = sqlx::query_scalar!(
+            r#"SELECT email_verified_at IS NOT NULL AS "verified!" FROM users WHERE id = $1"#,
+            author_id
+        )
+        .fetch_optional(&self.db)
+        .await?;
+        if verified != Some(true) {
+            return Err(AppError::Forbidden("Verify your email address before creating posts".to_string()));
+        }
+
+        // 게시글을 저장하고 같은 쿼리에서 작성자 정보를 함께 조회합니다.
         let post = sqlx::query_as!(
-            Post,
+            PostViewRow,
             r#"
-            INSERT INTO posts (title, content, author_id)
-            VALUES ($1, $2, $3)
-            RETURNING id, title, content, author_id, created_at, updated_at
+            WITH p AS (
+                INSERT INTO posts (title, content, author_id)
+                VALUES ($1, $2, $3)
+                RETURNING id, title, content, author_id, created_at, updated_at
+            )
+            SELECT p.id AS "id!", p.title AS "title!", p.content AS "content!",
+                   p.author_id AS "author_id!", u.username AS "author_username?",
+                   (SELECT COUNT(*) FROM comments c WHERE c.post_id = p.id) AS "comment_count!",
+                   p.created_at AS "created_at!", p.updated_at AS "updated_at!"
+            FROM p
+            LEFT JOIN users u ON u.id = p.author_id
             "#,
             dto.title,
             dto.content,
@@ -34,103 +62,212 @@ impl PostService {
         .fetch_one(&self.db)
         .await?;
 
-        Ok(post)
+        Ok(post.into())
     }
 
     // 특정 게시글을 조회합니다.
-    pub async fn get_post(&self, post_id: Uuid) -> Result<Option<Post>, sqlx::Error> {
+    pub async fn get_post(&self, post_id: Uuid) -> Result<Option<PostView>, AppError> {
         let post = sqlx::query_as!(
-            Post,
+            PostViewRow,
             r#"
-            SELECT id, title, content, author_id, created_at, updated_at
-            FROM posts
-            WHERE id = $1
+            SELECT p.id, p.title, p.content, p.author_id, u.username AS "author_username?",
+                   (SELECT COUNT(*) FROM comments c WHERE c.post_id = p.id) AS "comment_count!",
+                   p.created_at, p.updated_at
+            FROM posts p
+            LEFT JOIN users u ON u.id = p.author_id
+            WHERE p.id = $1
             "#,
             post_id
         )
         .fetch_optional(&self.db)
         .await?;
 
-        Ok(post)
+        Ok(post.map(PostView::from))
     }
 
     // 게시글 목록을 페이지네이션하여 조회합니다.
-    pub async fn get_posts(&self, page: i64, per_page: i64) -> Result<Vec<Post>, sqlx::Error> {
-        let offset = (page - 1) * per_page;
+    // 전체 게시글 수를 함께 조회하여 페이지 정보를 계산합니다.
+    pub async fn get_posts(&self, params: PageParams) -> Result<Page<PostView>, AppError> {
         let posts = sqlx::query_as!(
-            Post,
+            PostViewRow,
             r#"
-            SELECT id, title, content, author_id, created_at, updated_at
-            FROM posts
-            ORDER BY created_at DESC
+            SELECT p.id, p.title, p.content, p.author_id, u.username AS "author_username?",
+                   (SELECT COUNT(*) FROM comments c WHERE c.post_id = p.id) AS "comment_count!",
+                   p.created_at, p.updated_at
+            FROM posts p
+            LEFT JOIN users u ON u.id = p.author_id
+            ORDER BY p.created_at DESC, p.id DESC
             LIMIT $1 OFFSET $2
             "#,
-            per_page,
-            offset
+            params.per_page,
+            params.offset()
         )
         .fetch_all(&self.db)
         .await?;
 
-        Ok(posts)
+        let total = sqlx::query_scalar!(r#"SELECT COUNT(*) AS "count!" FROM posts"#)
+            .fetch_one(&self.db)
+            .await?;
+
+        let posts = posts.into_iter().map(PostView::from).collect();
+        Ok(Page::new(posts, total, params).with_next_cursor(post_cursor))
+    }
+
+    // 게시글 목록을 커서 기준으로 조회합니다.
+    // created_at 조건은 idx_posts_created_at 인덱스 범위 검색으로 처리되고,
+    // 같은 시각의 게시글은 id로 순서를 정합니다.
+    pub async fn get_posts_by_cursor(&self, params: CursorParams) -> Result<CursorPage<PostView>, AppError> {
+        let posts = match params.direction {
+            CursorDirection::After(cursor) => {
+                sqlx::query_as!(
+                    PostViewRow,
+                    r#"
+                    SELECT p.id, p.title, p.content, p.author_id, u.username AS "author_username?",
+                           (SELECT COUNT(*) FROM comments c WHERE c.post_id = p.id) AS "comment_count!",
+                           p.created_at, p.updated_at
+                    FROM posts p
+                    LEFT JOIN users u ON u.id = p.author_id
+                    WHERE p.created_at <= $1 AND (p.created_at < $1 OR p.id < $2)
+                    ORDER BY p.created_at DESC, p.id DESC
+                    LIMIT $3
+                    "#,
+                    cursor.created_at,
+                    cursor.id,
+                    params.fetch_limit()
+                )
+                .fetch_all(&self.db)
+                .await?
+            }
+            CursorDirection::Before(cursor) => {
+                sqlx::query_as!(
+                    PostViewRow,
+                    r#"
+                    SELECT p.id, p.title, p.content, p.author_id, u.username AS "author_username?",
+                           (SELECT COUNT(*) FROM comments c WHERE c.post_id = p.id) AS "comment_count!",
+                           p.created_at, p.updated_at
+                    FROM posts p
+                    LEFT JOIN users u ON u.id = p.author_id
+                    WHERE p.created_at >= $1 AND (p.created_at > $1 OR p.id > $2)
+                    ORDER BY p.created_at ASC, p.id ASC
+                    LIMIT $3
+                    "#,
+                    cursor.created_at,
+                    cursor.id,
+                    params.fetch_limit()
+                )
+                .fetch_all(&self.db)
+                .await?
+            }
+        };
+
+        let posts = posts.into_iter().map(PostView::from).collect();
+        Ok(CursorPage::from_rows(posts, params, post_cursor))
     }
 
     // 게시글을 수정합니다.
-    // 작성자만 수정할 수 있습니다.
+    // 권한은 PostPolicy로 확인하며, 게시글이 없으면 NotFound, 권한이 없으면 Forbidden을 반환합니다.
+    // 모더레이터 권한으로 다른 사용자의 게시글을 수정하면 모더레이터 작업으로 기록합니다.
     pub async fn update_post(
         &self,
         post_id: Uuid,
         dto: UpdatePostDto,
-        author_id: Uuid,
-    ) -> Result<Option<Post>, sqlx::Error> {
-        // 게시글 존재 여부와 작성자 확인
-        let post = match sqlx::query_as!(
-            Post,
-            "SELECT * FROM posts WHERE id = $1",
-            post_id
-        )
-        .fetch_optional(&self.db)
-        .await?
-        {
-            Some(post) if post.author_id == author_id => post,
-            _ => return Ok(None),
+        actor: &Actor,
+    ) -> Result<MutationOutcome<PostView>, AppError> {
+        let mut tx = self.db.begin().await?;
+
+        // 게시글을 잠근 뒤 권한을 확인하여, 확인과 수정 사이에 게시글이 바뀌지 않도록 합니다.
+        let Some(post) = lock_post(&mut tx, post_id).await? else {
+            return Ok(MutationOutcome::NotFound);
         };
+        let decision = self.policy.can(actor, Action::Update, &post);
+        if decision == Decision::Deny {
+            return Ok(MutationOutcome::Forbidden);
+        }
 
         // 게시글 수정
         let updated = sqlx::query_as!(
-            Post,
+            PostViewRow,
             r#"
-            UPDATE posts
-            SET
-                title = COALESCE($1, title),
-                content = COALESCE($2, content),
-                updated_at = CURRENT_TIMESTAMP
-            WHERE id = $3
-            RETURNING id, title, content, author_id, created_at, updated_at
+            WITH p AS (
+                UPDATE posts
+                SET
+                    title = COALESCE($1, title),
+                    content = COALESCE($2, content),
+                    updated_at = CURRENT_TIMESTAMP
+                WHERE id = $3
+                RETURNING id, title, content, author_id, created_at, updated_at
+            )
+            SELECT p.id AS "id!", p.title AS "title!", p.content AS "content!",
+                   p.author_id AS "author_id!", u.username AS "author_username?",
+                   (SELECT COUNT(*) FROM comments c WHERE c.post_id = p.id) AS "comment_count!",
+                   p.created_at AS "created_at!", p.updated_at AS "updated_at!"
+            FROM p
+            LEFT JOIN users u ON u.id = p.author_id
             "#,
             dto.title,
             dto.content,
             post_id
         )
-        .fetch_one(&self.db)
+        .fetch_one(&mut *tx)
         .await?;
 
-        Ok(Some(updated))
+        if decision.is_moderation() {
+            record_action(&mut tx, actor.id, ModeratorAction::UpdatePost, post_id, post.author_id).await?;
+        }
+        tx.commit().await?;
+
+        Ok(MutationOutcome::Done(updated.into()))
     }
 
     // 게시글을 삭제합니다.
-    // 작성자만 삭제할 수 있습니다.
-    pub async fn delete_post(&self, post_id: Uuid, author_id: Uuid) -> Result<bool, sqlx::Error> {
-        let result = sqlx::query!(
-            r#"
-            DELETE FROM posts
-            WHERE id = $1 AND author_id = $2
-            "#,
-            post_id,
-            author_id
-        )
-        .execute(&self.db)
-        .await?;
+    // 권한은 PostPolicy로 확인하며, 게시글이 없으면 NotFound, 권한이 없으면 Forbidden을 반환합니다.
+    // 모더레이터 권한으로 다른 사용자의 게시글을 삭제하면 모더레이터 작업으로 기록합니다.
+    pub async fn delete_post(&self, post_id: Uuid, actor: &Actor) -> Result<MutationOutcome<()>, AppError> {
+        let mut tx = self.db.begin().await?;
+
+        let Some(post) = lock_post(&mut tx, post_id).await? else {
+            return Ok(MutationOutcome::NotFound);
+        };
+        let decision = self.policy.can(actor, Action::Delete, &post);
+        if decision == Decision::Deny {
+            return Ok(MutationOutcome::Forbidden);
+        }
 
-        Ok(result.rows_affected() > 0)
+        sqlx::query!("DELETE FROM posts WHERE id = $1", post_id)
+            .execute(&mut *tx)
+            .await?;
+
+        if decision.is_moderation() {
+            record_action(&mut tx, actor.id, ModeratorAction::DeletePost, post_id, post.author_id).await?;
+        }
+        tx.commit().await?;
+
+        Ok(MutationOutcome::Done(()))
     }
 }
+
+// 게시글을 잠그고 권한 판단에 필요한 정보를 조회합니다. 게시글이 없으면 None을 반환합니다.
+async fn lock_post(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    post_id: Uuid,
+) -> Result<Option<PostResource>, AppError> {
+    let post = sqlx::query_as!(
+        PostResource,
+        r#"
+        SELECT author_id
+        FROM posts
+        WHERE id = $1
+        FOR UPDATE
+        "#,
+        post_id
+    )
+    .fetch_optional(&mut **tx)
+    .await?;
+
+    Ok(post)
+}
+
+// 게시글의 정렬 위치를 나타내는 커서를 만듭니다.
+fn post_cursor(post: &PostView) -> Cursor {
+    Cursor::new(post.created_at, post.id)
+}
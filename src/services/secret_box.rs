@@ -0,0 +1,62 @@
+// secret_box.rs
+// TOTP 비밀 키처럼 서버가 원문을 다시 꺼내 써야 하는 값을 AES-256-GCM으로 암호화합니다.
+// 데이터베이스만 유출되어서는 값을 복호화할 수 없도록, 키(mfa.encryption_key)는 설정으로만 전달합니다.
+//
+// 암호문은 "v1:" 뒤에 base64(nonce || 암호문 || 인증 태그) 형식으로 저장합니다.
+// 암호화할 때 함께 넘긴 연관 데이터(예: 사용자 ID)가 다르면 복호화에 실패하므로,
+// 다른 사용자의 행으로 암호문을 옮겨도 사용할 수 없습니다.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use rand::{rngs::OsRng, RngCore};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+
+use crate::errors::AppError;
+
+// 암호문 형식의 버전 표시입니다. 키나 알고리즘을 바꿀 때 구분하는 데 사용합니다.
+const VERSION_PREFIX: &str = "v1:";
+
+// SecretBox는 하나의 키로 값을 암호화하고 복호화합니다.
+pub struct SecretBox {
+    key: LessSafeKey,
+}
+
+impl SecretBox {
+    pub fn new(key: &[u8; 32]) -> Self {
+        let key = UnboundKey::new(&AES_256_GCM, key).expect("AES-256-GCM accepts 32-byte keys");
+        Self { key: LessSafeKey::new(key) }
+    }
+
+    // 값을 암호화합니다. 호출할 때마다 새 nonce를 사용합니다.
+    pub fn seal(&self, plaintext: &[u8], associated_data: &[u8]) -> Result<String, AppError> {
+        let mut nonce = [0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut nonce);
+
+        let mut in_out = plaintext.to_vec();
+        self.key
+            .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::from(associated_data), &mut in_out)
+            .map_err(|_| AppError::Internal("Failed to encrypt secret".to_string()))?;
+
+        let mut sealed = nonce.to_vec();
+        sealed.extend_from_slice(&in_out);
+        Ok(format!("{}{}", VERSION_PREFIX, STANDARD.encode(sealed)))
+    }
+
+    // 암호문을 복호화합니다. 키나 연관 데이터가 다르거나 암호문이 변조되었으면 실패합니다.
+    pub fn open(&self, sealed: &str, associated_data: &[u8]) -> Result<Vec<u8>, AppError> {
+        let invalid = || AppError::Internal("Failed to decrypt secret".to_string());
+
+        let encoded = sealed.strip_prefix(VERSION_PREFIX).ok_or_else(invalid)?;
+        let mut bytes = STANDARD.decode(encoded).map_err(|_| invalid())?;
+        if bytes.len() < NONCE_LEN {
+            return Err(invalid());
+        }
+        let mut ciphertext = bytes.split_off(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(&bytes).map_err(|_| invalid())?;
+
+        let plaintext = self
+            .key
+            .open_in_place(nonce, Aad::from(associated_data), &mut ciphertext)
+            .map_err(|_| invalid())?;
+        Ok(plaintext.to_vec())
+    }
+}
@@ -0,0 +1,91 @@
+// session_cache.rs
+// 액세스 토큰 폐기 여부를 확인할 때 사용하는 프로세스 내 캐시입니다.
+// 사용자별 token_version과 폐기된 jti 목록을 일정 시간(ttl) 동안 보관하여,
+// 인증이 필요한 요청마다 데이터베이스를 조회하지 않도록 합니다.
+//
+// 같은 프로세스에서의 로그아웃은 즉시 캐시를 무효화하므로 바로 반영되고,
+// 다른 프로세스에서의 로그아웃은 최대 ttl만큼 늦게 반영될 수 있습니다.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+// 캐시 정리를 시도할 항목 수입니다.
+const PRUNE_THRESHOLD: usize = 10_000;
+
+// 사용자의 토큰 폐기 상태입니다.
+#[derive(Debug, Clone)]
+pub struct SessionState {
+    pub token_version: i32,         // 현재 유효한 토큰 버전
+    pub revoked_jtis: HashSet<Uuid>, // 만료되지 않은 폐기 토큰의 jti
+}
+
+// 사용자별 캐시 항목입니다.
+// 무효화된 뒤에도 세대(generation)를 기억하기 위해 상태가 없는 항목(tombstone)으로 남겨 둡니다.
+struct Entry {
+    state: Option<SessionState>,  // 캐시된 상태 (무효화되면 None)
+    generation: u64,              // invalidate가 호출될 때마다 증가하는 세대
+    updated_at: Instant,          // 마지막으로 저장하거나 무효화한 시각
+}
+
+// 사용자 ID별 SessionState 캐시입니다.
+pub struct SessionCache {
+    ttl: Duration,
+    entries: RwLock<HashMap<Uuid, Entry>>,
+}
+
+impl SessionCache {
+    // ttl이 0이면 캐시를 사용하지 않습니다.
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: RwLock::new(HashMap::new()),
+        }
+    }
+
+    // 만료되지 않은 캐시 항목을 반환합니다.
+    pub fn get(&self, user_id: Uuid) -> Option<SessionState> {
+        let entries = self.entries.read().unwrap_or_else(|e| e.into_inner());
+        entries
+            .get(&user_id)
+            .filter(|entry| entry.updated_at.elapsed() < self.ttl)
+            .and_then(|entry| entry.state.clone())
+    }
+
+    // 사용자의 현재 세대를 반환합니다.
+    // 데이터베이스에서 상태를 읽기 전에 호출하고, 그 값을 insert에 전달합니다.
+    pub fn generation(&self, user_id: Uuid) -> u64 {
+        let entries = self.entries.read().unwrap_or_else(|e| e.into_inner());
+        entries.get(&user_id).map_or(0, |entry| entry.generation)
+    }
+
+    // 사용자의 상태를 캐시에 저장합니다.
+    // 상태를 읽는 동안 invalidate가 호출되어 세대가 바뀌었다면, 이미 오래된 상태이므로 저장하지 않습니다.
+    pub fn insert(&self, user_id: Uuid, state: SessionState, generation: u64) {
+        if self.ttl.is_zero() {
+            return;
+        }
+        let mut entries = self.entries.write().unwrap_or_else(|e| e.into_inner());
+        // 항목이 많아지면 만료된 항목과 오래된 tombstone을 정리합니다.
+        if entries.len() >= PRUNE_THRESHOLD {
+            let ttl = self.ttl;
+            entries.retain(|_, entry| entry.updated_at.elapsed() < ttl);
+        }
+        if entries.get(&user_id).map_or(0, |entry| entry.generation) != generation {
+            return;
+        }
+        entries.insert(user_id, Entry { state: Some(state), generation, updated_at: Instant::now() });
+    }
+
+    // 로그아웃 등으로 상태가 바뀐 사용자의 캐시 항목을 무효화합니다.
+    // 세대를 올려 두므로, 무효화 전에 읽기 시작한 상태는 캐시에 다시 저장되지 않습니다.
+    pub fn invalidate(&self, user_id: Uuid) {
+        if self.ttl.is_zero() {
+            return;
+        }
+        let mut entries = self.entries.write().unwrap_or_else(|e| e.into_inner());
+        let generation = entries.get(&user_id).map_or(0, |entry| entry.generation) + 1;
+        entries.insert(user_id, Entry { state: None, generation, updated_at: Instant::now() });
+    }
+}
@@ -0,0 +1,91 @@
+// totp.rs
+// 인증 앱(Google Authenticator 등)과 호환되는 TOTP(RFC 6238) 코드를 만들고 검증합니다.
+// HMAC-SHA1, 6자리, 30초 간격을 사용하며, 이는 대부분의 인증 앱의 기본값입니다.
+
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use rand::{rngs::OsRng, RngCore};
+use sha1::Sha1;
+
+// 코드가 바뀌는 간격(초)입니다.
+pub const STEP_SECS: i64 = 30;
+// 코드 자릿수입니다.
+pub const DIGITS: usize = 6;
+// 비밀 키의 바이트 수입니다 (RFC 4226 권장 160비트).
+const SECRET_BYTES: usize = 20;
+
+// 암호학적으로 안전한 난수로 새 비밀 키를 만듭니다.
+pub fn generate_secret() -> Vec<u8> {
+    let mut secret = vec![0u8; SECRET_BYTES];
+    OsRng.fill_bytes(&mut secret);
+    secret
+}
+
+// 인증 앱에 직접 입력할 수 있도록 비밀 키를 base32로 인코딩합니다.
+pub fn encode_secret(secret: &[u8]) -> String {
+    BASE32_NOPAD.encode(secret)
+}
+
+// 인증 앱이 QR 코드로 읽을 수 있는 otpauth:// URI를 만듭니다.
+pub fn provisioning_uri(issuer: &str, account: &str, secret: &[u8]) -> String {
+    format!(
+        "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+        percent_encode(issuer),
+        percent_encode(account),
+        encode_secret(secret),
+        percent_encode(issuer),
+        DIGITS,
+        STEP_SECS
+    )
+}
+
+// Unix 시각이 속한 시간 단계를 반환합니다.
+pub fn step_at(unix_secs: i64) -> i64 {
+    unix_secs.div_euclid(STEP_SECS)
+}
+
+// 주어진 시간 단계의 코드를 만듭니다 (RFC 4226의 HOTP).
+pub fn code_at(secret: &[u8], step: i64) -> String {
+    let mut mac = Hmac::<Sha1>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(&(step as u64).to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    // 동적 절단(dynamic truncation): 마지막 바이트의 하위 4비트가 가리키는 위치에서 31비트를 꺼냅니다.
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let value = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+    format!("{:0width$}", value % 10u32.pow(DIGITS as u32), width = DIGITS)
+}
+
+// 코드가 현재 시각 앞뒤 skew_steps 단계 안의 코드와 일치하면 그 시간 단계를 반환합니다.
+// 같은 코드가 다시 사용되지 않도록, 호출한 쪽에서 반환된 단계를 기록해야 합니다.
+pub fn verify(secret: &[u8], code: &str, unix_secs: i64, skew_steps: u32) -> Option<i64> {
+    let code = code.trim();
+    if code.len() != DIGITS || !code.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let current = step_at(unix_secs);
+    let skew = skew_steps as i64;
+    (current - skew..=current + skew).find(|&step| constant_time_eq(code_at(secret, step).as_bytes(), code.as_bytes()))
+}
+
+// 비교 시간으로 일치하는 자릿수가 드러나지 않도록 모든 바이트를 비교합니다.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+// URI 경로와 쿼리에 넣을 수 있도록 영문자, 숫자와 일부 기호 외의 바이트를 %XX로 인코딩합니다.
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'@' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
@@ -0,0 +1,169 @@
+// user_service.rs
+// 로그인한 사용자가 자신의 계정 정보를 조회하고 수정하는 서비스입니다.
+// 인증(로그인, 토큰 관리)은 AuthService가, 계정 정보 관리는 UserService가 담당합니다.
+//
+// - 사용자 이름은 대소문자를 구분하지 않고 유일해야 합니다.
+// - 이메일을 바꾸려면 현재 비밀번호가 필요하며, 바꾸면 인증 상태가 초기화되고 새 주소로 인증 메일을 보냅니다.
+// - 비밀번호를 바꾸려면 현재 비밀번호가 필요하며, 바꾸면 기존 세션이 모두 폐기됩니다.
+// - 현재 비밀번호 확인은 로그인과 같은 계정 단위 실패 제한(LoginThrottle)을 거칩니다.
+
+use sqlx::PgPool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::errors::AppError;
+use crate::models::user::{normalize_email, ChangePasswordDto, Role, UpdateProfileDto, User};
+use crate::models::validation::validate_password;
+use crate::services::auth_service::{revoke_sessions, username_taken};
+use crate::services::email_verification_service::EmailVerificationService;
+use crate::services::login_throttle::LoginThrottle;
+use crate::services::password_hasher::PasswordHasher;
+use crate::services::session_cache::SessionCache;
+
+// UserService는 계정 정보 조회와 수정 기능을 제공합니다.
+pub struct UserService {
+    db: PgPool,                                     // 데이터베이스 연결 풀
+    config: Arc<Config>,                            // 애플리케이션 설정 (비밀번호 규칙)
+    passwords: PasswordHasher,                      // 비밀번호 해시 및 확인
+    throttle: LoginThrottle,                        // 현재 비밀번호 확인 실패 기록 및 제한
+    sessions: Arc<SessionCache>,                    // AuthService와 공유하는 토큰 폐기 상태 캐시
+    verification: Arc<EmailVerificationService>,    // 이메일 변경 시 인증 메일 발송
+}
+
+impl UserService {
+    // 새로운 UserService 인스턴스를 생성합니다.
+    // 비밀번호 변경이 즉시 반영되도록 sessions에는 AuthService::sessions()를 넘겨야 합니다.
+    pub fn new(
+        db: PgPool,
+        config: Arc<Config>,
+        sessions: Arc<SessionCache>,
+        verification: Arc<EmailVerificationService>,
+    ) -> Self {
+        let passwords = PasswordHasher::new(&config.security);
+        let throttle = LoginThrottle::new(db.clone(), config.clone());
+        Self { db, config, passwords, throttle, sessions, verification }
+    }
+
+    // 사용자 정보를 조회합니다.
+    pub async fn get_user(&self, user_id: Uuid) -> Result<User, AppError> {
+        sqlx::query_as!(
+            User,
+            r#"
+            SELECT id, username, email, password_hash, token_version, role AS "role: Role", email_verified_at, created_at, updated_at
+            FROM users WHERE id = $1
+            "#,
+            user_id
+        )
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))
+    }
+
+    // 사용자 이름과 이메일을 수정합니다.
+    // 보낸 필드 중 실제로 바뀐 값만 반영하며, 바뀐 것이 없으면 현재 정보를 그대로 반환합니다.
+    // 이메일은 비밀번호 재설정 메일을 받는 주소이므로, 탈취한 세션만으로 계정을 가져갈 수 없도록 현재 비밀번호를 확인합니다.
+    // 이메일이 바뀌면 인증 상태를 초기화하고 새 주소로 인증 메일을 보냅니다.
+    pub async fn update_profile(&self, user_id: Uuid, dto: UpdateProfileDto) -> Result<User, AppError> {
+        let current = self.get_user(user_id).await?;
+
+        let username = dto.username.map(|u| u.trim().to_string()).filter(|u| *u != current.username);
+        let email = dto.email.map(|e| normalize_email(&e)).filter(|e| *e != current.email);
+        if username.is_none() && email.is_none() {
+            return Ok(current);
+        }
+
+        if email.is_some() {
+            let Some(current_password) = &dto.current_password else {
+                return Err(AppError::validation("current_password", "current password is required to change email"));
+            };
+            self.verify_current_password(&current, current_password).await?;
+        }
+
+        if let Some(username) = &username {
+            if username_taken(&self.db, username, Some(user_id)).await? {
+                return Err(AppError::Conflict("Username already exists".to_string()));
+            }
+        }
+        if let Some(email) = &email {
+            let taken = sqlx::query_scalar!(
+                r#"SELECT EXISTS (SELECT 1 FROM users WHERE LOWER(email) = $1 AND id <> $2) AS "taken!""#,
+                email,
+                user_id
+            )
+            .fetch_one(&self.db)
+            .await?;
+            if taken {
+                return Err(AppError::Conflict("Email already exists".to_string()));
+            }
+        }
+
+        // 위 확인과 수정 사이에 다른 사용자가 같은 값을 차지하면 unique 인덱스 위반으로 Conflict가 반환됩니다.
+        let user = sqlx::query_as!(
+            User,
+            r#"
+            UPDATE users
+            SET username = COALESCE($2, username),
+                email = COALESCE($3, email),
+                email_verified_at = CASE WHEN $3::VARCHAR IS NULL THEN email_verified_at END,
+                updated_at = CURRENT_TIMESTAMP
+            WHERE id = $1
+            RETURNING id, username, email, password_hash, token_version, role AS "role: Role", email_verified_at, created_at, updated_at
+            "#,
+            user_id,
+            username,
+            email
+        )
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        // 메일 발송에 실패해도 변경은 완료된 것이므로, 사용자는 resend-verification으로 다시 받을 수 있습니다.
+        if email.is_some() {
+            log::info!("user {} changed their email address", user.id);
+            if let Err(e) = self.verification.send_verification(&user).await {
+                log::warn!("failed to send verification email to user {}: {}", user.id, e);
+            }
+        }
+        Ok(user)
+    }
+
+    // 현재 비밀번호를 확인한 뒤 새 비밀번호로 바꿉니다.
+    // 현재 비밀번호가 틀리면 current_password 필드의 Validation 에러를 반환합니다.
+    // 비밀번호를 바꾸면 기존 액세스 토큰, 리프레시 토큰, API 토큰을 모두 폐기하므로, 클라이언트는 다시 로그인해야 합니다.
+    pub async fn change_password(&self, user_id: Uuid, dto: ChangePasswordDto) -> Result<(), AppError> {
+        let user = self.get_user(user_id).await?;
+        self.verify_current_password(&user, &dto.current_password).await?;
+
+        validate_password(&dto.password, &self.config.security)?;
+        let password_hash = self.passwords.hash(&dto.password).await?;
+
+        let mut tx = self.db.begin().await?;
+        sqlx::query!(
+            "UPDATE users SET password_hash = $2, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
+            user_id,
+            password_hash
+        )
+        .execute(&mut *tx)
+        .await?;
+        revoke_sessions(&mut tx, user_id).await?;
+        tx.commit().await?;
+
+        self.sessions.invalidate(user_id);
+        log::info!("user {} changed their password", user_id);
+        Ok(())
+    }
+
+    // 현재 비밀번호를 확인합니다. 틀리면 current_password 필드의 Validation 에러를 반환합니다.
+    // 탈취한 세션으로 비밀번호를 대입해 볼 수 없도록, 실패는 로그인 실패와 같은 계정 기록에 남기고
+    // 계정이 제한 중이면 비밀번호를 확인하지 않고 RateLimited를 반환합니다.
+    async fn verify_current_password(&self, user: &User, password: &str) -> Result<(), AppError> {
+        let attempt = self.throttle.reserve(&user.email, None).await?;
+        if !self.passwords.verify(password, &user.password_hash).await? {
+            self.throttle.record_failure(attempt, Some(user.id)).await?;
+            return Err(AppError::validation("current_password", "current password is incorrect"));
+        }
+        self.throttle.record_success(attempt).await?;
+        Ok(())
+    }
+}
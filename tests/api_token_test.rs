@@ -0,0 +1,326 @@
+use actix_web::{http::{header, StatusCode}, test, web, App};
+use chrono::{Duration, Utc};
+use rust_study::{
+    errors::AppError,
+    handlers::{moderation_handler, post_handler, user_handler},
+    middleware::{auth_middleware::Auth, require_role::RequireRole},
+    models::{
+        api_token::{CreateApiTokenDto, Scope},
+        user::{CreateUserDto, LoginDto, LoginOutcome, Role},
+    },
+    services::{
+        api_token_service::ApiTokenService, auth_service::AuthService, moderation_service::ModerationService,
+        opaque_token, post_service::PostService,
+    },
+};
+use serde_json::json;
+use serial_test::serial;
+use test_context::test_context;
+use uuid::Uuid;
+
+mod common;
+use common::TestDb;
+
+async fn create_test_app(
+    pool: sqlx::PgPool,
+) -> impl actix_web::dev::Service<actix_http::Request, Response = actix_web::dev::ServiceResponse, Error = actix_web::Error> {
+    let config = common::test_config();
+    test::init_service(
+        App::new()
+            .app_data(web::Data::from(config.clone()))
+            .app_data(web::Data::new(AuthService::new(pool.clone(), config)))
+            .app_data(web::Data::new(ApiTokenService::new(pool.clone())))
+            .app_data(web::Data::new(PostService::new(pool.clone())))
+            .app_data(web::Data::new(ModerationService::new(pool)))
+            .service(
+                web::scope("/api")
+                    .service(
+                        web::scope("/users/me")
+                            .wrap(Auth::required())
+                            .route("/tokens", web::post().to(user_handler::create_api_token))
+                            .route("/tokens", web::get().to(user_handler::list_api_tokens))
+                            .route("/tokens/{token_id}", web::delete().to(user_handler::revoke_api_token)),
+                    )
+                    .service(
+                        web::scope("/posts")
+                            .route("", web::post().to(post_handler::create_post).wrap(Auth::required()))
+                            .route("", web::get().to(post_handler::get_posts).wrap(Auth::optional()))
+                            .route("/{post_id}", web::delete().to(post_handler::delete_post).wrap(Auth::required())),
+                    )
+                    .service(
+                        web::scope("/moderation")
+                            .wrap(RequireRole::new(Role::Moderator))
+                            .wrap(Auth::required())
+                            .route("/actions", web::get().to(moderation_handler::get_actions)),
+                    ),
+            ),
+    )
+    .await
+}
+
+// 이메일 인증을 마친 사용자를 만들어 로그인하고 (사용자 ID, 액세스 토큰)을 반환합니다.
+async fn login(pool: &sqlx::PgPool, username: &str) -> (Uuid, String) {
+    let auth = AuthService::new(pool.clone(), common::test_config());
+    let email = format!("{}@example.com", username);
+    let user = auth
+        .register(CreateUserDto {
+            username: username.to_string(),
+            email: email.clone(),
+            password: "password123".to_string(),
+        })
+        .await
+        .unwrap();
+    common::verify_user_email(pool, user.id).await;
+    let response = auth
+        .login(LoginDto { email, password: "password123".to_string() })
+        .await
+        .and_then(LoginOutcome::tokens)
+        .unwrap();
+    (user.id, response.token)
+}
+
+fn token_dto(scopes: Vec<Scope>) -> CreateApiTokenDto {
+    CreateApiTokenDto {
+        name: "deploy bot".to_string(),
+        scopes,
+        expires_at: None,
+    }
+}
+
+fn bearer(token: &str) -> (header::HeaderName, String) {
+    (header::AUTHORIZATION, format!("Bearer {}", token))
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_token_is_shown_once_and_stored_hashed(ctx: &TestDb) {
+    let service = ApiTokenService::new(ctx.pool.clone());
+    let (user_id, _) = login(&ctx.pool, "tokenuser").await;
+
+    let created = service
+        .create(user_id, token_dto(vec![Scope::PostsWrite, Scope::PostsRead, Scope::PostsWrite]))
+        .await
+        .unwrap();
+    assert!(created.token.starts_with("rsb_"));
+    assert!(created.token.starts_with(&created.api_token.prefix));
+    assert_eq!(created.api_token.scopes, vec![Scope::PostsRead, Scope::PostsWrite]);
+
+    let stored = sqlx::query!("SELECT token_hash, scopes FROM api_tokens WHERE id = $1", created.api_token.id)
+        .fetch_one(&ctx.pool)
+        .await
+        .unwrap();
+    assert_eq!(stored.token_hash, opaque_token::hash(&created.token));
+    assert_eq!(stored.scopes, vec!["posts:read", "posts:write"]);
+
+    // The listing identifies the token by its prefix but never returns the token itself
+    let tokens = service.list(user_id).await.unwrap();
+    assert_eq!(tokens.len(), 1);
+    let listed = serde_json::to_value(&tokens[0]).unwrap();
+    assert_eq!(listed["prefix"], created.api_token.prefix);
+    assert_eq!(listed["scopes"], json!(["posts:read", "posts:write"]));
+    assert!(listed.get("token").is_none());
+    assert!(!listed.to_string().contains(&created.token));
+
+    let result = service
+        .create(user_id, CreateApiTokenDto {
+            expires_at: Some(Utc::now() - Duration::minutes(1)),
+            ..token_dto(vec![Scope::PostsRead])
+        })
+        .await;
+    assert!(matches!(result, Err(AppError::Validation { .. })));
+}
+
+#[test_context(TestDb)]
+#[actix_web::test]
+#[serial]
+async fn test_scopes_are_enforced(ctx: &TestDb) {
+    let app = create_test_app(ctx.pool.clone()).await;
+    let service = ApiTokenService::new(ctx.pool.clone());
+    let (user_id, _) = login(&ctx.pool, "botowner").await;
+    let reader = service.create(user_id, token_dto(vec![Scope::PostsRead])).await.unwrap().token;
+    let writer = service.create(user_id, token_dto(vec![Scope::PostsWrite])).await.unwrap().token;
+    let new_post = json!({ "title": "From a bot", "content": "Posted with an API token" });
+
+    let req = test::TestRequest::get().uri("/api/posts").insert_header(bearer(&reader)).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    let req = test::TestRequest::post()
+        .uri("/api/posts")
+        .insert_header(bearer(&reader))
+        .set_json(&new_post)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["message"], "API token is missing the posts:write scope");
+
+    let req = test::TestRequest::post()
+        .uri("/api/posts")
+        .insert_header(bearer(&writer))
+        .set_json(&new_post)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["author"]["id"], user_id.to_string());
+
+    // posts:write does not imply posts:read
+    let req = test::TestRequest::get().uri("/api/posts").insert_header(bearer(&writer)).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::FORBIDDEN);
+
+    let last_used_at = sqlx::query_scalar!("SELECT last_used_at FROM api_tokens WHERE token_hash = $1", opaque_token::hash(&writer))
+        .fetch_one(&ctx.pool)
+        .await
+        .unwrap();
+    assert!(last_used_at.is_some());
+}
+
+#[test_context(TestDb)]
+#[actix_web::test]
+#[serial]
+async fn test_revoked_and_expired_tokens_are_rejected(ctx: &TestDb) {
+    let app = create_test_app(ctx.pool.clone()).await;
+    let service = ApiTokenService::new(ctx.pool.clone());
+    let (user_id, _) = login(&ctx.pool, "revoker").await;
+    let (other_id, _) = login(&ctx.pool, "someoneelse").await;
+
+    let created = service.create(user_id, token_dto(vec![Scope::PostsRead])).await.unwrap();
+    let req = test::TestRequest::get().uri("/api/posts").insert_header(bearer(&created.token)).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    // Only the owner can revoke a token
+    let result = service.revoke(other_id, created.api_token.id).await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+    service.revoke(user_id, created.api_token.id).await.unwrap();
+    assert!(service.list(user_id).await.unwrap().is_empty());
+
+    let req = test::TestRequest::get().uri("/api/posts").insert_header(bearer(&created.token)).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["code"], "TOKEN_REVOKED");
+
+    let expiring = service
+        .create(user_id, CreateApiTokenDto {
+            expires_at: Some(Utc::now() + Duration::hours(1)),
+            ..token_dto(vec![Scope::PostsRead])
+        })
+        .await
+        .unwrap();
+    sqlx::query!("UPDATE api_tokens SET expires_at = CURRENT_TIMESTAMP - INTERVAL '1 second' WHERE id = $1", expiring.api_token.id)
+        .execute(&ctx.pool)
+        .await
+        .unwrap();
+    let req = test::TestRequest::get().uri("/api/posts").insert_header(bearer(&expiring.token)).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["code"], "TOKEN_EXPIRED");
+
+    let req = test::TestRequest::get()
+        .uri("/api/posts")
+        .insert_header(bearer("rsb_not-a-token-that-was-ever-issued"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[test_context(TestDb)]
+#[actix_web::test]
+#[serial]
+async fn test_token_endpoints_require_a_login_session(ctx: &TestDb) {
+    let app = create_test_app(ctx.pool.clone()).await;
+    let (user_id, access_token) = login(&ctx.pool, "moderator").await;
+    sqlx::query!("UPDATE users SET role = 'moderator' WHERE id = $1", user_id)
+        .execute(&ctx.pool)
+        .await
+        .unwrap();
+
+    let req = test::TestRequest::post()
+        .uri("/api/users/me/tokens")
+        .insert_header(bearer(&access_token))
+        .set_json(json!({ "name": "ci", "scopes": ["posts:read", "comments:read"] }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let api_token = body["token"].as_str().unwrap().to_string();
+    assert_eq!(body["name"], "ci");
+
+    let req = test::TestRequest::post()
+        .uri("/api/users/me/tokens")
+        .insert_header(bearer(&access_token))
+        .set_json(json!({ "name": "ci", "scopes": ["admin:everything"] }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::BAD_REQUEST);
+
+    // An API token cannot mint more tokens, manage the account or use role-gated endpoints,
+    // even when its owner is a moderator
+    for req in [
+        test::TestRequest::get().uri("/api/users/me/tokens"),
+        test::TestRequest::post()
+            .uri("/api/users/me/tokens")
+            .set_json(json!({ "name": "escalate", "scopes": ["posts:write"] })),
+        test::TestRequest::get().uri("/api/moderation/actions"),
+    ] {
+        let resp = test::call_service(&app, req.insert_header(bearer(&api_token)).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["message"], "API tokens cannot be used for this endpoint");
+    }
+
+    let req = test::TestRequest::get()
+        .uri("/api/users/me/tokens")
+        .insert_header(bearer(&access_token))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let token_id = body[0]["id"].as_str().unwrap().to_string();
+    assert_eq!(body.as_array().unwrap().len(), 1);
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/users/me/tokens/{}", token_id))
+        .insert_header(bearer(&access_token))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::NO_CONTENT);
+}
+
+#[test_context(TestDb)]
+#[actix_web::test]
+#[serial]
+async fn test_moderator_api_token_cannot_moderate(ctx: &TestDb) {
+    let app = create_test_app(ctx.pool.clone()).await;
+    let (_, author_token) = login(&ctx.pool, "author").await;
+    let (moderator_id, _) = login(&ctx.pool, "moderator").await;
+    sqlx::query!("UPDATE users SET role = 'moderator' WHERE id = $1", moderator_id)
+        .execute(&ctx.pool)
+        .await
+        .unwrap();
+    // The role is carried in the access token, so sign in again after promoting
+    let moderator_token = AuthService::new(ctx.pool.clone(), common::test_config())
+        .login(LoginDto { email: "moderator@example.com".to_string(), password: "password123".to_string() })
+        .await
+        .and_then(LoginOutcome::tokens)
+        .unwrap()
+        .token;
+    let api_token = ApiTokenService::new(ctx.pool.clone())
+        .create(moderator_id, token_dto(vec![Scope::PostsWrite]))
+        .await
+        .unwrap()
+        .token;
+
+    let req = test::TestRequest::post()
+        .uri("/api/posts")
+        .insert_header(bearer(&author_token))
+        .set_json(json!({ "title": "Mine", "content": "Written by the author" }))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let uri = format!("/api/posts/{}", body["id"].as_str().unwrap());
+
+    // A leaked script token acts as a plain user, even when its owner is a moderator
+    let req = test::TestRequest::delete().uri(&uri).insert_header(bearer(&api_token)).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::FORBIDDEN);
+
+    // The moderator's own login session still can
+    let req = test::TestRequest::delete().uri(&uri).insert_header(bearer(&moderator_token)).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::NO_CONTENT);
+}
@@ -2,13 +2,18 @@ use actix_web::{
     test, web, App, HttpResponse,
     http::{header, StatusCode},
 };
+use chrono::Utc;
 use fake::{Fake, Faker};
+use jsonwebtoken::{encode, EncodingKey, Header};
 use rust_study::{
+    handlers::auth_handler,
+    extractors::current_user::CurrentUser,
     middleware::auth_middleware::Auth,
-    models::user::CreateUserDto,
-    services::auth_service::AuthService,
+    models::user::{CreateUserDto, LoginOutcome, Role},
+    services::auth_service::{AuthService, Claims},
 };
 use test_context::test_context;
+use uuid::Uuid;
 
 mod common;
 use common::TestDb;
@@ -17,16 +22,26 @@ async fn protected_route() -> HttpResponse {
     HttpResponse::Ok().finish()
 }
 
+// 선택적 인증 경로에서 핸들러가 받은 사용자를 그대로 응답합니다.
+async fn optional_route(user: Option<CurrentUser>) -> HttpResponse {
+    HttpResponse::Ok().json(user.map(|user| user.id))
+}
+
 async fn create_test_app(
     pool: sqlx::PgPool,
-) -> impl actix_web::dev::Service<actix_http::Request, Response = actix_web::dev::ServiceResponse> {
+) -> impl actix_web::dev::Service<actix_http::Request, Response = actix_web::dev::ServiceResponse, Error = actix_web::Error> {
     test::init_service(
         App::new()
-            .app_data(web::Data::new(AuthService::new(pool)))
+            .app_data(web::Data::new(AuthService::new(pool, common::test_config())))
             .service(
                 web::scope("/api")
-                    .wrap(Auth)
+                    .wrap(Auth::required())
                     .route("/protected", web::get().to(protected_route)),
+            )
+            .service(
+                web::scope("/public")
+                    .wrap(Auth::optional())
+                    .route("/optional", web::get().to(optional_route)),
             ),
     )
     .await
@@ -43,6 +58,47 @@ async fn test_auth_middleware_no_token(ctx: &TestDb) {
 
     let resp = test::call_service(&app, req).await;
     assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(resp.headers().get(header::WWW_AUTHENTICATE).unwrap(), "Bearer");
+}
+
+#[test_context(TestDb)]
+#[actix_web::test]
+async fn test_auth_middleware_expired_token(ctx: &TestDb) {
+    let app = create_test_app(ctx.pool.clone()).await;
+
+    let config = common::test_config();
+    let now = Utc::now().timestamp();
+    let claims = Claims {
+        sub: Uuid::new_v4().to_string(),
+        exp: now - 3600,
+        nbf: now - 7200,
+        iat: now - 7200,
+        iss: config.jwt.issuer.clone(),
+        aud: config.jwt.audience.clone(),
+        jti: Uuid::new_v4().to_string(),
+        ver: 0,
+        role: Role::User,
+    };
+    let token = encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(config.jwt.secret.as_bytes()),
+    )
+    .unwrap();
+
+    let req = test::TestRequest::get()
+        .uri("/api/protected")
+        .insert_header((header::AUTHORIZATION, format!("Bearer {}", token)))
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        resp.headers().get(header::WWW_AUTHENTICATE).unwrap(),
+        r#"Bearer error="invalid_token", error_description="The access token expired""#
+    );
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["code"], "TOKEN_EXPIRED");
 }
 
 #[test_context(TestDb)]
@@ -62,7 +118,7 @@ async fn test_auth_middleware_invalid_token(ctx: &TestDb) {
 #[test_context(TestDb)]
 #[actix_web::test]
 async fn test_auth_middleware_valid_token(ctx: &TestDb) {
-    let auth_service = AuthService::new(ctx.pool.clone());
+    let auth_service = AuthService::new(ctx.pool.clone(), common::test_config());
     let app = create_test_app(ctx.pool.clone()).await;
 
     // Create a test user and get token
@@ -72,13 +128,14 @@ async fn test_auth_middleware_valid_token(ctx: &TestDb) {
         password: "password123".to_string(),
     };
 
-    let user = auth_service.register(dto.clone()).await.unwrap();
+    auth_service.register(dto.clone()).await.unwrap();
     let auth_response = auth_service
         .login(rust_study::models::user::LoginDto {
             email: dto.email,
             password: dto.password,
         })
         .await
+        .and_then(LoginOutcome::tokens)
         .unwrap();
 
     let req = test::TestRequest::get()
@@ -106,3 +163,102 @@ async fn test_auth_middleware_malformed_token(ctx: &TestDb) {
     let resp = test::call_service(&app, req).await;
     assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
 }
+
+#[test_context(TestDb)]
+#[actix_web::test]
+async fn test_auth_middleware_rejects_logged_out_token(ctx: &TestDb) {
+    let auth_service = web::Data::new(AuthService::new(ctx.pool.clone(), common::test_config()));
+    let app = test::init_service(
+        App::new().app_data(auth_service.clone()).service(
+            web::scope("/api")
+                .wrap(Auth::required())
+                .route("/protected", web::get().to(protected_route))
+                .route("/auth/logout", web::post().to(auth_handler::logout)),
+        ),
+    )
+    .await;
+
+    let dto = CreateUserDto {
+        username: Faker.fake::<String>(),
+        email: format!("{}@example.com", Faker.fake::<String>()),
+        password: "password123".to_string(),
+    };
+    auth_service.register(dto.clone()).await.unwrap();
+    let auth_response = auth_service
+        .login(rust_study::models::user::LoginDto {
+            email: dto.email,
+            password: dto.password,
+        })
+        .await
+        .and_then(LoginOutcome::tokens)
+        .unwrap();
+    let bearer = format!("Bearer {}", auth_response.token);
+
+    let req = test::TestRequest::get()
+        .uri("/api/protected")
+        .insert_header((header::AUTHORIZATION, bearer.clone()))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+    let req = test::TestRequest::post()
+        .uri("/api/auth/logout")
+        .insert_header((header::AUTHORIZATION, bearer.clone()))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::NO_CONTENT);
+
+    // The token is rejected even though it has not expired
+    let req = test::TestRequest::get()
+        .uri("/api/protected")
+        .insert_header((header::AUTHORIZATION, bearer))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["code"], "TOKEN_REVOKED");
+}
+
+#[test_context(TestDb)]
+#[actix_web::test]
+async fn test_auth_middleware_optional(ctx: &TestDb) {
+    let auth_service = AuthService::new(ctx.pool.clone(), common::test_config());
+    let app = create_test_app(ctx.pool.clone()).await;
+
+    // Without a token the request passes through anonymously
+    let req = test::TestRequest::get().uri("/public/optional").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert!(body.is_null());
+
+    // A valid token attaches the user
+    let dto = CreateUserDto {
+        username: Faker.fake::<String>(),
+        email: format!("{}@example.com", Faker.fake::<String>()),
+        password: "password123".to_string(),
+    };
+    let user = auth_service.register(dto.clone()).await.unwrap();
+    let auth_response = auth_service
+        .login(rust_study::models::user::LoginDto {
+            email: dto.email,
+            password: dto.password,
+        })
+        .await
+        .and_then(LoginOutcome::tokens)
+        .unwrap();
+    let req = test::TestRequest::get()
+        .uri("/public/optional")
+        .insert_header((header::AUTHORIZATION, format!("Bearer {}", auth_response.token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body, user.id.to_string());
+
+    // An invalid token is still rejected
+    let req = test::TestRequest::get()
+        .uri("/public/optional")
+        .insert_header((header::AUTHORIZATION, "Bearer invalid.token.here"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+}
@@ -1,11 +1,15 @@
 use crate::common::TestDb;
 use fake::{Fake, Faker};
+use chrono::Utc;
+use jsonwebtoken::{encode, EncodingKey, Header};
 use rust_study::{
-    models::user::{CreateUserDto, LoginDto},
-    services::auth_service::AuthService,
+    errors::{AppError, TokenError},
+    models::user::{AuthResponse, CreateUserDto, LoginDto, LoginOutcome, Role},
+    services::auth_service::{AuthService, Claims},
 };
 use serial_test::serial;
 use test_context::test_context;
+use uuid::Uuid;
 
 mod common;
 
@@ -13,7 +17,7 @@ mod common;
 #[tokio::test]
 #[serial]
 async fn test_register_user(ctx: &TestDb) {
-    let service = AuthService::new(ctx.pool.clone());
+    let service = AuthService::new(ctx.pool.clone(), common::test_config());
     
     let dto = CreateUserDto {
         username: Faker.fake::<String>(),
@@ -24,14 +28,15 @@ async fn test_register_user(ctx: &TestDb) {
     let user = service.register(dto.clone()).await.unwrap();
 
     assert_eq!(user.username, dto.username);
-    assert_eq!(user.email, dto.email);
+    // Emails are stored lowercased so lookups and login throttling agree on the account
+    assert_eq!(user.email, dto.email.to_lowercase());
 }
 
 #[test_context(TestDb)]
 #[tokio::test]
 #[serial]
 async fn test_register_duplicate_email(ctx: &TestDb) {
-    let service = AuthService::new(ctx.pool.clone());
+    let service = AuthService::new(ctx.pool.clone(), common::test_config());
     let email = format!("{}@example.com", Faker.fake::<String>());
     
     let dto1 = CreateUserDto {
@@ -52,11 +57,58 @@ async fn test_register_duplicate_email(ctx: &TestDb) {
     assert!(result.is_err());
 }
 
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_register_duplicate_username_ignores_case(ctx: &TestDb) {
+    let service = AuthService::new(ctx.pool.clone(), common::test_config());
+
+    service
+        .register(CreateUserDto {
+            username: "Alice".to_string(),
+            email: "alice@example.com".to_string(),
+            password: "password123".to_string(),
+        })
+        .await
+        .unwrap();
+    let result = service
+        .register(CreateUserDto {
+            username: "aLICE".to_string(),
+            email: "another-alice@example.com".to_string(),
+            password: "password123".to_string(),
+        })
+        .await;
+
+    assert!(matches!(result, Err(AppError::Conflict(msg)) if msg == "Username already exists"));
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_register_trims_username(ctx: &TestDb) {
+    let service = AuthService::new(ctx.pool.clone(), common::test_config());
+    let register = |username: &str, email: &str| CreateUserDto {
+        username: username.to_string(),
+        email: email.to_string(),
+        password: "password123".to_string(),
+    };
+
+    service.register(register("alice", "alice@example.com")).await.unwrap();
+    let result = service.register(register("alice ", "alice2@example.com")).await;
+    assert!(matches!(result, Err(AppError::Conflict(msg)) if msg == "Username already exists"));
+
+    // Surrounding spaces are not stored, so the name blocks its untrimmed twin too
+    let bob = service.register(register(" bob ", "bob@example.com")).await.unwrap();
+    assert_eq!(bob.username, "bob");
+    let result = service.register(register("bob", "bob2@example.com")).await;
+    assert!(matches!(result, Err(AppError::Conflict(_))));
+}
+
 #[test_context(TestDb)]
 #[tokio::test]
 #[serial]
 async fn test_login_success(ctx: &TestDb) {
-    let service = AuthService::new(ctx.pool.clone());
+    let service = AuthService::new(ctx.pool.clone(), common::test_config());
     let password = "password123".to_string();
     
     let register_dto = CreateUserDto {
@@ -72,7 +124,7 @@ async fn test_login_success(ctx: &TestDb) {
         password,
     };
 
-    let auth_response = service.login(login_dto).await.unwrap();
+    let auth_response = service.login(login_dto).await.and_then(LoginOutcome::tokens).unwrap();
 
     assert_eq!(auth_response.user.id, created_user.id);
     assert!(!auth_response.token.is_empty());
@@ -82,7 +134,7 @@ async fn test_login_success(ctx: &TestDb) {
 #[tokio::test]
 #[serial]
 async fn test_login_wrong_password(ctx: &TestDb) {
-    let service = AuthService::new(ctx.pool.clone());
+    let service = AuthService::new(ctx.pool.clone(), common::test_config());
     
     let register_dto = CreateUserDto {
         username: Faker.fake::<String>(),
@@ -105,7 +157,7 @@ async fn test_login_wrong_password(ctx: &TestDb) {
 #[tokio::test]
 #[serial]
 async fn test_verify_token(ctx: &TestDb) {
-    let service = AuthService::new(ctx.pool.clone());
+    let service = AuthService::new(ctx.pool.clone(), common::test_config());
     
     let register_dto = CreateUserDto {
         username: Faker.fake::<String>(),
@@ -120,7 +172,7 @@ async fn test_verify_token(ctx: &TestDb) {
         password: register_dto.password,
     };
 
-    let auth_response = service.login(login_dto).await.unwrap();
+    let auth_response = service.login(login_dto).await.and_then(LoginOutcome::tokens).unwrap();
     let claims = service.verify_token(&auth_response.token).unwrap();
 
     assert_eq!(claims.sub, created_user.id.to_string());
@@ -130,7 +182,231 @@ async fn test_verify_token(ctx: &TestDb) {
 #[tokio::test]
 #[serial]
 async fn test_verify_invalid_token(ctx: &TestDb) {
-    let service = AuthService::new(ctx.pool.clone());
-    let result = service.verify_token("invalid.token.here").await;
+    let service = AuthService::new(ctx.pool.clone(), common::test_config());
+    let result = service.verify_token("invalid.token.here");
     assert!(result.is_err());
 }
+
+// 테스트 설정과 같은 발급자, 수신자를 가진 유효한 클레임을 만듭니다.
+fn valid_claims() -> Claims {
+    let config = common::test_config();
+    let now = Utc::now().timestamp();
+    Claims {
+        sub: Uuid::new_v4().to_string(),
+        exp: now + 900,
+        nbf: now,
+        iat: now,
+        iss: config.jwt.issuer.clone(),
+        aud: config.jwt.audience.clone(),
+        jti: Uuid::new_v4().to_string(),
+        ver: 0,
+        role: Role::User,
+    }
+}
+
+fn sign(claims: &Claims, secret: &str) -> String {
+    encode(&Header::default(), claims, &EncodingKey::from_secret(secret.as_bytes())).unwrap()
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_verify_token_classifies_failures(ctx: &TestDb) {
+    let config = common::test_config();
+    let service = AuthService::new(ctx.pool.clone(), config.clone());
+    let secret = config.jwt.secret.as_str();
+    let leeway = config.jwt.leeway_secs as i64;
+    let now = Utc::now().timestamp();
+
+    assert!(service.verify_token(&sign(&valid_claims(), secret)).is_ok());
+
+    // Clock skew within the leeway is tolerated
+    let claims = Claims { exp: now - leeway / 2, ..valid_claims() };
+    assert!(service.verify_token(&sign(&claims, secret)).is_ok());
+
+    let cases = [
+        (Claims { exp: now - leeway - 60, ..valid_claims() }, TokenError::Expired),
+        (Claims { nbf: now + leeway + 60, ..valid_claims() }, TokenError::NotYetValid),
+        (Claims { iat: now + leeway + 60, ..valid_claims() }, TokenError::NotYetValid),
+        (Claims { iss: "someone-else".to_string(), ..valid_claims() }, TokenError::InvalidClaims),
+        (Claims { aud: "another-api".to_string(), ..valid_claims() }, TokenError::InvalidClaims),
+    ];
+    for (claims, expected) in cases {
+        match service.verify_token(&sign(&claims, secret)) {
+            Err(AppError::InvalidToken(kind)) => assert_eq!(kind, expected, "{:?}", claims),
+            other => panic!("expected {:?}, got {:?}", expected, other.map(|_| ())),
+        }
+    }
+
+    let forged = sign(&valid_claims(), "a-completely-different-signing-key");
+    assert!(matches!(
+        service.verify_token(&forged),
+        Err(AppError::InvalidToken(TokenError::BadSignature))
+    ));
+    assert!(matches!(
+        service.verify_token("invalid.token.here"),
+        Err(AppError::InvalidToken(TokenError::Malformed))
+    ));
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_register_weak_password(ctx: &TestDb) {
+    let service = AuthService::new(ctx.pool.clone(), common::test_config());
+
+    let dto = CreateUserDto {
+        username: Faker.fake::<String>(),
+        email: format!("{}@example.com", Uuid::new_v4()),
+        password: "short".to_string(),
+    };
+
+    let result = service.register(dto).await;
+    assert!(matches!(result, Err(AppError::Validation { .. })));
+}
+
+// 테스트 사용자를 등록하고 로그인 응답을 반환합니다.
+async fn register_and_login(service: &AuthService) -> AuthResponse {
+    let dto = CreateUserDto {
+        username: Faker.fake::<String>(),
+        email: format!("{}@example.com", Uuid::new_v4()),
+        password: "password123".to_string(),
+    };
+    service.register(dto.clone()).await.unwrap();
+    service
+        .login(LoginDto {
+            email: dto.email,
+            password: dto.password,
+        })
+        .await
+        .and_then(LoginOutcome::tokens)
+        .unwrap()
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_refresh_rotates_token(ctx: &TestDb) {
+    let service = AuthService::new(ctx.pool.clone(), common::test_config());
+    let login = register_and_login(&service).await;
+
+    let refreshed = service.refresh(&login.refresh_token).await.unwrap();
+    assert_eq!(refreshed.user.id, login.user.id);
+    assert_ne!(refreshed.refresh_token, login.refresh_token);
+    assert!(service.verify_token(&refreshed.token).is_ok());
+
+    // The new token can be used once more
+    assert!(service.refresh(&refreshed.refresh_token).await.is_ok());
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_refresh_reuse_revokes_family(ctx: &TestDb) {
+    let service = AuthService::new(ctx.pool.clone(), common::test_config());
+    let login = register_and_login(&service).await;
+    let other_session = service
+        .login(LoginDto {
+            email: login.user.email.clone(),
+            password: "password123".to_string(),
+        })
+        .await
+        .and_then(LoginOutcome::tokens)
+        .unwrap();
+
+    let refreshed = service.refresh(&login.refresh_token).await.unwrap();
+
+    // Replaying the rotated token is rejected...
+    let result = service.refresh(&login.refresh_token).await;
+    assert!(matches!(result, Err(AppError::Unauthorized(_))));
+
+    // ...and revokes the token that replaced it
+    let result = service.refresh(&refreshed.refresh_token).await;
+    assert!(matches!(result, Err(AppError::Unauthorized(_))));
+
+    // Other login sessions are unaffected
+    assert!(service.refresh(&other_session.refresh_token).await.is_ok());
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_refresh_rejects_expired_and_unknown_tokens(ctx: &TestDb) {
+    let service = AuthService::new(ctx.pool.clone(), common::test_config());
+    let login = register_and_login(&service).await;
+
+    sqlx::query!("UPDATE refresh_tokens SET expires_at = CURRENT_TIMESTAMP - INTERVAL '1 second'")
+        .execute(&ctx.pool)
+        .await
+        .unwrap();
+
+    let result = service.refresh(&login.refresh_token).await;
+    assert!(matches!(result, Err(AppError::Unauthorized(_))));
+
+    let result = service.refresh("not-a-real-token").await;
+    assert!(matches!(result, Err(AppError::Unauthorized(_))));
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_logout_revokes_access_and_refresh_tokens(ctx: &TestDb) {
+    let service = AuthService::new(ctx.pool.clone(), common::test_config());
+    let login = register_and_login(&service).await;
+    let other_session = service
+        .login(LoginDto {
+            email: login.user.email.clone(),
+            password: "password123".to_string(),
+        })
+        .await
+        .and_then(LoginOutcome::tokens)
+        .unwrap();
+
+    // Populate the revocation cache before logging out
+    let claims = service.authenticate(&login.token).await.unwrap();
+
+    service.logout(&claims, Some(&login.refresh_token)).await.unwrap();
+
+    assert!(matches!(
+        service.authenticate(&login.token).await,
+        Err(AppError::InvalidToken(TokenError::Revoked))
+    ));
+    assert!(service.refresh(&login.refresh_token).await.is_err());
+
+    // Other sessions stay logged in
+    assert!(service.authenticate(&other_session.token).await.is_ok());
+    assert!(service.refresh(&other_session.refresh_token).await.is_ok());
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_logout_all_revokes_every_session(ctx: &TestDb) {
+    let service = AuthService::new(ctx.pool.clone(), common::test_config());
+    let login = register_and_login(&service).await;
+    let other_session = service
+        .login(LoginDto {
+            email: login.user.email.clone(),
+            password: "password123".to_string(),
+        })
+        .await
+        .and_then(LoginOutcome::tokens)
+        .unwrap();
+
+    service.logout_all(login.user.id).await.unwrap();
+
+    assert!(service.authenticate(&login.token).await.is_err());
+    assert!(service.authenticate(&other_session.token).await.is_err());
+    assert!(service.refresh(&other_session.refresh_token).await.is_err());
+
+    // Logging in again issues tokens with the new version
+    let fresh = service
+        .login(LoginDto {
+            email: login.user.email.clone(),
+            password: "password123".to_string(),
+        })
+        .await
+        .and_then(LoginOutcome::tokens)
+        .unwrap();
+    assert!(service.authenticate(&fresh.token).await.is_ok());
+}
@@ -1,8 +1,13 @@
 use crate::common::TestDb;
 use fake::{Fake, Faker};
-use rust_study::models::comment::{CreateCommentDto, UpdateCommentDto};
-use rust_study::services::comment_service::CommentService;
+use rust_study::config::CommentsConfig;
+use rust_study::errors::AppError;
+use rust_study::models::comment::{CommentTreeParams, CommentTreeQuery, CreateCommentDto, UpdateCommentDto};
+use rust_study::models::pagination::{Cursor, CursorParams, PageParams};
+use rust_study::models::user::{Actor, Role};
+use rust_study::services::{comment_service::CommentService, MutationOutcome};
 use serial_test::serial;
+use std::sync::Arc;
 use test_context::test_context;
 use uuid::Uuid;
 
@@ -27,9 +32,9 @@ async fn create_test_comment(
 #[tokio::test]
 #[serial]
 async fn test_create_comment(ctx: &TestDb) {
-    let service = CommentService::new(ctx.pool.clone());
-    let post_id = Uuid::new_v4();
+    let service = CommentService::new(ctx.pool.clone(), common::test_config());
     let author_id = Uuid::new_v4();
+    let post_id = common::create_test_post(&ctx.pool, "Test post", "Test content", author_id).await;
     
     let dto = CreateCommentDto {
         content: Faker.fake::<String>(),
@@ -40,7 +45,7 @@ async fn test_create_comment(ctx: &TestDb) {
 
     assert_eq!(comment.content, dto.content);
     assert_eq!(comment.post_id, post_id);
-    assert_eq!(comment.author_id, author_id);
+    assert_eq!(comment.author.id, author_id);
     assert_eq!(comment.parent_id, None);
 }
 
@@ -48,9 +53,9 @@ async fn test_create_comment(ctx: &TestDb) {
 #[tokio::test]
 #[serial]
 async fn test_create_reply(ctx: &TestDb) {
-    let service = CommentService::new(ctx.pool.clone());
-    let post_id = Uuid::new_v4();
+    let service = CommentService::new(ctx.pool.clone(), common::test_config());
     let author_id = Uuid::new_v4();
+    let post_id = common::create_test_post(&ctx.pool, "Test post", "Test content", author_id).await;
     
     // Create parent comment
     let parent_id = create_test_comment(&service, post_id, author_id, None).await;
@@ -65,7 +70,7 @@ async fn test_create_reply(ctx: &TestDb) {
 
     assert_eq!(reply.content, dto.content);
     assert_eq!(reply.post_id, post_id);
-    assert_eq!(reply.author_id, author_id);
+    assert_eq!(reply.author.id, author_id);
     assert_eq!(reply.parent_id, Some(parent_id));
 }
 
@@ -73,9 +78,9 @@ async fn test_create_reply(ctx: &TestDb) {
 #[tokio::test]
 #[serial]
 async fn test_get_post_comments(ctx: &TestDb) {
-    let service = CommentService::new(ctx.pool.clone());
-    let post_id = Uuid::new_v4();
+    let service = CommentService::new(ctx.pool.clone(), common::test_config());
     let author_id = Uuid::new_v4();
+    let post_id = common::create_test_post(&ctx.pool, "Test post", "Test content", author_id).await;
 
     // Create 15 comments
     for _ in 0..15 {
@@ -83,21 +88,23 @@ async fn test_get_post_comments(ctx: &TestDb) {
     }
 
     // Test first page (10 comments)
-    let comments = service.get_post_comments(post_id, 1, 10).await.unwrap();
-    assert_eq!(comments.len(), 10);
+    let comments = service.get_post_comments(post_id, PageParams::new(1, 10)).await.unwrap();
+    assert_eq!(comments.items.len(), 10);
+    assert_eq!(comments.total, 15);
+    assert_eq!(comments.total_pages, 2);
 
     // Test second page (5 comments)
-    let comments = service.get_post_comments(post_id, 2, 10).await.unwrap();
-    assert_eq!(comments.len(), 5);
+    let comments = service.get_post_comments(post_id, PageParams::new(2, 10)).await.unwrap();
+    assert_eq!(comments.items.len(), 5);
 }
 
 #[test_context(TestDb)]
 #[tokio::test]
 #[serial]
 async fn test_get_replies(ctx: &TestDb) {
-    let service = CommentService::new(ctx.pool.clone());
-    let post_id = Uuid::new_v4();
+    let service = CommentService::new(ctx.pool.clone(), common::test_config());
     let author_id = Uuid::new_v4();
+    let post_id = common::create_test_post(&ctx.pool, "Test post", "Test content", author_id).await;
 
     // Create parent comment
     let parent_id = create_test_comment(&service, post_id, author_id, None).await;
@@ -107,9 +114,10 @@ async fn test_get_replies(ctx: &TestDb) {
         create_test_comment(&service, post_id, author_id, Some(parent_id)).await;
     }
 
-    let replies = service.get_replies(parent_id, 1, 10).await.unwrap();
-    assert_eq!(replies.len(), 5);
-    for reply in replies {
+    let replies = service.get_replies(parent_id, PageParams::new(1, 10)).await.unwrap();
+    assert_eq!(replies.items.len(), 5);
+    assert_eq!(replies.total, 5);
+    for reply in replies.items {
         assert_eq!(reply.parent_id, Some(parent_id));
     }
 }
@@ -118,9 +126,9 @@ async fn test_get_replies(ctx: &TestDb) {
 #[tokio::test]
 #[serial]
 async fn test_update_comment(ctx: &TestDb) {
-    let service = CommentService::new(ctx.pool.clone());
-    let post_id = Uuid::new_v4();
+    let service = CommentService::new(ctx.pool.clone(), common::test_config());
     let author_id = Uuid::new_v4();
+    let post_id = common::create_test_post(&ctx.pool, "Test post", "Test content", author_id).await;
 
     let comment_id = create_test_comment(&service, post_id, author_id, None).await;
 
@@ -129,11 +137,10 @@ async fn test_update_comment(ctx: &TestDb) {
         content: new_content.clone(),
     };
 
-    let updated_comment = service
-        .update_comment(comment_id, author_id, dto)
-        .await
-        .unwrap()
-        .unwrap();
+    let updated_comment = match service.update_comment(comment_id, &common::user_actor(author_id), dto).await.unwrap() {
+        MutationOutcome::Done(comment) => comment,
+        other => panic!("expected Done, got {:?}", other),
+    };
 
     assert_eq!(updated_comment.id, comment_id);
     assert_eq!(updated_comment.content, new_content);
@@ -143,15 +150,15 @@ async fn test_update_comment(ctx: &TestDb) {
 #[tokio::test]
 #[serial]
 async fn test_delete_comment(ctx: &TestDb) {
-    let service = CommentService::new(ctx.pool.clone());
-    let post_id = Uuid::new_v4();
+    let service = CommentService::new(ctx.pool.clone(), common::test_config());
     let author_id = Uuid::new_v4();
+    let post_id = common::create_test_post(&ctx.pool, "Test post", "Test content", author_id).await;
 
     let comment_id = create_test_comment(&service, post_id, author_id, None).await;
 
     // Delete the comment
-    let result = service.delete_comment(comment_id, author_id).await.unwrap();
-    assert!(result);
+    let result = service.delete_comment(comment_id, &common::user_actor(author_id)).await.unwrap();
+    assert!(matches!(result, MutationOutcome::Done(())));
 
     // Verify comment is deleted
     let comment = service.get_comment(comment_id).await.unwrap();
@@ -162,18 +169,350 @@ async fn test_delete_comment(ctx: &TestDb) {
 #[tokio::test]
 #[serial]
 async fn test_delete_comment_wrong_author(ctx: &TestDb) {
-    let service = CommentService::new(ctx.pool.clone());
-    let post_id = Uuid::new_v4();
+    let service = CommentService::new(ctx.pool.clone(), common::test_config());
     let author_id = Uuid::new_v4();
+    let post_id = common::create_test_post(&ctx.pool, "Test post", "Test content", author_id).await;
     let wrong_author_id = Uuid::new_v4();
 
     let comment_id = create_test_comment(&service, post_id, author_id, None).await;
 
     // Try to delete with wrong author
-    let result = service.delete_comment(comment_id, wrong_author_id).await.unwrap();
-    assert!(!result);
+    let result = service.delete_comment(comment_id, &common::user_actor(wrong_author_id)).await.unwrap();
+    assert!(matches!(result, MutationOutcome::Forbidden));
 
     // Verify comment still exists
     let comment = service.get_comment(comment_id).await.unwrap();
     assert!(comment.is_some());
 }
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_update_comment_wrong_author(ctx: &TestDb) {
+    let service = CommentService::new(ctx.pool.clone(), common::test_config());
+    let author_id = Uuid::new_v4();
+    let post_id = common::create_test_post(&ctx.pool, "Test post", "Test content", author_id).await;
+    let wrong_author_id = Uuid::new_v4();
+
+    let comment_id = create_test_comment(&service, post_id, author_id, None).await;
+
+    let dto = UpdateCommentDto {
+        content: Faker.fake::<String>(),
+    };
+
+    let result = service.update_comment(comment_id, &common::user_actor(wrong_author_id), dto).await.unwrap();
+    assert!(matches!(result, MutationOutcome::Forbidden));
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_update_and_delete_missing_comment(ctx: &TestDb) {
+    let service = CommentService::new(ctx.pool.clone(), common::test_config());
+    let author_id = Uuid::new_v4();
+    let missing_comment_id = Uuid::new_v4();
+
+    let dto = UpdateCommentDto {
+        content: Faker.fake::<String>(),
+    };
+
+    let result = service.update_comment(missing_comment_id, &common::user_actor(author_id), dto).await.unwrap();
+    assert!(matches!(result, MutationOutcome::NotFound));
+
+    let result = service.delete_comment(missing_comment_id, &common::user_actor(author_id)).await.unwrap();
+    assert!(matches!(result, MutationOutcome::NotFound));
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_get_post_comments_by_cursor(ctx: &TestDb) {
+    let service = CommentService::new(ctx.pool.clone(), common::test_config());
+    let author_id = Uuid::new_v4();
+    let post_id = common::create_test_post(&ctx.pool, "Test post", "Test content", author_id).await;
+
+    // Create 12 comments
+    for _ in 0..12 {
+        create_test_comment(&service, post_id, author_id, None).await;
+    }
+
+    let first = service.get_post_comments(post_id, PageParams::new(1, 5)).await.unwrap();
+    let mut cursor = Cursor::decode(first.next_cursor.as_deref().unwrap()).unwrap();
+    let mut seen: Vec<Uuid> = first.items.iter().map(|c| c.id).collect();
+
+    // Walk the remaining pages with the cursor
+    loop {
+        let page = service
+            .get_post_comments_by_cursor(post_id, CursorParams::after(cursor, 5))
+            .await
+            .unwrap();
+        seen.extend(page.items.iter().map(|c| c.id));
+        match page.next_cursor {
+            Some(next) => cursor = Cursor::decode(&next).unwrap(),
+            None => break,
+        }
+    }
+
+    assert_eq!(seen.len(), 12);
+    seen.sort();
+    seen.dedup();
+    assert_eq!(seen.len(), 12);
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_comment_author_summary(ctx: &TestDb) {
+    let service = CommentService::new(ctx.pool.clone(), common::test_config());
+    let author_id = common::create_test_user(&ctx.pool, "bob", "bob@example.com", "hash").await;
+    let post_id = common::create_test_post(&ctx.pool, "Test post", "Test content", author_id).await;
+
+    let dto = CreateCommentDto {
+        content: "hello".to_string(),
+        parent_id: None,
+    };
+    let comment = service.create_comment(post_id, author_id, dto).await.unwrap();
+    assert_eq!(comment.author.username, "bob");
+    assert!(!comment.author.deleted);
+
+    // Deleting the account keeps the comment with a placeholder author
+    sqlx::query!("DELETE FROM users WHERE id = $1", author_id)
+        .execute(&ctx.pool)
+        .await
+        .unwrap();
+
+    let comment = service.get_comment(comment.id).await.unwrap().unwrap();
+    assert_eq!(comment.author.id, author_id);
+    assert!(comment.author.deleted);
+
+    let comments = service.get_post_comments(post_id, PageParams::new(1, 10)).await.unwrap();
+    assert!(comments.items[0].author.deleted);
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_reply_count_after_cascade_delete(ctx: &TestDb) {
+    let service = CommentService::new(ctx.pool.clone(), common::test_config());
+    let author_id = Uuid::new_v4();
+    let post_id = common::create_test_post(&ctx.pool, "Test post", "Test content", author_id).await;
+
+    let root_id = create_test_comment(&service, post_id, author_id, None).await;
+    let child_id = create_test_comment(&service, post_id, author_id, Some(root_id)).await;
+    create_test_comment(&service, post_id, author_id, Some(root_id)).await;
+    create_test_comment(&service, post_id, author_id, Some(child_id)).await;
+
+    let root = service.get_comment(root_id).await.unwrap().unwrap();
+    assert_eq!(root.reply_count, 2);
+
+    let replies = service.get_replies(root_id, PageParams::new(1, 10)).await.unwrap();
+    let child = replies.items.iter().find(|c| c.id == child_id).unwrap();
+    assert_eq!(child.reply_count, 1);
+
+    // Deleting a reply cascades to its own replies
+    service.delete_comment(child_id, &common::user_actor(author_id)).await.unwrap();
+
+    let comments = service.get_post_comments(post_id, PageParams::new(1, 10)).await.unwrap();
+    assert_eq!(comments.total, 2);
+    let root = comments.items.iter().find(|c| c.id == root_id).unwrap();
+    assert_eq!(root.reply_count, 1);
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_get_comment_tree(ctx: &TestDb) {
+    let service = CommentService::new(ctx.pool.clone(), common::test_config());
+    let author_id = Uuid::new_v4();
+    let post_id = common::create_test_post(&ctx.pool, "Test post", "Test content", author_id).await;
+
+    // root1 -> a -> b -> c, root1 -> a2, root1 -> a3, plus root2 and root3
+    let root1 = create_test_comment(&service, post_id, author_id, None).await;
+    let a = create_test_comment(&service, post_id, author_id, Some(root1)).await;
+    let b = create_test_comment(&service, post_id, author_id, Some(a)).await;
+    create_test_comment(&service, post_id, author_id, Some(b)).await;
+    create_test_comment(&service, post_id, author_id, Some(root1)).await;
+    create_test_comment(&service, post_id, author_id, Some(root1)).await;
+    create_test_comment(&service, post_id, author_id, None).await;
+    create_test_comment(&service, post_id, author_id, None).await;
+
+    let tree = service
+        .get_comment_tree(post_id, CommentTreeParams { max_depth: 2, limit: 2 })
+        .await
+        .unwrap();
+
+    // Only two top-level comments fit, oldest first
+    assert_eq!(tree.items.len(), 2);
+    assert_eq!(tree.more_comments, 1);
+    assert_eq!(tree.items[0].comment.id, root1);
+
+    // root1 has three replies but only two are included
+    let root = &tree.items[0];
+    assert_eq!(root.comment.depth, 1);
+    assert_eq!(root.replies.len(), 2);
+    assert_eq!(root.more_replies, 1);
+
+    // Depth limit cuts the branch below a
+    let first_reply = &root.replies[0];
+    assert_eq!(first_reply.comment.id, a);
+    assert_eq!(first_reply.comment.depth, 2);
+    assert!(first_reply.replies.is_empty());
+    assert_eq!(first_reply.more_replies, 1);
+
+    // A deeper tree reaches the leaf
+    let tree = service
+        .get_comment_tree(post_id, CommentTreeParams { max_depth: 4, limit: 10 })
+        .await
+        .unwrap();
+    assert_eq!(tree.more_comments, 0);
+    let leaf = &tree.items[0].replies[0].replies[0].replies[0];
+    assert_eq!(leaf.comment.depth, 4);
+    assert_eq!(leaf.more_replies, 0);
+}
+
+#[test]
+fn test_comment_tree_query_resolve() {
+    let config = CommentsConfig::default();
+
+    let params = CommentTreeQuery::default().resolve(&config).unwrap();
+    assert_eq!(params.max_depth, config.tree_default_depth);
+    assert_eq!(params.limit, config.tree_default_limit);
+
+    let query = CommentTreeQuery {
+        depth: Some(1_000),
+        limit: Some(1_000),
+    };
+    let params = query.resolve(&config).unwrap();
+    assert_eq!(params.max_depth, config.tree_max_depth);
+    assert_eq!(params.limit, config.tree_max_limit);
+
+    let query = CommentTreeQuery {
+        depth: Some(0),
+        limit: None,
+    };
+    assert!(query.resolve(&config).is_err());
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_create_reply_validates_parent(ctx: &TestDb) {
+    let service = CommentService::new(ctx.pool.clone(), common::test_config());
+    let author_id = Uuid::new_v4();
+    let post_id = common::create_test_post(&ctx.pool, "Test post", "Test content", author_id).await;
+    let other_post_id = common::create_test_post(&ctx.pool, "Other post", "Other content", author_id).await;
+    let other_parent_id = create_test_comment(&service, other_post_id, author_id, None).await;
+
+    let reply = |parent_id| CreateCommentDto {
+        content: "reply".to_string(),
+        parent_id: Some(parent_id),
+    };
+
+    // Parent on a different post
+    let result = service.create_comment(post_id, author_id, reply(other_parent_id)).await;
+    assert!(matches!(result, Err(AppError::BadRequest(_))));
+
+    // Missing parent
+    let result = service.create_comment(post_id, author_id, reply(Uuid::new_v4())).await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+
+    // Missing post
+    let dto = CreateCommentDto {
+        content: "comment".to_string(),
+        parent_id: None,
+    };
+    let result = service.create_comment(Uuid::new_v4(), author_id, dto).await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_create_reply_max_depth(ctx: &TestDb) {
+    let mut config = (*common::test_config()).clone();
+    config.comments.max_depth = 2;
+    let service = CommentService::new(ctx.pool.clone(), Arc::new(config));
+    let author_id = Uuid::new_v4();
+    let post_id = common::create_test_post(&ctx.pool, "Test post", "Test content", author_id).await;
+
+    let root_id = create_test_comment(&service, post_id, author_id, None).await;
+    let reply_id = create_test_comment(&service, post_id, author_id, Some(root_id)).await;
+
+    let reply = service.get_comment(reply_id).await.unwrap().unwrap();
+    assert_eq!(reply.depth, 2);
+
+    let dto = CreateCommentDto {
+        content: "too deep".to_string(),
+        parent_id: Some(reply_id),
+    };
+    let result = service.create_comment(post_id, author_id, dto).await;
+    assert!(matches!(result, Err(AppError::BadRequest(_))));
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_moderator_can_edit_and_delete_any_comment(ctx: &TestDb) {
+    let service = CommentService::new(ctx.pool.clone(), common::test_config());
+    let author_id = Uuid::new_v4();
+    let post_id = common::create_test_post(&ctx.pool, "title", "content", author_id).await;
+    let comment_id = create_test_comment(&service, post_id, author_id, None).await;
+
+    // Regular users still cannot touch other people's comments
+    let result = service.delete_comment(comment_id, &common::user_actor(Uuid::new_v4())).await.unwrap();
+    assert!(matches!(result, MutationOutcome::Forbidden));
+
+    let moderator_id = common::create_test_user(&ctx.pool, "moderator", "moderator@example.com", "hash").await;
+    let moderator = Actor { id: moderator_id, role: Role::Moderator };
+
+    let dto = UpdateCommentDto { content: "[removed by moderator]".to_string() };
+    let result = service.update_comment(comment_id, &moderator, dto).await.unwrap();
+    assert!(matches!(result, MutationOutcome::Done(ref c) if c.content == "[removed by moderator]"));
+
+    let result = service.delete_comment(comment_id, &moderator).await.unwrap();
+    assert!(matches!(result, MutationOutcome::Done(())));
+
+    let actions = sqlx::query_scalar!(
+        r#"SELECT action::TEXT AS "action!" FROM moderator_actions WHERE moderator_id = $1 ORDER BY created_at"#,
+        moderator_id
+    )
+    .fetch_all(&ctx.pool)
+    .await
+    .unwrap();
+    assert_eq!(actions, ["update_comment", "delete_comment"]);
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_post_author_can_delete_comments_on_their_post(ctx: &TestDb) {
+    let service = CommentService::new(ctx.pool.clone(), common::test_config());
+    let post_author_id = Uuid::new_v4();
+    let commenter_id = Uuid::new_v4();
+    let post_id = common::create_test_post(&ctx.pool, "title", "content", post_author_id).await;
+    let comment_id = create_test_comment(&service, post_id, commenter_id, None).await;
+
+    // The post author may delete, but not rewrite, someone else's comment
+    let dto = UpdateCommentDto { content: Faker.fake::<String>() };
+    let result = service.update_comment(comment_id, &common::user_actor(post_author_id), dto).await.unwrap();
+    assert!(matches!(result, MutationOutcome::Forbidden));
+
+    let result = service.delete_comment(comment_id, &common::user_actor(post_author_id)).await.unwrap();
+    assert!(matches!(result, MutationOutcome::Done(())));
+    assert!(service.get_comment(comment_id).await.unwrap().is_none());
+
+    // This is not a moderator action
+    let recorded = sqlx::query_scalar!(r#"SELECT COUNT(*) AS "count!" FROM moderator_actions"#)
+        .fetch_one(&ctx.pool)
+        .await
+        .unwrap();
+    assert_eq!(recorded, 0);
+
+    // The board can turn the rule off
+    let mut config = (*common::test_config()).clone();
+    config.board.post_author_can_delete_comments = false;
+    let service = CommentService::new(ctx.pool.clone(), Arc::new(config));
+    let comment_id = create_test_comment(&service, post_id, commenter_id, None).await;
+    let result = service.delete_comment(comment_id, &common::user_actor(post_author_id)).await.unwrap();
+    assert!(matches!(result, MutationOutcome::Forbidden));
+}
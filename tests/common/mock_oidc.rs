@@ -0,0 +1,202 @@
+// mock_oidc.rs
+// 통합 테스트에서 외부 ID 공급자 대신 사용하는 OIDC 공급자입니다.
+// discovery 문서, 토큰 엔드포인트, JWKS를 제공하며, ID 토큰은 tests/fixtures/jwt의 RSA 키로 서명합니다.
+// 로그인 화면 대신 authorize()로 사용자가 공급자에서 로그인을 마친 것처럼 인가 코드를 발급합니다.
+
+use actix_web::{web, App, HttpRequest, HttpResponse};
+use base64::{engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD}, Engine};
+use chrono::Utc;
+use jsonwebtoken::{encode, Algorithm};
+use rust_study::config::{Config, JwtConfig, JwtKeyConfig};
+use rust_study::services::jwt_keys::JwtKeys;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use uuid::Uuid;
+
+pub const CLIENT_ID: &str = "board-client";
+pub const CLIENT_SECRET: &str = "board-client-secret";
+pub const REDIRECT_URI: &str = "http://localhost:3000/oidc/callback";
+
+// 공급자에서 로그인한 사용자입니다.
+#[derive(Debug, Clone)]
+pub struct MockUser {
+    pub subject: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub preferred_username: Option<String>,
+}
+
+impl MockUser {
+    // 이메일 인증을 마친 사용자를 만듭니다.
+    pub fn new(subject: &str, email: &str) -> Self {
+        Self {
+            subject: subject.to_string(),
+            email: Some(email.to_string()),
+            email_verified: true,
+            preferred_username: None,
+        }
+    }
+}
+
+// 발급했지만 아직 토큰으로 교환하지 않은 인가 코드입니다.
+struct PendingCode {
+    user: MockUser,
+    client_id: String,
+    redirect_uri: String,
+    code_challenge: String,
+    nonce: String,
+}
+
+#[derive(Default)]
+struct ProviderState {
+    issuer: OnceLock<String>,
+    codes: Mutex<HashMap<String, PendingCode>>,
+}
+
+pub struct MockOidcProvider {
+    server: actix_test::TestServer,
+    state: web::Data<ProviderState>,
+}
+
+impl MockOidcProvider {
+    pub fn start() -> Self {
+        let fixture = |name: &str| format!("{}/tests/fixtures/jwt/{}", env!("CARGO_MANIFEST_DIR"), name);
+        let jwt_config = JwtConfig {
+            signing_kid: "mock-1".to_string(),
+            keys: vec![JwtKeyConfig {
+                kid: "mock-1".to_string(),
+                algorithm: Algorithm::RS256,
+                public_key_file: fixture("rsa-public.pem"),
+                private_key_file: Some(fixture("rsa-private.pem")),
+            }],
+            ..JwtConfig::default()
+        };
+        let keys = web::Data::new(JwtKeys::from_config(&jwt_config).unwrap());
+        let state = web::Data::new(ProviderState::default());
+
+        let server = actix_test::start({
+            let state = state.clone();
+            move || {
+                App::new()
+                    .app_data(state.clone())
+                    .app_data(keys.clone())
+                    .route("/.well-known/openid-configuration", web::get().to(discovery))
+                    .route("/token", web::post().to(token))
+                    .route("/jwks", web::get().to(jwks))
+            }
+        });
+        state.issuer.set(format!("http://{}", server.addr())).unwrap();
+        Self { server, state }
+    }
+
+    pub fn issuer(&self) -> String {
+        self.state.issuer.get().unwrap().clone()
+    }
+
+    pub fn discovery_url(&self) -> String {
+        self.server.url("/.well-known/openid-configuration")
+    }
+
+    // 이 공급자를 사용하도록 OIDC 설정을 채운 테스트 설정을 반환합니다.
+    pub fn config(&self) -> Arc<Config> {
+        let mut config = (*super::test_config()).clone();
+        config.oidc.provider = "mock".to_string();
+        config.oidc.discovery_url = self.discovery_url();
+        config.oidc.client_id = CLIENT_ID.to_string();
+        config.oidc.client_secret = CLIENT_SECRET.to_string();
+        config.oidc.redirect_uri = REDIRECT_URI.to_string();
+        Arc::new(config)
+    }
+
+    // 사용자가 authorization_url에서 로그인을 마친 것처럼 인가 코드를 발급하고, (code, state)를 반환합니다.
+    pub fn authorize(&self, authorization_url: &str, user: MockUser) -> (String, String) {
+        let url = reqwest::Url::parse(authorization_url).unwrap();
+        assert!(authorization_url.starts_with(&format!("{}/authorize?", self.issuer())));
+        let params: HashMap<String, String> = url.query_pairs().into_owned().collect();
+        assert_eq!(params["response_type"], "code");
+        assert_eq!(params["code_challenge_method"], "S256");
+        assert!(params["scope"].split(' ').any(|scope| scope == "openid"));
+
+        let code = Uuid::new_v4().to_string();
+        self.state.codes.lock().unwrap().insert(
+            code.clone(),
+            PendingCode {
+                user,
+                client_id: params["client_id"].clone(),
+                redirect_uri: params["redirect_uri"].clone(),
+                code_challenge: params["code_challenge"].clone(),
+                nonce: params["nonce"].clone(),
+            },
+        );
+        (code, params["state"].clone())
+    }
+}
+
+async fn discovery(state: web::Data<ProviderState>) -> HttpResponse {
+    let issuer = state.issuer.get().unwrap();
+    HttpResponse::Ok().json(json!({
+        "issuer": issuer,
+        "authorization_endpoint": format!("{}/authorize", issuer),
+        "token_endpoint": format!("{}/token", issuer),
+        "jwks_uri": format!("{}/jwks", issuer),
+        "response_types_supported": ["code"],
+        "subject_types_supported": ["public"],
+        "id_token_signing_alg_values_supported": ["RS256"],
+        "code_challenge_methods_supported": ["S256"],
+    }))
+}
+
+async fn jwks(keys: web::Data<JwtKeys>) -> HttpResponse {
+    HttpResponse::Ok().json(keys.jwks())
+}
+
+// 인가 코드를 ID 토큰으로 교환합니다. 클라이언트 인증, redirect_uri, PKCE가 모두 맞아야 합니다.
+async fn token(
+    state: web::Data<ProviderState>,
+    keys: web::Data<JwtKeys>,
+    form: web::Form<HashMap<String, String>>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let invalid_grant = || HttpResponse::BadRequest().json(json!({ "error": "invalid_grant" }));
+
+    let expected_auth = format!("Basic {}", STANDARD.encode(format!("{}:{}", CLIENT_ID, CLIENT_SECRET)));
+    let client_auth = req.headers().get("Authorization").and_then(|value| value.to_str().ok());
+    if client_auth != Some(expected_auth.as_str()) {
+        return HttpResponse::Unauthorized().json(json!({ "error": "invalid_client" }));
+    }
+    if form.get("grant_type").map(String::as_str) != Some("authorization_code") {
+        return HttpResponse::BadRequest().json(json!({ "error": "unsupported_grant_type" }));
+    }
+    let Some(pending) = form.get("code").and_then(|code| state.codes.lock().unwrap().remove(code)) else {
+        return invalid_grant();
+    };
+    let verifier = form.get("code_verifier").cloned().unwrap_or_default();
+    if pending.client_id != CLIENT_ID
+        || form.get("redirect_uri") != Some(&pending.redirect_uri)
+        || URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes())) != pending.code_challenge
+    {
+        return invalid_grant();
+    }
+
+    let now = Utc::now().timestamp();
+    let claims = json!({
+        "iss": state.issuer.get().unwrap(),
+        "aud": CLIENT_ID,
+        "sub": pending.user.subject,
+        "iat": now,
+        "exp": now + 300,
+        "nonce": pending.nonce,
+        "email": pending.user.email,
+        "email_verified": pending.user.email_verified,
+        "preferred_username": pending.user.preferred_username,
+    });
+    let id_token = encode(&keys.header(), &claims, keys.encoding_key()).unwrap();
+    HttpResponse::Ok().json(json!({
+        "access_token": Uuid::new_v4().to_string(),
+        "token_type": "Bearer",
+        "expires_in": 300,
+        "id_token": id_token,
+    }))
+}
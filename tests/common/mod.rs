@@ -1,5 +1,13 @@
+// 여러 테스트 바이너리에서 공유하는 모듈이므로, 일부 헬퍼만 사용하는 바이너리에서의 경고를 막습니다.
+#![allow(dead_code)]
+
+pub mod mock_oidc;
+
+use rust_study::config::Config;
+use rust_study::models::user::{Actor, Role};
 use sqlx::PgPool;
-use test_context::{AsyncTestContext, TestContext};
+use std::sync::Arc;
+use test_context::AsyncTestContext;
 use uuid::Uuid;
 
 pub struct TestDb {
@@ -15,7 +23,7 @@ impl TestDb {
     }
 
     pub async fn clear_tables(&self) {
-        sqlx::query!("TRUNCATE TABLE users, posts, comments CASCADE")
+        sqlx::query!("TRUNCATE TABLE users, posts, comments, login_throttles, oidc_login_states CASCADE")
             .execute(&self.pool)
             .await
             .unwrap();
@@ -35,6 +43,19 @@ impl AsyncTestContext for TestDb {
     }
 }
 
+// 테스트용 설정을 생성합니다.
+// 테스트 속도를 위해 bcrypt 비용과 Argon2 매개변수를 최솟값에 가깝게 낮춥니다.
+pub fn test_config() -> Arc<Config> {
+    let mut config = Config::default();
+    config.database.url = std::env::var("DATABASE_URL").unwrap_or_default();
+    config.jwt.secret = "test-secret-key-for-integration-tests".to_string();
+    config.security.bcrypt_cost = 4;
+    config.security.argon2_memory_kib = 1024;
+    config.security.argon2_iterations = 1;
+    config.mfa.encryption_key = "dGVzdC1tZmEtZW5jcnlwdGlvbi1rZXktMzItYnl0ZXM=".to_string();
+    Arc::new(config)
+}
+
 // 테스트용 헬퍼 함수들
 pub async fn create_test_post(pool: &PgPool, title: &str, content: &str, author_id: Uuid) -> Uuid {
     let post = sqlx::query!(
@@ -63,8 +84,8 @@ pub async fn create_test_comment(
 ) -> Uuid {
     let comment = sqlx::query!(
         r#"
-        INSERT INTO comments (content, post_id, author_id, parent_id)
-        VALUES ($1, $2, $3, $4)
+        INSERT INTO comments (content, post_id, author_id, parent_id, depth)
+        VALUES ($1, $2, $3, $4, COALESCE((SELECT depth + 1 FROM comments WHERE id = $4), 1))
         RETURNING id
         "#,
         content,
@@ -79,6 +100,7 @@ pub async fn create_test_comment(
     comment.id
 }
 
+// 이메일 인증을 마친 사용자를 만듭니다.
 pub async fn create_test_user(
     pool: &PgPool,
     username: &str,
@@ -87,8 +109,8 @@ pub async fn create_test_user(
 ) -> Uuid {
     let user = sqlx::query!(
         r#"
-        INSERT INTO users (username, email, password_hash)
-        VALUES ($1, $2, $3)
+        INSERT INTO users (username, email, password_hash, email_verified_at)
+        VALUES ($1, $2, $3, CURRENT_TIMESTAMP)
         RETURNING id
         "#,
         username,
@@ -101,3 +123,16 @@ pub async fn create_test_user(
 
     user.id
 }
+
+// 인증 메일을 거치지 않고 사용자의 이메일을 인증된 것으로 표시합니다.
+pub async fn verify_user_email(pool: &PgPool, user_id: Uuid) {
+    sqlx::query!("UPDATE users SET email_verified_at = CURRENT_TIMESTAMP WHERE id = $1", user_id)
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+// 일반 사용자 역할의 Actor를 만듭니다.
+pub fn user_actor(id: Uuid) -> Actor {
+    Actor { id, role: Role::User }
+}
@@ -0,0 +1,128 @@
+use rust_study::config::{Config, MailTransport, PasswordAlgorithm};
+use std::io::Write;
+
+fn valid_config() -> Config {
+    let mut config = Config::default();
+    config.database.url = "postgres://localhost/test".to_string();
+    config.jwt.secret = "0123456789abcdef0123456789abcdef".to_string();
+    config
+}
+
+#[test]
+fn test_defaults_require_secrets() {
+    let err = Config::default().validate().unwrap_err().to_string();
+    assert!(err.contains("DATABASE_URL"));
+    assert!(err.contains("JWT_SECRET"));
+}
+
+#[test]
+fn test_valid_config_passes() {
+    assert!(valid_config().validate().is_ok());
+}
+
+#[test]
+fn test_file_values_override_defaults() {
+    let path = std::env::temp_dir().join(format!("config-test-{}.toml", uuid::Uuid::new_v4()));
+    let mut file = std::fs::File::create(&path).unwrap();
+    writeln!(file, "[server]\nport = 9090\n\n[pagination]\nmax_per_page = 50").unwrap();
+
+    let config = Config::from_file(path.to_str().unwrap()).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(config.server.port, 9090);
+    assert_eq!(config.server.host, "127.0.0.1");
+    assert_eq!(config.pagination.max_per_page, 50);
+    assert_eq!(config.pagination.default_per_page, 10);
+}
+
+#[test]
+fn test_missing_file_is_an_error() {
+    let result = Config::from_file("/nonexistent/config.toml");
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_invalid_pagination_is_rejected() {
+    let mut config = valid_config();
+    config.pagination.default_per_page = 500;
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("default_per_page"));
+}
+
+#[test]
+fn test_leeway_must_be_shorter_than_token_ttl() {
+    let mut config = valid_config();
+    config.jwt.leeway_secs = config.jwt.ttl_secs as u64;
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("JWT_LEEWAY_SECS"));
+}
+
+#[test]
+fn test_password_hashing_defaults_to_argon2id_with_valid_parameters() {
+    let mut config = valid_config();
+    assert_eq!(config.security.password_hash_algorithm, PasswordAlgorithm::Argon2id);
+    assert_eq!("bcrypt".parse::<PasswordAlgorithm>(), Ok(PasswordAlgorithm::Bcrypt));
+    assert!("scrypt".parse::<PasswordAlgorithm>().is_err());
+
+    config.security.argon2_iterations = 0;
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("ARGON2_ITERATIONS"));
+}
+
+#[test]
+fn test_login_lockout_must_follow_free_attempts() {
+    let mut config = valid_config();
+    config.security.login_account_lockout_threshold = config.security.login_account_free_attempts;
+    config.security.login_backoff_max_secs = 0;
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("LOGIN_ACCOUNT_LOCKOUT_THRESHOLD"));
+    assert!(err.contains("LOGIN_BACKOFF_MAX_SECS"));
+    assert!(!err.contains("LOGIN_IP_LOCKOUT_THRESHOLD"));
+}
+
+#[test]
+fn test_smtp_transport_requires_host_and_complete_credentials() {
+    let mut config = valid_config();
+    config.mail.transport = MailTransport::Smtp;
+    config.mail.smtp.username = "mailer".to_string();
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("SMTP_HOST"));
+    assert!(err.contains("mail.smtp.password"));
+
+    config.mail.smtp.host = "smtp.example.com".to_string();
+    config.mail.smtp.password = "secret".to_string();
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn test_mfa_encryption_key_must_be_32_bytes() {
+    let mut config = valid_config();
+    config.mfa.encryption_key = "c2hvcnQta2V5".to_string();
+    config.mfa.recovery_code_count = 0;
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("MFA_ENCRYPTION_KEY"));
+    assert!(err.contains("MFA_RECOVERY_CODE_COUNT"));
+
+    config.mfa.encryption_key = "dGVzdC1tZmEtZW5jcnlwdGlvbi1rZXktMzItYnl0ZXM=".to_string();
+    config.mfa.recovery_code_count = 10;
+    assert!(config.validate().is_ok());
+    assert!(config.mfa.encryption_key_bytes().is_some());
+}
+
+#[test]
+fn test_oidc_settings_are_checked_only_when_enabled() {
+    let mut config = valid_config();
+    config.oidc.scopes = "email".to_string();
+    assert!(config.validate().is_ok());
+
+    config.oidc.discovery_url = "https://accounts.example.com/.well-known/openid-configuration".to_string();
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("OIDC_CLIENT_ID"));
+    assert!(err.contains("OIDC_REDIRECT_URI"));
+    assert!(err.contains("OIDC_SCOPES"));
+
+    config.oidc.client_id = "board".to_string();
+    config.oidc.redirect_uri = "https://board.example.com/oidc/callback".to_string();
+    config.oidc.scopes = "openid email".to_string();
+    assert!(config.validate().is_ok());
+}
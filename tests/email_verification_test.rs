@@ -0,0 +1,206 @@
+use actix_web::{http::header, http::StatusCode, test, web, App};
+use rust_study::{
+    config::Config,
+    errors::AppError,
+    handlers::auth_handler,
+    mail::FileMailer,
+    middleware::auth_middleware::Auth,
+    models::user::{CreateUserDto, LoginDto, LoginOutcome, User},
+    services::{auth_service::AuthService, email_verification_service::EmailVerificationService},
+};
+use serde_json::json;
+use serial_test::serial;
+use std::{path::PathBuf, sync::Arc};
+use test_context::test_context;
+use uuid::Uuid;
+
+mod common;
+use common::TestDb;
+
+// 테스트마다 메일을 저장할 빈 디렉터리를 만듭니다.
+fn mail_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("mail-test-{}", Uuid::new_v4()))
+}
+
+fn verification_service(pool: &sqlx::PgPool, config: Arc<Config>, dir: &PathBuf) -> EmailVerificationService {
+    let mailer = Arc::new(FileMailer::new(dir, &config.mail.from));
+    EmailVerificationService::new(pool.clone(), config, mailer)
+}
+
+// 가장 최근에 저장된 메일에서 인증 토큰을 꺼냅니다.
+fn last_sent_token(dir: &PathBuf) -> String {
+    let mut files: Vec<_> = std::fs::read_dir(dir).unwrap().map(|entry| entry.unwrap().path()).collect();
+    files.sort();
+    let message = std::fs::read_to_string(files.last().unwrap()).unwrap();
+    let start = message.find("?token=").unwrap() + "?token=".len();
+    message[start..].split_whitespace().next().unwrap().to_string()
+}
+
+fn sent_count(dir: &PathBuf) -> usize {
+    std::fs::read_dir(dir).map_or(0, |entries| entries.count())
+}
+
+async fn register_user(pool: &sqlx::PgPool) -> User {
+    AuthService::new(pool.clone(), common::test_config())
+        .register(CreateUserDto {
+            username: format!("user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("{}@example.com", Uuid::new_v4()),
+            password: "password123".to_string(),
+        })
+        .await
+        .unwrap()
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_verify_email_with_mailed_token(ctx: &TestDb) {
+    let dir = mail_dir();
+    let service = verification_service(&ctx.pool, common::test_config(), &dir);
+    let user = register_user(&ctx.pool).await;
+    assert!(user.email_verified_at.is_none());
+
+    service.send_verification(&user).await.unwrap();
+    let message = std::fs::read_to_string(std::fs::read_dir(&dir).unwrap().next().unwrap().unwrap().path()).unwrap();
+    assert!(message.contains(&format!("To: {}\r\n", user.email)));
+
+    let token = last_sent_token(&dir);
+    let verified = service.verify_email(&token).await.unwrap();
+    assert_eq!(verified.id, user.id);
+    assert!(verified.email_verified_at.is_some());
+
+    // Tokens are single-use, and verified users cannot ask for another one
+    assert!(matches!(service.verify_email(&token).await, Err(AppError::BadRequest(_))));
+    assert!(matches!(service.resend_verification(user.id).await, Err(AppError::Conflict(_))));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_resend_is_rate_limited_and_replaces_old_token(ctx: &TestDb) {
+    let dir = mail_dir();
+    let service = verification_service(&ctx.pool, common::test_config(), &dir);
+    let user = register_user(&ctx.pool).await;
+
+    service.send_verification(&user).await.unwrap();
+    let first_token = last_sent_token(&dir);
+
+    match service.resend_verification(user.id).await {
+        Err(AppError::RateLimited { retry_after: Some(secs), .. }) => assert!((1..=60).contains(&secs)),
+        other => panic!("expected RateLimited, got {:?}", other),
+    }
+    assert_eq!(sent_count(&dir), 1);
+
+    let mut config = (*common::test_config()).clone();
+    config.security.email_verification_resend_secs = 0;
+    let service = verification_service(&ctx.pool, Arc::new(config), &dir);
+    service.resend_verification(user.id).await.unwrap();
+    assert_eq!(sent_count(&dir), 2);
+
+    // Only the most recently mailed token works
+    let second_token = last_sent_token(&dir);
+    assert_ne!(first_token, second_token);
+    assert!(matches!(service.verify_email(&first_token).await, Err(AppError::BadRequest(_))));
+    assert!(service.verify_email(&second_token).await.is_ok());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_expired_or_stale_tokens_are_rejected(ctx: &TestDb) {
+    let dir = mail_dir();
+    let service = verification_service(&ctx.pool, common::test_config(), &dir);
+    let user = register_user(&ctx.pool).await;
+
+    service.send_verification(&user).await.unwrap();
+    let token = last_sent_token(&dir);
+    sqlx::query!("UPDATE email_verification_tokens SET expires_at = CURRENT_TIMESTAMP - INTERVAL '1 second'")
+        .execute(&ctx.pool)
+        .await
+        .unwrap();
+    assert!(matches!(service.verify_email(&token).await, Err(AppError::BadRequest(_))));
+
+    // A token only verifies the address it was sent to
+    service.send_verification(&user).await.unwrap();
+    let token = last_sent_token(&dir);
+    sqlx::query!("UPDATE users SET email = 'changed@example.com' WHERE id = $1", user.id)
+        .execute(&ctx.pool)
+        .await
+        .unwrap();
+    assert!(matches!(service.verify_email(&token).await, Err(AppError::BadRequest(_))));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test_context(TestDb)]
+#[actix_web::test]
+#[serial]
+async fn test_verification_endpoints(ctx: &TestDb) {
+    let dir = mail_dir();
+    let config = common::test_config();
+    let auth_service = web::Data::new(AuthService::new(ctx.pool.clone(), config.clone()));
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::from(config.clone()))
+            .app_data(auth_service.clone())
+            .app_data(web::Data::new(verification_service(&ctx.pool, config, &dir)))
+            .service(
+                web::scope("/api/auth")
+                    .route("/register", web::post().to(auth_handler::register))
+                    .route("/verify-email", web::post().to(auth_handler::verify_email))
+                    .service(
+                        web::resource("/resend-verification")
+                            .wrap(Auth::required())
+                            .route(web::post().to(auth_handler::resend_verification)),
+                    ),
+            ),
+    )
+    .await;
+
+    // Registering sends the verification email
+    let req = test::TestRequest::post()
+        .uri("/api/auth/register")
+        .set_json(json!({ "username": "verifier", "email": "verifier@example.com", "password": "password123" }))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["email_verified_at"], serde_json::Value::Null);
+    assert_eq!(sent_count(&dir), 1);
+
+    let req = test::TestRequest::post().uri("/api/auth/resend-verification").to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::UNAUTHORIZED);
+
+    let login = auth_service
+        .login(LoginDto {
+            email: "verifier@example.com".to_string(),
+            password: "password123".to_string(),
+        })
+        .await
+        .and_then(LoginOutcome::tokens)
+        .unwrap();
+    let req = test::TestRequest::post()
+        .uri("/api/auth/resend-verification")
+        .insert_header((header::AUTHORIZATION, format!("Bearer {}", login.token)))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    let req = test::TestRequest::post()
+        .uri("/api/auth/verify-email")
+        .set_json(json!({ "token": "not-a-real-token" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::BAD_REQUEST);
+
+    let req = test::TestRequest::post()
+        .uri("/api/auth/verify-email")
+        .set_json(json!({ "token": last_sent_token(&dir) }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert!(body["email_verified_at"].is_string());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
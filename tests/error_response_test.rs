@@ -0,0 +1,166 @@
+use actix_web::{
+    body::{BoxBody, EitherBody},
+    dev::ServiceResponse,
+    http::StatusCode,
+    test, web, App, HttpResponse,
+};
+use rust_study::{
+    errors::{self, AppError},
+    middleware::{auth_middleware::Auth, request_id::RequestId},
+    services::auth_service::AuthService,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+mod common;
+
+async fn not_found() -> Result<HttpResponse, AppError> {
+    Err(AppError::NotFound("Post not found".to_string()))
+}
+
+async fn invalid_title() -> Result<HttpResponse, AppError> {
+    Err(AppError::validation("title", "title must not be empty"))
+}
+
+async fn internal() -> Result<HttpResponse, AppError> {
+    Err(AppError::Internal("connection refused by db-primary:5432".to_string()))
+}
+
+async fn ok() -> HttpResponse {
+    HttpResponse::Ok().finish()
+}
+
+async fn by_id(_id: web::Path<Uuid>) -> HttpResponse {
+    HttpResponse::Ok().finish()
+}
+
+async fn create_test_app(
+) -> impl actix_web::dev::Service<actix_http::Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = actix_web::Error> {
+    // 데이터베이스에 접속하지 않는 요청만 보내므로 지연 연결 풀을 사용합니다.
+    let config = common::test_config();
+    let pool = sqlx::PgPool::connect_lazy(&config.database.url).unwrap();
+    test::init_service(
+        App::new()
+            .wrap(RequestId)
+            .app_data(web::Data::new(AuthService::new(pool, config)))
+            .app_data(web::PathConfig::default().error_handler(errors::path_error_handler))
+            .route("/not-found", web::get().to(not_found))
+            .route("/invalid", web::post().to(invalid_title))
+            .route("/internal", web::get().to(internal))
+            .route("/ok", web::get().to(ok))
+            .route("/items/{id}", web::get().to(by_id))
+            .service(web::scope("/protected").wrap(Auth::required()).route("", web::get().to(ok))),
+    )
+    .await
+}
+
+// 응답의 X-Request-Id 헤더 값을 반환합니다.
+fn request_id_header<B>(resp: &ServiceResponse<B>) -> String {
+    resp.headers().get("x-request-id").unwrap().to_str().unwrap().to_string()
+}
+
+#[actix_web::test]
+async fn test_error_body_has_code_message_fields_and_request_id() {
+    let app = create_test_app().await;
+
+    let req = test::TestRequest::get().uri("/not-found").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    let request_id = request_id_header(&resp);
+    assert!(Uuid::parse_str(&request_id).is_ok());
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(
+        body,
+        json!({
+            "code": "NOT_FOUND",
+            "message": "Post not found",
+            "fields": [],
+            "request_id": request_id,
+        })
+    );
+}
+
+#[actix_web::test]
+async fn test_validation_error_lists_fields() {
+    let app = create_test_app().await;
+
+    let req = test::TestRequest::post().uri("/invalid").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["code"], "VALIDATION_FAILED");
+    assert_eq!(body["fields"], json!([{ "field": "title", "message": "title must not be empty" }]));
+}
+
+#[actix_web::test]
+async fn test_internal_error_hides_details() {
+    let app = create_test_app().await;
+
+    let req = test::TestRequest::get().uri("/internal").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["code"], "INTERNAL_ERROR");
+    assert_eq!(body["message"], "Internal server error");
+    assert!(!body.to_string().contains("db-primary"));
+}
+
+#[actix_web::test]
+async fn test_extractor_and_middleware_errors_use_the_same_shape() {
+    let app = create_test_app().await;
+
+    let req = test::TestRequest::get().uri("/items/not-a-uuid").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let request_id = request_id_header(&resp);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["code"], "BAD_REQUEST");
+    assert_eq!(body["request_id"], request_id);
+
+    // The auth middleware answers before any handler runs
+    let req = test::TestRequest::get().uri("/protected").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    let request_id = request_id_header(&resp);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["code"], "UNAUTHORIZED");
+    assert_eq!(body["request_id"], request_id);
+}
+
+#[actix_web::test]
+async fn test_client_request_id_is_echoed_back() {
+    let app = create_test_app().await;
+
+    let req = test::TestRequest::get()
+        .uri("/not-found")
+        .insert_header(("X-Request-Id", "client-trace_42"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(request_id_header(&resp), "client-trace_42");
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["request_id"], "client-trace_42");
+
+    // Successful responses carry the header too
+    let req = test::TestRequest::get()
+        .uri("/ok")
+        .insert_header(("X-Request-Id", "client-trace_43"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(request_id_header(&resp), "client-trace_43");
+}
+
+#[actix_web::test]
+async fn test_invalid_client_request_id_is_replaced() {
+    let app = create_test_app().await;
+
+    let req = test::TestRequest::get()
+        .uri("/ok")
+        .insert_header(("X-Request-Id", "not valid; <script>"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    let request_id = request_id_header(&resp);
+    assert_ne!(request_id, "not valid; <script>");
+    assert!(Uuid::parse_str(&request_id).is_ok());
+}
@@ -0,0 +1,183 @@
+use actix_web::{test, web, App};
+use jsonwebtoken::{decode_header, Algorithm};
+use rust_study::{
+    config::{Config, JwtKeyConfig},
+    errors::{AppError, TokenError},
+    handlers::auth_handler,
+    models::user::{AuthResponse, CreateUserDto, LoginDto, LoginOutcome},
+    services::{auth_service::AuthService, jwt_keys::JwtKeys},
+};
+use serial_test::serial;
+use std::sync::Arc;
+use test_context::test_context;
+use uuid::Uuid;
+
+mod common;
+use common::TestDb;
+
+fn fixture(name: &str) -> String {
+    format!("{}/tests/fixtures/jwt/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+// 주어진 이름의 fixture 키 쌍으로 키 설정을 만듭니다. 검증 전용 키는 개인 키를 생략합니다.
+fn key(kid: &str, algorithm: Algorithm, name: &str, signing: bool) -> JwtKeyConfig {
+    JwtKeyConfig {
+        kid: kid.to_string(),
+        algorithm,
+        public_key_file: fixture(&format!("{}-public.pem", name)),
+        private_key_file: signing.then(|| fixture(&format!("{}-private.pem", name))),
+    }
+}
+
+fn config_with_keys(signing_kid: &str, keys: Vec<JwtKeyConfig>) -> Arc<Config> {
+    let mut config = (*common::test_config()).clone();
+    config.jwt.signing_kid = signing_kid.to_string();
+    config.jwt.keys = keys;
+    Arc::new(config)
+}
+
+async fn register_and_login(service: &AuthService) -> AuthResponse {
+    let dto = CreateUserDto {
+        username: format!("user_{}", &Uuid::new_v4().to_string()[..8]),
+        email: format!("{}@example.com", Uuid::new_v4()),
+        password: "password123".to_string(),
+    };
+    service.register(dto.clone()).await.unwrap();
+    service
+        .login(LoginDto {
+            email: dto.email,
+            password: dto.password,
+        })
+        .await
+        .and_then(LoginOutcome::tokens)
+        .unwrap()
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_asymmetric_tokens_carry_kid(ctx: &TestDb) {
+    for (algorithm, name) in [(Algorithm::RS256, "rsa"), (Algorithm::ES256, "ec"), (Algorithm::EdDSA, "ed25519")] {
+        let config = config_with_keys("current", vec![key("current", algorithm, name, true)]);
+        let service = AuthService::new(ctx.pool.clone(), config);
+
+        let login = register_and_login(&service).await;
+        let header = decode_header(&login.token).unwrap();
+        assert_eq!(header.alg, algorithm);
+        assert_eq!(header.kid.as_deref(), Some("current"));
+        assert_eq!(service.verify_token(&login.token).unwrap().sub, login.user.id.to_string());
+    }
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_key_rotation_accepts_previous_key(ctx: &TestDb) {
+    let before = AuthService::new(
+        ctx.pool.clone(),
+        config_with_keys("2024-01", vec![key("2024-01", Algorithm::RS256, "rsa", true)]),
+    );
+    let old_token = register_and_login(&before).await.token;
+
+    // The new key signs, the old key stays around for verification only
+    let during = AuthService::new(
+        ctx.pool.clone(),
+        config_with_keys(
+            "2024-06",
+            vec![
+                key("2024-06", Algorithm::EdDSA, "ed25519", true),
+                key("2024-01", Algorithm::RS256, "rsa", false),
+            ],
+        ),
+    );
+    let new_token = register_and_login(&during).await.token;
+    assert_eq!(decode_header(&new_token).unwrap().kid.as_deref(), Some("2024-06"));
+    assert!(during.verify_token(&old_token).is_ok());
+    assert!(during.verify_token(&new_token).is_ok());
+
+    // Once the old key is removed its tokens are rejected
+    let after = AuthService::new(
+        ctx.pool.clone(),
+        config_with_keys("2024-06", vec![key("2024-06", Algorithm::EdDSA, "ed25519", true)]),
+    );
+    assert!(after.verify_token(&new_token).is_ok());
+    assert!(matches!(
+        after.verify_token(&old_token),
+        Err(AppError::InvalidToken(TokenError::BadSignature))
+    ));
+
+    // Tokens signed with the shared secret no longer verify either
+    let hs256_token = register_and_login(&AuthService::new(ctx.pool.clone(), common::test_config())).await.token;
+    assert!(matches!(
+        after.verify_token(&hs256_token),
+        Err(AppError::InvalidToken(TokenError::BadSignature))
+    ));
+}
+
+#[actix_web::test]
+async fn test_mismatched_key_pair_is_rejected() {
+    let mut mismatched = key("current", Algorithm::ES256, "ec", true);
+    mismatched.public_key_file = fixture("ec-other-public.pem");
+    let config = config_with_keys("current", vec![mismatched]);
+
+    let err = JwtKeys::from_config(&config.jwt).err().unwrap().to_string();
+    assert!(err.contains("current"), "{}", err);
+}
+
+#[actix_web::test]
+async fn test_signing_kid_must_have_private_key() {
+    let mut config = (*config_with_keys("old", vec![key("old", Algorithm::RS256, "rsa", false)])).clone();
+    config.database.url = "postgres://localhost/test".to_string();
+
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("private_key_file"), "{}", err);
+}
+
+#[actix_web::test]
+async fn test_jwks_endpoint() {
+    let config = config_with_keys(
+        "rsa",
+        vec![
+            key("rsa", Algorithm::RS256, "rsa", true),
+            key("ec", Algorithm::ES256, "ec", false),
+            key("ed", Algorithm::EdDSA, "ed25519", false),
+        ],
+    );
+    let pool = sqlx::PgPool::connect_lazy(&config.database.url).unwrap();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(AuthService::new(pool, config)))
+            .route("/.well-known/jwks.json", web::get().to(auth_handler::jwks)),
+    )
+    .await;
+
+    let req = test::TestRequest::get().uri("/.well-known/jwks.json").to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let keys = body["keys"].as_array().unwrap();
+    assert_eq!(keys.len(), 3);
+
+    assert_eq!(keys[0]["kid"], "rsa");
+    assert_eq!(keys[0]["kty"], "RSA");
+    assert_eq!(keys[0]["alg"], "RS256");
+    assert_eq!(keys[0]["use"], "sig");
+    assert_eq!(keys[0]["e"], "AQAB");
+    assert!(keys[0]["n"].as_str().unwrap().len() > 300);
+
+    assert_eq!(keys[1]["kty"], "EC");
+    assert_eq!(keys[1]["crv"], "P-256");
+    assert_eq!(keys[1]["x"].as_str().unwrap().len(), 43);
+    assert_eq!(keys[1]["y"].as_str().unwrap().len(), 43);
+
+    assert_eq!(keys[2]["kty"], "OKP");
+    assert_eq!(keys[2]["crv"], "Ed25519");
+    assert_eq!(keys[2]["alg"], "EdDSA");
+
+    // Private key material is never published
+    for key in keys {
+        assert!(key.get("d").is_none());
+    }
+
+    // Other services can build a verification key straight from the published JWK
+    let jwk: jsonwebtoken::jwk::Jwk = serde_json::from_value(keys[0].clone()).unwrap();
+    assert!(jsonwebtoken::DecodingKey::from_jwk(&jwk).is_ok());
+}
@@ -0,0 +1,278 @@
+use actix_web::{http::header, http::StatusCode, test, web, App};
+use rust_study::{
+    config::Config,
+    errors::AppError,
+    handlers::auth_handler,
+    models::user::{AuthResponse, CreateUserDto, LoginDto, LoginOutcome},
+    services::{auth_service::AuthService, login_throttle::backoff_secs},
+};
+use serde_json::json;
+use serial_test::serial;
+use std::net::{IpAddr, Ipv4Addr};
+use std::sync::Arc;
+use test_context::test_context;
+
+mod common;
+use common::TestDb;
+
+const EMAIL: &str = "target@example.com";
+const PASSWORD: &str = "password123";
+const ATTACKER_IP: IpAddr = IpAddr::V4(Ipv4Addr::new(203, 0, 113, 7));
+
+// 대기 시간 없이 3번 실패하면 잠기는 설정입니다.
+fn lockout_config() -> Arc<Config> {
+    let mut config = (*common::test_config()).clone();
+    config.security.login_account_free_attempts = 0;
+    config.security.login_account_lockout_threshold = 3;
+    config.security.login_ip_free_attempts = 0;
+    config.security.login_ip_lockout_threshold = 4;
+    config.security.login_backoff_base_secs = 0;
+    Arc::new(config)
+}
+
+async fn register(service: &AuthService) {
+    service
+        .register(CreateUserDto {
+            username: "target".to_string(),
+            email: EMAIL.to_string(),
+            password: PASSWORD.to_string(),
+        })
+        .await
+        .unwrap();
+}
+
+async fn login(service: &AuthService, email: &str, password: &str, ip: Option<IpAddr>) -> Result<AuthResponse, AppError> {
+    let dto = LoginDto {
+        email: email.to_string(),
+        password: password.to_string(),
+    };
+    service.login_from(dto, ip).await.and_then(LoginOutcome::tokens)
+}
+
+async fn audit_events(pool: &sqlx::PgPool) -> Vec<(String, Option<uuid::Uuid>)> {
+    sqlx::query!("SELECT event, user_id FROM auth_audit_log ORDER BY created_at")
+        .fetch_all(pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| (row.event, row.user_id))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_backoff_doubles_after_free_attempts() {
+    let delays: Vec<i64> = (1..=9).map(|failures| backoff_secs(failures, 3, 1, 30)).collect();
+    assert_eq!(delays, vec![0, 0, 0, 1, 2, 4, 8, 16, 30]);
+    assert_eq!(backoff_secs(i32::MAX, 0, 1, 60), 60);
+    assert_eq!(backoff_secs(10, 3, 0, 60), 0);
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_failures_back_off_exponentially(ctx: &TestDb) {
+    let mut config = (*common::test_config()).clone();
+    config.security.login_account_free_attempts = 1;
+    config.security.login_backoff_base_secs = 30;
+    let service = AuthService::new(ctx.pool.clone(), Arc::new(config));
+    register(&service).await;
+
+    assert!(matches!(login(&service, EMAIL, "wrong", None).await, Err(AppError::Unauthorized(_))));
+    assert!(matches!(login(&service, EMAIL, "wrong", None).await, Err(AppError::Unauthorized(_))));
+
+    // Even the right password has to wait until the backoff has passed
+    match login(&service, EMAIL, PASSWORD, None).await {
+        Err(AppError::RateLimited { retry_after: Some(secs), .. }) => assert!((1..=30).contains(&secs)),
+        other => panic!("expected RateLimited, got {:?}", other.map(|_| ())),
+    }
+
+    sqlx::query!("UPDATE login_throttles SET last_failed_at = last_failed_at - INTERVAL '30 seconds'")
+        .execute(&ctx.pool)
+        .await
+        .unwrap();
+    assert!(login(&service, EMAIL, PASSWORD, None).await.is_ok());
+
+    // A successful login clears the account's failures
+    let remaining = sqlx::query_scalar!("SELECT COUNT(*) FROM login_throttles WHERE scope = 'account'")
+        .fetch_one(&ctx.pool)
+        .await
+        .unwrap();
+    assert_eq!(remaining, Some(0));
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_account_lockout_is_audited(ctx: &TestDb) {
+    let service = AuthService::new(ctx.pool.clone(), lockout_config());
+    register(&service).await;
+    let user_id = sqlx::query_scalar!("SELECT id FROM users WHERE email = $1", EMAIL)
+        .fetch_one(&ctx.pool)
+        .await
+        .unwrap();
+
+    for _ in 0..3 {
+        assert!(matches!(login(&service, EMAIL, "wrong", None).await, Err(AppError::Unauthorized(_))));
+    }
+    // The email is matched case-insensitively
+    let result = login(&service, "Target@Example.com", PASSWORD, None).await;
+    assert!(matches!(result, Err(AppError::RateLimited { retry_after: Some(_), .. })));
+    assert_eq!(audit_events(&ctx.pool).await, vec![("account_locked".to_string(), Some(user_id))]);
+
+    sqlx::query!("UPDATE login_throttles SET locked_until = CURRENT_TIMESTAMP - INTERVAL '1 second'")
+        .execute(&ctx.pool)
+        .await
+        .unwrap();
+    assert!(login(&service, EMAIL, PASSWORD, None).await.is_ok());
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_unknown_emails_are_throttled_like_accounts(ctx: &TestDb) {
+    let service = AuthService::new(ctx.pool.clone(), lockout_config());
+
+    for _ in 0..3 {
+        match login(&service, "nobody@example.com", "wrong", None).await {
+            Err(AppError::Unauthorized(message)) => assert_eq!(message, "Invalid credentials"),
+            other => panic!("expected Unauthorized, got {:?}", other.map(|_| ())),
+        }
+    }
+    let result = login(&service, "nobody@example.com", "wrong", None).await;
+    assert!(matches!(result, Err(AppError::RateLimited { .. })));
+    assert_eq!(audit_events(&ctx.pool).await, vec![("account_locked".to_string(), None)]);
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_ip_lockout_spans_accounts(ctx: &TestDb) {
+    let service = AuthService::new(ctx.pool.clone(), lockout_config());
+    register(&service).await;
+
+    // Spreading guesses over many emails does not get around the per-IP limit
+    for i in 0..4 {
+        let email = format!("guess{}@example.com", i);
+        assert!(matches!(login(&service, &email, "wrong", Some(ATTACKER_IP)).await, Err(AppError::Unauthorized(_))));
+    }
+    let result = login(&service, EMAIL, PASSWORD, Some(ATTACKER_IP)).await;
+    assert!(matches!(result, Err(AppError::RateLimited { .. })));
+    assert!(audit_events(&ctx.pool).await.contains(&("ip_locked".to_string(), None)));
+
+    // Other clients are not affected
+    let other_ip = IpAddr::V4(Ipv4Addr::new(198, 51, 100, 1));
+    assert!(login(&service, EMAIL, PASSWORD, Some(other_ip)).await.is_ok());
+}
+
+#[test_context(TestDb)]
+#[actix_web::test]
+#[serial]
+async fn test_login_endpoint_sets_retry_after(ctx: &TestDb) {
+    let config = lockout_config();
+    let service = web::Data::new(AuthService::new(ctx.pool.clone(), config.clone()));
+    register(&service).await;
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::from(config))
+            .app_data(service.clone())
+            .route("/api/auth/login", web::post().to(auth_handler::login)),
+    )
+    .await;
+
+    let mut statuses = Vec::new();
+    for _ in 0..4 {
+        let req = test::TestRequest::post()
+            .uri("/api/auth/login")
+            .peer_addr("203.0.113.7:40000".parse().unwrap())
+            .set_json(json!({ "email": EMAIL, "password": "wrong" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        statuses.push(resp.status());
+        if resp.status() == StatusCode::TOO_MANY_REQUESTS {
+            assert!(resp.headers().contains_key(header::RETRY_AFTER));
+        }
+    }
+    assert_eq!(
+        statuses,
+        vec![StatusCode::UNAUTHORIZED, StatusCode::UNAUTHORIZED, StatusCode::UNAUTHORIZED, StatusCode::TOO_MANY_REQUESTS]
+    );
+
+    let ip_failures = sqlx::query_scalar!("SELECT failed_count FROM login_throttles WHERE scope = 'ip' AND subject = '203.0.113.7'")
+        .fetch_one(&ctx.pool)
+        .await
+        .unwrap();
+    assert_eq!(ip_failures, 3);
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_concurrent_guesses_cannot_exceed_the_lockout(ctx: &TestDb) {
+    let service = AuthService::new(ctx.pool.clone(), lockout_config());
+    register(&service).await;
+
+    // Every attempt is counted before its password is checked, so parallel requests cannot all pass the check
+    let results = futures::future::join_all((0..10).map(|_| login(&service, EMAIL, "wrong", None))).await;
+    let unauthorized = results.iter().filter(|r| matches!(r, Err(AppError::Unauthorized(_)))).count();
+    let limited = results.iter().filter(|r| matches!(r, Err(AppError::RateLimited { .. }))).count();
+    assert_eq!((unauthorized, limited), (3, 7));
+
+    let failures = sqlx::query_scalar!("SELECT failed_count FROM login_throttles WHERE scope = 'account'")
+        .fetch_one(&ctx.pool)
+        .await
+        .unwrap();
+    assert_eq!(failures, 3);
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_email_case_does_not_change_the_account(ctx: &TestDb) {
+    let service = AuthService::new(ctx.pool.clone(), lockout_config());
+    service
+        .register(CreateUserDto {
+            username: "target".to_string(),
+            email: "Target@Example.com".to_string(),
+            password: PASSWORD.to_string(),
+        })
+        .await
+        .unwrap();
+    let user_id = sqlx::query_scalar!("SELECT id FROM users WHERE email = $1", EMAIL)
+        .fetch_one(&ctx.pool)
+        .await
+        .unwrap();
+
+    // Failures under any spelling of the email are tied to the same user
+    for email in ["TARGET@EXAMPLE.COM", " target@example.com", "Target@example.COM"] {
+        assert!(matches!(login(&service, email, "wrong", None).await, Err(AppError::Unauthorized(_))));
+    }
+    assert_eq!(audit_events(&ctx.pool).await, vec![("account_locked".to_string(), Some(user_id))]);
+
+    sqlx::query!("UPDATE login_throttles SET locked_until = CURRENT_TIMESTAMP - INTERVAL '1 second'")
+        .execute(&ctx.pool)
+        .await
+        .unwrap();
+    assert!(login(&service, "TARGET@example.com", PASSWORD, None).await.is_ok());
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_right_password_does_not_keep_a_reserved_failure(ctx: &TestDb) {
+    let service = AuthService::new(ctx.pool.clone(), lockout_config());
+    register(&service).await;
+
+    for _ in 0..2 {
+        assert!(matches!(login(&service, EMAIL, "wrong", Some(ATTACKER_IP)).await, Err(AppError::Unauthorized(_))));
+    }
+    assert!(login(&service, EMAIL, PASSWORD, Some(ATTACKER_IP)).await.is_ok());
+
+    // The account starts over and the IP keeps only its real failures
+    let rows = sqlx::query!("SELECT scope, failed_count, locked_until FROM login_throttles ORDER BY scope")
+        .fetch_all(&ctx.pool)
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!((rows[0].scope.as_str(), rows[0].failed_count), ("ip", 2));
+    assert!(rows[0].locked_until.is_none());
+}
@@ -0,0 +1,121 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use rust_study::config::{SmtpConfig, SmtpSecurity};
+use rust_study::mail::{Email, FileMailer, Mailer, SmtpMailer};
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::thread;
+
+fn email() -> Email {
+    Email {
+        to: "reader@example.com".to_string(),
+        subject: "Hello".to_string(),
+        body: "first line\n.starts with a dot\nlast line".to_string(),
+    }
+}
+
+// 한 번의 SMTP 세션을 받아 클라이언트가 보낸 줄들을 그대로 돌려주는 가짜 서버를 띄웁니다.
+fn fake_smtp_server() -> (u16, thread::JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let handle = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut writer = stream.try_clone().unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut received = Vec::new();
+        let mut in_data = false;
+
+        writer.write_all(b"220 fake ESMTP ready\r\n").unwrap();
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).unwrap() == 0 {
+                break;
+            }
+            let line = line.trim_end_matches("\r\n").to_string();
+            received.push(line.clone());
+
+            let reply: &[u8] = if in_data {
+                if line != "." {
+                    continue;
+                }
+                in_data = false;
+                b"250 queued\r\n"
+            } else if line.starts_with("EHLO") {
+                b"250-fake greets you\r\n250 AUTH PLAIN\r\n"
+            } else if line.starts_with("AUTH PLAIN") {
+                b"235 authenticated\r\n"
+            } else if line == "DATA" {
+                in_data = true;
+                b"354 go ahead\r\n"
+            } else if line == "QUIT" {
+                writer.write_all(b"221 bye\r\n").unwrap();
+                break;
+            } else {
+                b"250 ok\r\n"
+            };
+            writer.write_all(reply).unwrap();
+        }
+        received
+    });
+
+    (port, handle)
+}
+
+#[test]
+fn test_message_headers_cannot_be_injected() {
+    let mut email = email();
+    email.subject = "Hello\r\nBcc: attacker@example.com".to_string();
+
+    let message = email.to_message("Board <no-reply@board.example>");
+    assert!(message.contains("Subject: Hello  Bcc: attacker@example.com\r\n"));
+    assert!(!message.contains("\r\nBcc:"));
+    assert!(message.contains("@board.example>\r\n"));
+    assert!(message.ends_with("\r\nfirst line\r\n.starts with a dot\r\nlast line\r\n"));
+}
+
+#[tokio::test]
+async fn test_smtp_mailer_speaks_smtp() {
+    let (port, server) = fake_smtp_server();
+    let config = SmtpConfig {
+        host: "127.0.0.1".to_string(),
+        port,
+        security: SmtpSecurity::None,
+        username: "mailer".to_string(),
+        password: "secret".to_string(),
+        timeout_secs: 5,
+    };
+
+    SmtpMailer::new(&config, "Board <no-reply@board.example>")
+        .unwrap()
+        .send(&email())
+        .await
+        .unwrap();
+    let received = server.join().unwrap();
+
+    assert!(received[0].starts_with("EHLO "));
+    assert_eq!(received[1], format!("AUTH PLAIN {}", STANDARD.encode("\0mailer\0secret")));
+    assert_eq!(received[2], "MAIL FROM:<no-reply@board.example>");
+    assert_eq!(received[3], "RCPT TO:<reader@example.com>");
+    assert_eq!(received[4], "DATA");
+    assert!(received.contains(&"To: reader@example.com".to_string()));
+    // Lines starting with a dot are escaped so they don't end the message early
+    assert!(received.contains(&"..starts with a dot".to_string()));
+    assert_eq!(&received[received.len() - 2..], [".", "QUIT"]);
+}
+
+#[tokio::test]
+async fn test_file_mailer_writes_eml_files() {
+    let dir = std::env::temp_dir().join(format!("mail-test-{}", uuid::Uuid::new_v4()));
+    let mailer = FileMailer::new(&dir, "no-reply@board.example");
+
+    mailer.send(&email()).await.unwrap();
+    mailer.send(&email()).await.unwrap();
+
+    let files: Vec<_> = std::fs::read_dir(mailer.dir()).unwrap().map(|e| e.unwrap().path()).collect();
+    assert_eq!(files.len(), 2);
+    assert!(files.iter().all(|path| path.extension().unwrap() == "eml"));
+    let message = std::fs::read_to_string(&files[0]).unwrap();
+    assert!(message.starts_with("From: no-reply@board.example\r\nTo: reader@example.com\r\n"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,376 @@
+use actix_web::{http::header, http::StatusCode, test, web, App};
+use chrono::Utc;
+use data_encoding::BASE32_NOPAD;
+use rust_study::{
+    config::Config,
+    errors::AppError,
+    handlers::{auth_handler, user_handler},
+    middleware::auth_middleware::Auth,
+    models::{
+        mfa::{DisableTotpDto, MfaChallenge, MfaVerifyDto},
+        user::{CreateUserDto, LoginDto, LoginOutcome, User},
+    },
+    services::{auth_service::AuthService, mfa_service::MfaService, secret_box::SecretBox, totp},
+};
+use serde_json::json;
+use serial_test::serial;
+use std::sync::Arc;
+use test_context::test_context;
+
+mod common;
+use common::TestDb;
+
+const EMAIL: &str = "moderator@example.com";
+const PASSWORD: &str = "password123";
+
+async fn register(service: &AuthService) -> User {
+    service
+        .register(CreateUserDto {
+            username: "moderator".to_string(),
+            email: EMAIL.to_string(),
+            password: PASSWORD.to_string(),
+        })
+        .await
+        .unwrap()
+}
+
+async fn login(service: &AuthService) -> LoginOutcome {
+    service
+        .login(LoginDto {
+            email: EMAIL.to_string(),
+            password: PASSWORD.to_string(),
+        })
+        .await
+        .unwrap()
+}
+
+fn challenge(outcome: LoginOutcome) -> MfaChallenge {
+    match outcome {
+        LoginOutcome::MfaRequired(challenge) => challenge,
+        other => panic!("expected MfaRequired, got {:?}", other),
+    }
+}
+
+fn verify_dto(challenge: &MfaChallenge, code: &str) -> MfaVerifyDto {
+    MfaVerifyDto {
+        mfa_token: challenge.mfa_token.clone(),
+        code: code.to_string(),
+    }
+}
+
+// 현재 시각에서 offset 단계만큼 떨어진 시간 단계의 코드를 만듭니다.
+fn code(secret: &[u8], offset: i64) -> String {
+    totp::code_at(secret, totp::step_at(Utc::now().timestamp()) + offset)
+}
+
+fn disable_dto(current_password: &str, code: &str) -> DisableTotpDto {
+    DisableTotpDto {
+        current_password: current_password.to_string(),
+        code: code.to_string(),
+    }
+}
+
+// TOTP를 등록하고 확인까지 마친 뒤, 비밀 키와 복구 코드를 반환합니다.
+async fn enable_totp(mfa: &MfaService, user: &User) -> (Vec<u8>, Vec<String>) {
+    let enrollment = mfa.begin_totp_enrollment(user.id).await.unwrap();
+    let secret = BASE32_NOPAD.decode(enrollment.secret.as_bytes()).unwrap();
+    let codes = mfa.confirm_totp(user.id, &code(&secret, 0)).await.unwrap();
+    (secret, codes.recovery_codes)
+}
+
+#[tokio::test]
+async fn test_totp_matches_rfc_6238_vectors() {
+    let secret = b"12345678901234567890";
+    assert_eq!(totp::code_at(secret, totp::step_at(59)), "287082");
+    assert_eq!(totp::code_at(secret, totp::step_at(1111111109)), "081804");
+    assert_eq!(totp::code_at(secret, totp::step_at(1234567890)), "005924");
+
+    // A code from the previous step is accepted within the allowed clock skew only
+    assert_eq!(totp::verify(secret, "081804", 1111111109 + 30, 1), Some(totp::step_at(1111111109)));
+    assert_eq!(totp::verify(secret, "081804", 1111111109 + 30, 0), None);
+    assert_eq!(totp::verify(secret, "81804", 1111111109, 1), None);
+
+    let uri = totp::provisioning_uri("Rust Study Board", "a+b@example.com", secret);
+    assert_eq!(
+        uri,
+        "otpauth://totp/Rust%20Study%20Board:a%2Bb@example.com?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ\
+         &issuer=Rust%20Study%20Board&algorithm=SHA1&digits=6&period=30"
+    );
+}
+
+#[tokio::test]
+async fn test_secret_box_binds_ciphertext_to_associated_data() {
+    let secrets = SecretBox::new(&[7u8; 32]);
+    let sealed = secrets.seal(b"totp secret", b"user-1").unwrap();
+    assert!(sealed.starts_with("v1:"));
+    assert_ne!(sealed, secrets.seal(b"totp secret", b"user-1").unwrap());
+    assert_eq!(secrets.open(&sealed, b"user-1").unwrap(), b"totp secret");
+
+    assert!(secrets.open(&sealed, b"user-2").is_err());
+    assert!(SecretBox::new(&[8u8; 32]).open(&sealed, b"user-1").is_err());
+    let tampered = format!("{}A", &sealed[..sealed.len() - 1]);
+    assert!(secrets.open(&tampered, b"user-1").is_err());
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_enrollment_takes_effect_after_confirmation(ctx: &TestDb) {
+    let config = common::test_config();
+    let auth = AuthService::new(ctx.pool.clone(), config.clone());
+    let mfa = MfaService::new(ctx.pool.clone(), config);
+    let user = register(&auth).await;
+
+    let enrollment = mfa.begin_totp_enrollment(user.id).await.unwrap();
+    assert!(enrollment.provisioning_uri.contains(&format!("secret={}", enrollment.secret)));
+    let stored = sqlx::query_scalar!("SELECT secret_ciphertext FROM user_totp WHERE user_id = $1", user.id)
+        .fetch_one(&ctx.pool)
+        .await
+        .unwrap();
+    assert!(!stored.contains(&enrollment.secret));
+
+    // Until it is confirmed, the enrollment does not affect login
+    assert!(matches!(login(&auth).await, LoginOutcome::Authenticated(_)));
+    match mfa.confirm_totp(user.id, "000000").await {
+        Err(AppError::Validation { fields, .. }) => assert_eq!(fields[0].field, "code"),
+        other => panic!("expected Validation, got {:?}", other),
+    }
+
+    let secret = BASE32_NOPAD.decode(enrollment.secret.as_bytes()).unwrap();
+    let codes = mfa.confirm_totp(user.id, &code(&secret, 0)).await.unwrap();
+    assert_eq!(codes.recovery_codes.len(), 10);
+    assert!(mfa.is_enabled(user.id).await.unwrap());
+    assert!(matches!(mfa.begin_totp_enrollment(user.id).await, Err(AppError::Conflict(_))));
+    assert!(matches!(login(&auth).await, LoginOutcome::MfaRequired(_)));
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_login_requires_second_factor(ctx: &TestDb) {
+    let config = common::test_config();
+    let auth = AuthService::new(ctx.pool.clone(), config.clone());
+    let mfa = MfaService::new(ctx.pool.clone(), config);
+    let user = register(&auth).await;
+    let (secret, _) = enable_totp(&mfa, &user).await;
+
+    let outcome = login(&auth).await;
+    assert!(matches!(outcome, LoginOutcome::MfaRequired(_)));
+    let challenge = challenge(outcome);
+
+    // The code used to confirm enrollment cannot be replayed
+    let confirmed_step = sqlx::query_scalar!("SELECT last_used_step FROM user_totp WHERE user_id = $1", user.id)
+        .fetch_one(&ctx.pool)
+        .await
+        .unwrap()
+        .unwrap();
+    let result = auth.verify_mfa(verify_dto(&challenge, &totp::code_at(&secret, confirmed_step)), None).await;
+    assert!(matches!(result, Err(AppError::Unauthorized(_))));
+
+    let session = auth.verify_mfa(verify_dto(&challenge, &code(&secret, 1)), None).await.unwrap();
+    assert_eq!(session.user.id, user.id);
+    assert!(auth.authenticate(&session.token).await.is_ok());
+
+    // The MFA token is single use
+    match auth.verify_mfa(verify_dto(&challenge, &code(&secret, 1)), None).await {
+        Err(AppError::Unauthorized(message)) => assert_eq!(message, "Invalid or expired MFA token"),
+        other => panic!("expected Unauthorized, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_recovery_codes_are_single_use(ctx: &TestDb) {
+    let config = common::test_config();
+    let auth = AuthService::new(ctx.pool.clone(), config.clone());
+    let mfa = MfaService::new(ctx.pool.clone(), config);
+    let user = register(&auth).await;
+    let (_, recovery_codes) = enable_totp(&mfa, &user).await;
+
+    // Recovery codes are accepted without the hyphen and in upper case
+    let typed = recovery_codes[0].replace('-', "").to_uppercase();
+    let challenge_1 = challenge(login(&auth).await);
+    assert!(auth.verify_mfa(verify_dto(&challenge_1, &typed), None).await.is_ok());
+
+    let challenge_2 = challenge(login(&auth).await);
+    let result = auth.verify_mfa(verify_dto(&challenge_2, &recovery_codes[0]), None).await;
+    assert!(matches!(result, Err(AppError::Unauthorized(_))));
+    assert!(auth.verify_mfa(verify_dto(&challenge_2, &recovery_codes[1]), None).await.is_ok());
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_mfa_token_is_revoked_after_max_attempts(ctx: &TestDb) {
+    let mut config = (*common::test_config()).clone();
+    config.mfa.challenge_max_attempts = 2;
+    let config = Arc::new(config);
+    let auth = AuthService::new(ctx.pool.clone(), config.clone());
+    let mfa = MfaService::new(ctx.pool.clone(), config);
+    let user = register(&auth).await;
+    let (secret, _) = enable_totp(&mfa, &user).await;
+
+    let challenge = challenge(login(&auth).await);
+    for _ in 0..2 {
+        match auth.verify_mfa(verify_dto(&challenge, "000000"), None).await {
+            Err(AppError::Unauthorized(message)) => assert_eq!(message, "Invalid authentication code"),
+            other => panic!("expected Unauthorized, got {:?}", other.map(|_| ())),
+        }
+    }
+    match auth.verify_mfa(verify_dto(&challenge, &code(&secret, 1)), None).await {
+        Err(AppError::Unauthorized(message)) => assert_eq!(message, "Invalid or expired MFA token"),
+        other => panic!("expected Unauthorized, got {:?}", other.map(|_| ())),
+    }
+
+    // Wrong codes count as failed logins for the account
+    let failures = sqlx::query_scalar!("SELECT failed_count FROM login_throttles WHERE scope = 'account' AND subject = $1", EMAIL)
+        .fetch_one(&ctx.pool)
+        .await
+        .unwrap();
+    assert_eq!(failures, 2);
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_disable_totp_requires_a_code(ctx: &TestDb) {
+    let config = common::test_config();
+    let auth = AuthService::new(ctx.pool.clone(), config.clone());
+    let mfa = MfaService::new(ctx.pool.clone(), config);
+    let user = register(&auth).await;
+    assert!(matches!(
+        mfa.disable_totp(user.id, &disable_dto(PASSWORD, "000000")).await,
+        Err(AppError::BadRequest(_))
+    ));
+    let (secret, recovery_codes) = enable_totp(&mfa, &user).await;
+
+    assert!(matches!(
+        mfa.disable_totp(user.id, &disable_dto(PASSWORD, "000000")).await,
+        Err(AppError::Validation { .. })
+    ));
+    // A valid code is not enough without the current password
+    match mfa.disable_totp(user.id, &disable_dto("wrong-password", &code(&secret, 1))).await {
+        Err(AppError::Validation { fields, .. }) => assert_eq!(fields[0].field, "current_password"),
+        other => panic!("expected Validation, got {:?}", other),
+    }
+    mfa.disable_totp(user.id, &disable_dto(PASSWORD, &recovery_codes[0])).await.unwrap();
+
+    assert!(!mfa.is_enabled(user.id).await.unwrap());
+    assert!(matches!(login(&auth).await, LoginOutcome::Authenticated(_)));
+    let remaining = sqlx::query_scalar!("SELECT COUNT(*) FROM mfa_recovery_codes WHERE user_id = $1", user.id)
+        .fetch_one(&ctx.pool)
+        .await
+        .unwrap();
+    assert_eq!(remaining, Some(0));
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_disable_totp_guesses_are_rate_limited(ctx: &TestDb) {
+    let mut config: Config = (*common::test_config()).clone();
+    config.security.login_account_free_attempts = 0;
+    config.security.login_account_lockout_threshold = 3;
+    config.security.login_backoff_base_secs = 0;
+    let config = Arc::new(config);
+    let auth = AuthService::new(ctx.pool.clone(), config.clone());
+    let mfa = MfaService::new(ctx.pool.clone(), config);
+    let user = register(&auth).await;
+    let (secret, _) = enable_totp(&mfa, &user).await;
+
+    for guess in ["000000", "111111", "222222"] {
+        assert!(matches!(
+            mfa.disable_totp(user.id, &disable_dto(PASSWORD, guess)).await,
+            Err(AppError::Validation { .. })
+        ));
+    }
+
+    // Once the account is locked, even the right code is not checked and MFA stays on
+    assert!(matches!(
+        mfa.disable_totp(user.id, &disable_dto(PASSWORD, &code(&secret, 1))).await,
+        Err(AppError::RateLimited { .. })
+    ));
+    assert!(mfa.is_enabled(user.id).await.unwrap());
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_enrollment_requires_encryption_key(ctx: &TestDb) {
+    let mut config: Config = (*common::test_config()).clone();
+    config.mfa.encryption_key = String::new();
+    let config = Arc::new(config);
+    let auth = AuthService::new(ctx.pool.clone(), config.clone());
+    let mfa = MfaService::new(ctx.pool.clone(), config);
+    let user = register(&auth).await;
+
+    assert!(matches!(mfa.begin_totp_enrollment(user.id).await, Err(AppError::Internal(_))));
+}
+
+#[test_context(TestDb)]
+#[actix_web::test]
+#[serial]
+async fn test_mfa_endpoints(ctx: &TestDb) {
+    let config = common::test_config();
+    let auth = web::Data::new(AuthService::new(ctx.pool.clone(), config.clone()));
+    register(&auth).await;
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::from(config.clone()))
+            .app_data(auth.clone())
+            .app_data(web::Data::new(MfaService::new(ctx.pool.clone(), config)))
+            .route("/api/auth/login", web::post().to(auth_handler::login))
+            .route("/api/auth/mfa/verify", web::post().to(auth_handler::verify_mfa))
+            .service(
+                web::scope("/api/users/me")
+                    .wrap(Auth::required())
+                    .route("/mfa/totp", web::post().to(user_handler::begin_totp))
+                    .route("/mfa/totp", web::delete().to(user_handler::disable_totp))
+                    .route("/mfa/totp/confirm", web::post().to(user_handler::confirm_totp)),
+            ),
+    )
+    .await;
+    let credentials = json!({ "email": EMAIL, "password": PASSWORD });
+
+    let req = test::TestRequest::post().uri("/api/auth/login").set_json(&credentials).to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let bearer = (header::AUTHORIZATION, format!("Bearer {}", body["token"].as_str().unwrap()));
+
+    let req = test::TestRequest::post().uri("/api/users/me/mfa/totp").to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::UNAUTHORIZED);
+
+    let req = test::TestRequest::post().uri("/api/users/me/mfa/totp").insert_header(bearer.clone()).to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert!(body["provisioning_uri"].as_str().unwrap().starts_with("otpauth://totp/"));
+    let secret = BASE32_NOPAD.decode(body["secret"].as_str().unwrap().as_bytes()).unwrap();
+
+    let req = test::TestRequest::post()
+        .uri("/api/users/me/mfa/totp/confirm")
+        .insert_header(bearer.clone())
+        .set_json(json!({ "code": code(&secret, 0) }))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["recovery_codes"].as_array().unwrap().len(), 10);
+
+    // Login now answers with an MFA challenge instead of tokens
+    let req = test::TestRequest::post().uri("/api/auth/login").set_json(&credentials).to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["mfa_required"], true);
+    assert!(body.get("token").is_none());
+    let mfa_token = body["mfa_token"].as_str().unwrap().to_string();
+
+    let req = test::TestRequest::post()
+        .uri("/api/auth/mfa/verify")
+        .set_json(json!({ "mfa_token": mfa_token, "code": "000000" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::UNAUTHORIZED);
+
+    let req = test::TestRequest::post()
+        .uri("/api/auth/mfa/verify")
+        .set_json(json!({ "mfa_token": mfa_token, "code": code(&secret, 1) }))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert!(body["token"].is_string());
+    assert_eq!(body["user"]["email"], EMAIL);
+}
@@ -0,0 +1,163 @@
+use actix_web::{http::header, http::StatusCode, test, web, App};
+use rust_study::{
+    handlers::{moderation_handler, post_handler},
+    middleware::{auth_middleware::Auth, require_role::RequireRole},
+    models::user::{AuthResponse, CreateUserDto, LoginDto, LoginOutcome, Role},
+    services::{auth_service::AuthService, moderation_service::ModerationService, post_service::PostService},
+};
+use serde_json::json;
+use serial_test::serial;
+use test_context::test_context;
+use uuid::Uuid;
+
+mod common;
+use common::TestDb;
+
+async fn create_test_app(
+    pool: sqlx::PgPool,
+    auth_service: web::Data<AuthService>,
+) -> impl actix_web::dev::Service<actix_http::Request, Response = actix_web::dev::ServiceResponse, Error = actix_web::Error> {
+    test::init_service(
+        App::new()
+            .app_data(web::Data::from(common::test_config()))
+            .app_data(auth_service)
+            .app_data(web::Data::new(PostService::new(pool.clone())))
+            .app_data(web::Data::new(ModerationService::new(pool)))
+            .service(
+                web::scope("/api")
+                    .service(
+                        web::scope("/posts")
+                            .wrap(Auth::required())
+                            .route("/{post_id}", web::delete().to(post_handler::delete_post)),
+                    )
+                    .service(
+                        web::scope("/moderation")
+                            .wrap(RequireRole::new(Role::Moderator))
+                            .wrap(Auth::required())
+                            .route("/actions", web::get().to(moderation_handler::get_actions)),
+                    )
+                    .service(
+                        web::scope("/admin")
+                            .wrap(RequireRole::new(Role::Admin))
+                            .wrap(Auth::required())
+                            .route("/users/{user_id}/role", web::put().to(moderation_handler::update_user_role)),
+                    ),
+            ),
+    )
+    .await
+}
+
+// 주어진 역할의 사용자를 만들고 로그인합니다.
+async fn login_with_role(pool: &sqlx::PgPool, service: &AuthService, role: Role) -> AuthResponse {
+    let dto = CreateUserDto {
+        username: format!("user_{}", &Uuid::new_v4().to_string()[..8]),
+        email: format!("{}@example.com", Uuid::new_v4()),
+        password: "password123".to_string(),
+    };
+    let user = service.register(dto.clone()).await.unwrap();
+    sqlx::query!("UPDATE users SET role = $2 WHERE id = $1", user.id, role as Role)
+        .execute(pool)
+        .await
+        .unwrap();
+
+    service
+        .login(LoginDto {
+            email: dto.email,
+            password: dto.password,
+        })
+        .await
+        .and_then(LoginOutcome::tokens)
+        .unwrap()
+}
+
+fn bearer(auth: &AuthResponse) -> (header::HeaderName, String) {
+    (header::AUTHORIZATION, format!("Bearer {}", auth.token))
+}
+
+#[test_context(TestDb)]
+#[actix_web::test]
+#[serial]
+async fn test_require_role_guards_moderation_routes(ctx: &TestDb) {
+    let auth_service = web::Data::new(AuthService::new(ctx.pool.clone(), common::test_config()));
+    let app = create_test_app(ctx.pool.clone(), auth_service.clone()).await;
+
+    let user = login_with_role(&ctx.pool, &auth_service, Role::User).await;
+    let moderator = login_with_role(&ctx.pool, &auth_service, Role::Moderator).await;
+    assert_eq!(auth_service.verify_token(&moderator.token).unwrap().role, Role::Moderator);
+
+    let req = test::TestRequest::get().uri("/api/moderation/actions").to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::UNAUTHORIZED);
+
+    let req = test::TestRequest::get()
+        .uri("/api/moderation/actions")
+        .insert_header(bearer(&user))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::FORBIDDEN);
+
+    // A moderator removes someone else's post and the action shows up in the log
+    let post_id = common::create_test_post(&ctx.pool, "title", "content", user.user.id).await;
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/posts/{}", post_id))
+        .insert_header(bearer(&moderator))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::NO_CONTENT);
+
+    let req = test::TestRequest::get()
+        .uri("/api/moderation/actions")
+        .insert_header(bearer(&moderator))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["items"][0]["action"], "delete_post");
+    assert_eq!(body["items"][0]["moderator_id"], moderator.user.id.to_string());
+    assert_eq!(body["items"][0]["target_id"], post_id.to_string());
+
+    // Moderators are not admins
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/admin/users/{}/role", user.user.id))
+        .insert_header(bearer(&moderator))
+        .set_json(json!({ "role": "moderator" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::FORBIDDEN);
+}
+
+#[test_context(TestDb)]
+#[actix_web::test]
+#[serial]
+async fn test_admin_changes_user_role(ctx: &TestDb) {
+    let auth_service = web::Data::new(AuthService::new(ctx.pool.clone(), common::test_config()));
+    let app = create_test_app(ctx.pool.clone(), auth_service.clone()).await;
+
+    let admin = login_with_role(&ctx.pool, &auth_service, Role::Admin).await;
+    let user = login_with_role(&ctx.pool, &auth_service, Role::User).await;
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/admin/users/{}/role", user.user.id))
+        .insert_header(bearer(&admin))
+        .set_json(json!({ "role": "moderator" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["role"], "moderator");
+
+    // Tokens carrying the old role stop working; refreshing picks up the new role
+    assert!(auth_service.authenticate(&user.token).await.is_err());
+    let refreshed = auth_service.refresh(&user.refresh_token).await.unwrap();
+    assert_eq!(auth_service.authenticate(&refreshed.token).await.unwrap().role, Role::Moderator);
+
+    // Admins cannot lock themselves out
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/admin/users/{}/role", admin.user.id))
+        .insert_header(bearer(&admin))
+        .set_json(json!({ "role": "user" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::FORBIDDEN);
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/admin/users/{}/role", Uuid::new_v4()))
+        .insert_header(bearer(&admin))
+        .set_json(json!({ "role": "moderator" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,240 @@
+use actix_web::{http::StatusCode, test, web, App};
+use chrono::Utc;
+use data_encoding::BASE32_NOPAD;
+use rust_study::{
+    errors::AppError,
+    handlers::auth_handler,
+    models::{
+        oidc::OidcCallbackDto,
+        user::{CreateUserDto, LoginDto, User},
+    },
+    services::{
+        auth_service::AuthService,
+        mfa_service::MfaService,
+        oidc_service::{pkce_challenge, OidcService},
+        totp,
+    },
+};
+use serial_test::serial;
+use test_context::test_context;
+
+mod common;
+use common::mock_oidc::{MockOidcProvider, MockUser};
+use common::TestDb;
+
+// 공급자에서 로그인을 마친 것처럼 code와 state를 받아 로그인을 완료합니다.
+async fn sign_in_with(provider: &MockOidcProvider, oidc: &OidcService, user: MockUser) -> Result<User, AppError> {
+    let authorization = oidc.authorize().await.unwrap();
+    let (code, state) = provider.authorize(&authorization.authorization_url, user);
+    oidc.complete(&OidcCallbackDto { code, state }).await
+}
+
+async fn register(auth: &AuthService, username: &str, email: &str) -> User {
+    auth.register(CreateUserDto {
+        username: username.to_string(),
+        email: email.to_string(),
+        password: "password123".to_string(),
+    })
+    .await
+    .unwrap()
+}
+
+async fn count(pool: &sqlx::PgPool, table: &str) -> i64 {
+    sqlx::query_scalar::<_, i64>(&format!("SELECT COUNT(*) FROM {}", table))
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_pkce_challenge_matches_rfc_7636() {
+    assert_eq!(
+        pkce_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+        "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+    );
+}
+
+#[test_context(TestDb)]
+#[actix_web::test]
+#[serial]
+async fn test_first_login_creates_verified_user(ctx: &TestDb) {
+    let provider = MockOidcProvider::start();
+    let config = provider.config();
+    let auth = AuthService::new(ctx.pool.clone(), config.clone());
+    let oidc = OidcService::new(ctx.pool.clone(), config);
+    register(&auth, "Octocat", "someone-else@example.com").await;
+
+    let mock_user = MockUser {
+        preferred_username: Some("octocat".to_string()),
+        ..MockUser::new("subject-1", "octocat@example.com")
+    };
+    let user = sign_in_with(&provider, &oidc, mock_user).await.unwrap();
+    assert_eq!(user.email, "octocat@example.com");
+    assert!(user.email_verified_at.is_some());
+    // The preferred name is taken (case-insensitively), so the new account gets a suffix
+    assert!(user.username.starts_with("octocat_"));
+
+    // Later logins find the same account through the linked identity, even if the email changed
+    let again = sign_in_with(&provider, &oidc, MockUser::new("subject-1", "new-octocat@example.com")).await.unwrap();
+    assert_eq!(again.id, user.id);
+    assert_eq!(count(&ctx.pool, "users").await, 2);
+    let identity = sqlx::query!("SELECT provider, email FROM user_identities WHERE subject = 'subject-1'")
+        .fetch_one(&ctx.pool)
+        .await
+        .unwrap();
+    assert_eq!(identity.provider, "mock");
+    assert_eq!(identity.email.as_deref(), Some("new-octocat@example.com"));
+
+    // The password is unusable until it is reset
+    let outcome = auth
+        .login(LoginDto {
+            email: user.email.clone(),
+            password: "password123".to_string(),
+        })
+        .await;
+    assert!(matches!(outcome, Err(AppError::Unauthorized(_))));
+}
+
+#[test_context(TestDb)]
+#[actix_web::test]
+#[serial]
+async fn test_login_links_only_verified_accounts(ctx: &TestDb) {
+    let provider = MockOidcProvider::start();
+    let config = provider.config();
+    let auth = AuthService::new(ctx.pool.clone(), config.clone());
+    let oidc = OidcService::new(ctx.pool.clone(), config);
+    let verified = register(&auth, "alice", "alice@example.com").await;
+    common::verify_user_email(&ctx.pool, verified.id).await;
+    register(&auth, "mallory", "bob@example.com").await;
+
+    let user = sign_in_with(&provider, &oidc, MockUser::new("alice-sub", "alice@example.com")).await.unwrap();
+    assert_eq!(user.id, verified.id);
+
+    // Someone registered with bob's address but never verified it: do not hand that account to bob
+    let result = sign_in_with(&provider, &oidc, MockUser::new("bob-sub", "bob@example.com")).await;
+    assert!(matches!(result, Err(AppError::Conflict(_))));
+
+    // Emails the provider has not verified are not trusted either
+    let unverified = MockUser {
+        email_verified: false,
+        ..MockUser::new("carol-sub", "carol@example.com")
+    };
+    let result = sign_in_with(&provider, &oidc, unverified).await;
+    assert!(matches!(result, Err(AppError::BadRequest(_))));
+    assert_eq!(count(&ctx.pool, "user_identities").await, 1);
+}
+
+#[test_context(TestDb)]
+#[actix_web::test]
+#[serial]
+async fn test_state_is_single_use_and_pkce_is_enforced(ctx: &TestDb) {
+    let provider = MockOidcProvider::start();
+    let oidc = OidcService::new(ctx.pool.clone(), provider.config());
+
+    let authorization = oidc.authorize().await.unwrap();
+    let (code, state) = provider.authorize(&authorization.authorization_url, MockUser::new("subject-1", "user@example.com"));
+    let dto = OidcCallbackDto { code, state };
+    assert!(oidc.complete(&dto).await.is_ok());
+    match oidc.complete(&dto).await {
+        Err(AppError::Unauthorized(message)) => assert_eq!(message, "Invalid or expired login state"),
+        other => panic!("expected Unauthorized, got {:?}", other),
+    }
+
+    // A code intercepted on its way back is useless without the verifier that only the server holds
+    let authorization = oidc.authorize().await.unwrap();
+    let (code, state) = provider.authorize(&authorization.authorization_url, MockUser::new("subject-1", "user@example.com"));
+    sqlx::query!("UPDATE oidc_login_states SET code_verifier = 'attacker-chosen-verifier-0123456789abcdefghij'")
+        .execute(&ctx.pool)
+        .await
+        .unwrap();
+    match oidc.complete(&OidcCallbackDto { code, state }).await {
+        Err(AppError::Unauthorized(message)) => assert_eq!(message, "The identity provider rejected the authorization code"),
+        other => panic!("expected Unauthorized, got {:?}", other),
+    }
+}
+
+#[test_context(TestDb)]
+#[actix_web::test]
+#[serial]
+async fn test_id_token_nonce_must_match(ctx: &TestDb) {
+    let provider = MockOidcProvider::start();
+    let oidc = OidcService::new(ctx.pool.clone(), provider.config());
+
+    let authorization = oidc.authorize().await.unwrap();
+    let (code, state) = provider.authorize(&authorization.authorization_url, MockUser::new("subject-1", "user@example.com"));
+    sqlx::query!("UPDATE oidc_login_states SET nonce = 'some-other-nonce'")
+        .execute(&ctx.pool)
+        .await
+        .unwrap();
+    match oidc.complete(&OidcCallbackDto { code, state }).await {
+        Err(AppError::Unauthorized(message)) => assert_eq!(message, "Invalid ID token"),
+        other => panic!("expected Unauthorized, got {:?}", other),
+    }
+    assert_eq!(count(&ctx.pool, "users").await, 0);
+}
+
+#[test_context(TestDb)]
+#[actix_web::test]
+#[serial]
+async fn test_oidc_endpoints(ctx: &TestDb) {
+    let provider = MockOidcProvider::start();
+    let config = provider.config();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::from(config.clone()))
+            .app_data(web::Data::new(AuthService::new(ctx.pool.clone(), config.clone())))
+            .app_data(web::Data::new(OidcService::new(ctx.pool.clone(), config.clone())))
+            .route("/api/auth/oidc/authorize", web::get().to(auth_handler::oidc_authorize))
+            .route("/api/auth/oidc/callback", web::post().to(auth_handler::oidc_callback)),
+    )
+    .await;
+    let mock_user = MockUser::new("subject-1", "user@example.com");
+
+    let req = test::TestRequest::get().uri("/api/auth/oidc/authorize").to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let (code, state) = provider.authorize(body["authorization_url"].as_str().unwrap(), mock_user.clone());
+
+    let req = test::TestRequest::post()
+        .uri("/api/auth/oidc/callback")
+        .set_json(serde_json::json!({ "code": code, "state": state }))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert!(body["token"].is_string());
+    assert_eq!(body["user"]["email"], "user@example.com");
+
+    // Two-factor authentication applies to OIDC logins as well
+    let user_id = body["user"]["id"].as_str().unwrap().parse().unwrap();
+    let mfa = MfaService::new(ctx.pool.clone(), config);
+    let enrollment = mfa.begin_totp_enrollment(user_id).await.unwrap();
+    let secret = BASE32_NOPAD.decode(enrollment.secret.as_bytes()).unwrap();
+    mfa.confirm_totp(user_id, &totp::code_at(&secret, totp::step_at(Utc::now().timestamp())))
+        .await
+        .unwrap();
+
+    let req = test::TestRequest::get().uri("/api/auth/oidc/authorize").to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let (code, state) = provider.authorize(body["authorization_url"].as_str().unwrap(), mock_user);
+    let req = test::TestRequest::post()
+        .uri("/api/auth/oidc/callback")
+        .set_json(serde_json::json!({ "code": code, "state": state }))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["mfa_required"], true);
+    assert!(body.get("token").is_none());
+}
+
+#[test_context(TestDb)]
+#[actix_web::test]
+#[serial]
+async fn test_oidc_login_is_disabled_without_discovery_url(ctx: &TestDb) {
+    let config = common::test_config();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(OidcService::new(ctx.pool.clone(), config)))
+            .route("/api/auth/oidc/authorize", web::get().to(auth_handler::oidc_authorize)),
+    )
+    .await;
+
+    let req = test::TestRequest::get().uri("/api/auth/oidc/authorize").to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::NOT_FOUND);
+}
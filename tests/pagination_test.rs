@@ -0,0 +1,122 @@
+use chrono::{DateTime, Utc};
+use rust_study::config::PaginationConfig;
+use rust_study::errors::AppError;
+use rust_study::models::pagination::{Cursor, CursorParams, Page, PageParams, PaginationQuery};
+use uuid::Uuid;
+
+#[test]
+fn test_resolve_uses_defaults() {
+    let params = PaginationQuery::default()
+        .resolve(&PaginationConfig::default())
+        .unwrap();
+
+    assert_eq!(params, PageParams::new(1, 10));
+    assert_eq!(params.offset(), 0);
+}
+
+#[test]
+fn test_resolve_rejects_non_positive_values() {
+    let query = PaginationQuery {
+        page: Some(0),
+        per_page: Some(-5),
+        ..Default::default()
+    };
+
+    assert!(query.resolve(&PaginationConfig::default()).is_err());
+}
+
+#[test]
+fn test_resolve_rejects_page_that_overflows_offset() {
+    let query = PaginationQuery {
+        page: Some(i64::MAX),
+        per_page: Some(10),
+        ..Default::default()
+    };
+
+    match query.resolve(&PaginationConfig::default()) {
+        Err(AppError::Validation { fields, .. }) => {
+            assert_eq!(fields.len(), 1);
+            assert_eq!(fields[0].field, "page");
+        }
+        other => panic!("expected a validation error, got {:?}", other),
+    }
+
+    // The largest page whose offset still fits is accepted
+    let query = PaginationQuery {
+        page: Some(i64::MAX / 10 + 1),
+        per_page: Some(10),
+        ..Default::default()
+    };
+    assert!(query.resolve(&PaginationConfig::default()).is_ok());
+}
+
+#[test]
+fn test_resolve_caps_per_page() {
+    let query = PaginationQuery {
+        page: Some(3),
+        per_page: Some(1_000),
+        ..Default::default()
+    };
+
+    let params = query.resolve(&PaginationConfig::default()).unwrap();
+    assert_eq!(params.per_page, 100);
+    assert_eq!(params.offset(), 200);
+}
+
+#[test]
+fn test_page_total_pages() {
+    let page = Page::new(vec![1, 2, 3], 21, PageParams::new(1, 10));
+    assert_eq!(page.total_pages, 3);
+
+    let empty: Page<i32> = Page::new(vec![], 0, PageParams::new(1, 10));
+    assert_eq!(empty.total_pages, 0);
+}
+
+#[test]
+fn test_cursor_round_trip() {
+    let cursor = Cursor::new(
+        DateTime::from_timestamp_micros(1_712_300_000_123_456).unwrap(),
+        Uuid::new_v4(),
+    );
+
+    let encoded = cursor.encode();
+    assert_eq!(Cursor::decode(&encoded), Some(cursor));
+}
+
+#[test]
+fn test_cursor_decode_rejects_garbage() {
+    assert_eq!(Cursor::decode("not-a-cursor"), None);
+    assert_eq!(Cursor::decode(""), None);
+}
+
+#[test]
+fn test_resolve_cursor() {
+    let cursor = Cursor::new(Utc::now(), Uuid::new_v4());
+    let config = PaginationConfig::default();
+
+    // No cursor: offset mode
+    assert_eq!(PaginationQuery::default().resolve_cursor(&config).unwrap(), None);
+
+    let query = PaginationQuery {
+        after: Some(cursor.encode()),
+        per_page: Some(5),
+        ..Default::default()
+    };
+    let params = query.resolve_cursor(&config).unwrap().unwrap();
+    assert_eq!(params, CursorParams::after(Cursor::decode(&cursor.encode()).unwrap(), 5));
+
+    // after and before together are rejected
+    let query = PaginationQuery {
+        after: Some(cursor.encode()),
+        before: Some(cursor.encode()),
+        ..Default::default()
+    };
+    assert!(query.resolve_cursor(&config).is_err());
+
+    // Malformed cursor is rejected
+    let query = PaginationQuery {
+        before: Some("garbage".to_string()),
+        ..Default::default()
+    };
+    assert!(query.resolve_cursor(&config).is_err());
+}
@@ -0,0 +1,110 @@
+use rust_study::{
+    config::{Config, PasswordAlgorithm},
+    errors::AppError,
+    models::user::{CreateUserDto, LoginDto, LoginOutcome},
+    services::{
+        auth_service::AuthService,
+        password_hasher::{algorithm_of, PasswordHasher},
+    },
+};
+use serial_test::serial;
+use std::sync::Arc;
+use test_context::test_context;
+
+mod common;
+use common::TestDb;
+
+fn config_with(algorithm: PasswordAlgorithm) -> Arc<Config> {
+    let mut config = (*common::test_config()).clone();
+    config.security.password_hash_algorithm = algorithm;
+    Arc::new(config)
+}
+
+#[tokio::test]
+async fn test_hashes_are_identified_by_prefix() {
+    let argon2 = PasswordHasher::new(&config_with(PasswordAlgorithm::Argon2id).security);
+    let bcrypt = PasswordHasher::new(&config_with(PasswordAlgorithm::Bcrypt).security);
+
+    let argon2_hash = argon2.hash("password123").await.unwrap();
+    let bcrypt_hash = bcrypt.hash("password123").await.unwrap();
+    assert!(argon2_hash.starts_with("$argon2id$v=19$m=1024,t=1,p=1$"));
+    assert!(bcrypt_hash.starts_with("$2b$04$"));
+    assert_eq!(algorithm_of(&argon2_hash), Some(PasswordAlgorithm::Argon2id));
+    assert_eq!(algorithm_of(&bcrypt_hash), Some(PasswordAlgorithm::Bcrypt));
+
+    // Either hasher verifies both formats, whatever it is configured to produce
+    for hasher in [&argon2, &bcrypt] {
+        for hash in [&argon2_hash, &bcrypt_hash] {
+            assert!(hasher.verify("password123", hash).await.unwrap());
+            assert!(!hasher.verify("password124", hash).await.unwrap());
+        }
+    }
+
+    let result = argon2.verify("password123", "plaintext-password").await;
+    assert!(matches!(result, Err(AppError::Internal(_))));
+}
+
+#[tokio::test]
+async fn test_needs_rehash_when_algorithm_or_cost_changes() {
+    let config = config_with(PasswordAlgorithm::Argon2id);
+    let argon2 = PasswordHasher::new(&config.security);
+    let argon2_hash = argon2.hash("password123").await.unwrap();
+    let bcrypt_hash = PasswordHasher::new(&config_with(PasswordAlgorithm::Bcrypt).security)
+        .hash("password123")
+        .await
+        .unwrap();
+
+    assert!(!argon2.needs_rehash(&argon2_hash));
+    assert!(argon2.needs_rehash(&bcrypt_hash));
+
+    let mut stronger = (*config).clone();
+    stronger.security.argon2_iterations = 2;
+    assert!(PasswordHasher::new(&stronger.security).needs_rehash(&argon2_hash));
+
+    stronger.security.password_hash_algorithm = PasswordAlgorithm::Bcrypt;
+    let bcrypt = PasswordHasher::new(&stronger.security);
+    assert!(!bcrypt.needs_rehash(&bcrypt_hash));
+    assert!(bcrypt.needs_rehash(&argon2_hash));
+    stronger.security.bcrypt_cost = 5;
+    assert!(PasswordHasher::new(&stronger.security).needs_rehash(&bcrypt_hash));
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_login_upgrades_outdated_hash(ctx: &TestDb) {
+    let legacy = AuthService::new(ctx.pool.clone(), config_with(PasswordAlgorithm::Bcrypt));
+    let user = legacy
+        .register(CreateUserDto {
+            username: "legacy".to_string(),
+            email: "legacy@example.com".to_string(),
+            password: "password123".to_string(),
+        })
+        .await
+        .unwrap();
+    assert!(user.password_hash.starts_with("$2b$"));
+
+    let stored_hash = || async {
+        sqlx::query_scalar!("SELECT password_hash FROM users WHERE id = $1", user.id)
+            .fetch_one(&ctx.pool)
+            .await
+            .unwrap()
+    };
+    let login = |password: &str| LoginDto {
+        email: "legacy@example.com".to_string(),
+        password: password.to_string(),
+    };
+
+    // A failed login leaves the stored hash alone
+    let service = AuthService::new(ctx.pool.clone(), config_with(PasswordAlgorithm::Argon2id));
+    assert!(service.login(login("wrong-password1")).await.is_err());
+    assert_eq!(stored_hash().await, user.password_hash);
+
+    service.login(login("password123")).await.and_then(LoginOutcome::tokens).unwrap();
+    let upgraded = stored_hash().await;
+    assert!(upgraded.starts_with("$argon2id$"));
+
+    // The upgraded hash keeps working and is not rewritten again
+    service.login(login("password123")).await.and_then(LoginOutcome::tokens).unwrap();
+    assert_eq!(stored_hash().await, upgraded);
+}
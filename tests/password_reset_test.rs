@@ -0,0 +1,209 @@
+use actix_web::{http::StatusCode, test, web, App};
+use rust_study::{
+    errors::{AppError, TokenError},
+    handlers::auth_handler,
+    mail::FileMailer,
+    models::{
+        api_token::{CreateApiTokenDto, Scope},
+        user::{AuthResponse, CreateUserDto, LoginDto, LoginOutcome},
+    },
+    services::{api_token_service::ApiTokenService, auth_service::AuthService},
+};
+use serde_json::json;
+use serial_test::serial;
+use std::{path::PathBuf, sync::Arc, time::Duration};
+use test_context::test_context;
+use uuid::Uuid;
+
+mod common;
+use common::TestDb;
+
+const EMAIL: &str = "forgetful@example.com";
+const PASSWORD: &str = "password123";
+
+// 재설정 메일을 테스트 디렉터리에 저장하는 AuthService를 만듭니다.
+fn auth_service(pool: &sqlx::PgPool, dir: &PathBuf) -> AuthService {
+    AuthService::new(pool.clone(), common::test_config())
+        .with_mailer(Arc::new(FileMailer::new(dir, "no-reply@example.com")))
+}
+
+async fn register_and_login(service: &AuthService) -> AuthResponse {
+    service
+        .register(CreateUserDto {
+            username: "forgetful".to_string(),
+            email: EMAIL.to_string(),
+            password: PASSWORD.to_string(),
+        })
+        .await
+        .unwrap();
+    login(service, PASSWORD).await.unwrap()
+}
+
+async fn login(service: &AuthService, password: &str) -> Result<AuthResponse, AppError> {
+    service
+        .login(LoginDto {
+            email: EMAIL.to_string(),
+            password: password.to_string(),
+        })
+        .await
+        .and_then(LoginOutcome::tokens)
+}
+
+fn sent_count(dir: &PathBuf) -> usize {
+    std::fs::read_dir(dir).map_or(0, |entries| entries.count())
+}
+
+// 재설정 메일은 백그라운드에서 발송되므로, 메일이 count통 저장될 때까지 기다린 뒤 마지막 토큰을 꺼냅니다.
+async fn wait_for_token(dir: &PathBuf, count: usize) -> String {
+    for _ in 0..100 {
+        if sent_count(dir) >= count {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    let mut files: Vec<_> = std::fs::read_dir(dir).unwrap().map(|entry| entry.unwrap().path()).collect();
+    assert_eq!(files.len(), count);
+    files.sort();
+    let message = std::fs::read_to_string(files.last().unwrap()).unwrap();
+    let start = message.find("?token=").unwrap() + "?token=".len();
+    message[start..].split_whitespace().next().unwrap().to_string()
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_reset_password_revokes_existing_sessions(ctx: &TestDb) {
+    let dir = std::env::temp_dir().join(format!("mail-test-{}", Uuid::new_v4()));
+    let service = auth_service(&ctx.pool, &dir);
+    let session = register_and_login(&service).await;
+
+    service.forgot_password(EMAIL).await.unwrap();
+    let token = wait_for_token(&dir, 1).await;
+
+    // Weak passwords are rejected without using up the token
+    let result = service.reset_password(&token, "short").await;
+    assert!(matches!(result, Err(AppError::Validation { .. })));
+
+    service.reset_password(&token, "new-password456").await.unwrap();
+    assert!(matches!(
+        service.reset_password(&token, "another-password789").await,
+        Err(AppError::BadRequest(_))
+    ));
+
+    // Everything issued before the reset stops working
+    assert!(service.authenticate(&session.token).await.is_err());
+    assert!(service.refresh(&session.refresh_token).await.is_err());
+    assert!(login(&service, PASSWORD).await.is_err());
+    assert!(login(&service, "new-password456").await.is_ok());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_reset_password_revokes_api_tokens(ctx: &TestDb) {
+    let dir = std::env::temp_dir().join(format!("mail-test-{}", Uuid::new_v4()));
+    let service = auth_service(&ctx.pool, &dir);
+    register_and_login(&service).await;
+    let user_id = sqlx::query_scalar!("SELECT id FROM users WHERE email = $1", EMAIL)
+        .fetch_one(&ctx.pool)
+        .await
+        .unwrap();
+    let api_token = ApiTokenService::new(ctx.pool.clone())
+        .create(user_id, CreateApiTokenDto {
+            name: "left behind by an attacker".to_string(),
+            scopes: vec![Scope::PostsWrite],
+            expires_at: None,
+        })
+        .await
+        .unwrap()
+        .token;
+    assert!(service.authenticate_api_token(&api_token).await.is_ok());
+
+    service.forgot_password(EMAIL).await.unwrap();
+    let token = wait_for_token(&dir, 1).await;
+    service.reset_password(&token, "new-password456").await.unwrap();
+
+    // Recovering the account also cuts off API tokens created with the old password
+    assert!(matches!(
+        service.authenticate_api_token(&api_token).await,
+        Err(AppError::InvalidToken(TokenError::Revoked))
+    ));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_reset_tokens_expire_and_are_not_resent_too_often(ctx: &TestDb) {
+    let dir = std::env::temp_dir().join(format!("mail-test-{}", Uuid::new_v4()));
+    let service = auth_service(&ctx.pool, &dir);
+    register_and_login(&service).await;
+
+    service.forgot_password(EMAIL).await.unwrap();
+    let token = wait_for_token(&dir, 1).await;
+
+    // A second request right away succeeds but does not send another email
+    service.forgot_password(EMAIL).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(sent_count(&dir), 1);
+
+    sqlx::query!("UPDATE password_reset_tokens SET expires_at = CURRENT_TIMESTAMP - INTERVAL '1 second'")
+        .execute(&ctx.pool)
+        .await
+        .unwrap();
+    assert!(matches!(
+        service.reset_password(&token, "new-password456").await,
+        Err(AppError::BadRequest(_))
+    ));
+    assert!(login(&service, PASSWORD).await.is_ok());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test_context(TestDb)]
+#[actix_web::test]
+#[serial]
+async fn test_forgot_password_does_not_reveal_accounts(ctx: &TestDb) {
+    let dir = std::env::temp_dir().join(format!("mail-test-{}", Uuid::new_v4()));
+    let service = web::Data::new(auth_service(&ctx.pool, &dir));
+    register_and_login(&service).await;
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::from(common::test_config()))
+            .app_data(service.clone())
+            .route("/api/auth/forgot-password", web::post().to(auth_handler::forgot_password))
+            .route("/api/auth/reset-password", web::post().to(auth_handler::reset_password)),
+    )
+    .await;
+
+    let mut responses = Vec::new();
+    for email in ["nobody@example.com", EMAIL] {
+        let req = test::TestRequest::post()
+            .uri("/api/auth/forgot-password")
+            .set_json(json!({ "email": email }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        let status = resp.status();
+        responses.push((status, test::read_body(resp).await));
+    }
+    assert_eq!(responses[0], responses[1]);
+    assert_eq!(responses[0].0, StatusCode::ACCEPTED);
+
+    let token = wait_for_token(&dir, 1).await;
+    let req = test::TestRequest::post()
+        .uri("/api/auth/reset-password")
+        .set_json(json!({ "token": token, "password": "new-password456" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::NO_CONTENT);
+
+    let req = test::TestRequest::post()
+        .uri("/api/auth/reset-password")
+        .set_json(json!({ "token": token, "password": "new-password456" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::BAD_REQUEST);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,90 @@
+use rust_study::config::BoardConfig;
+use rust_study::models::user::{Actor, Role};
+use rust_study::policy::{
+    Action, CommentPolicy, CommentResource, Decision, Grant, Policy, PostPolicy, PostResource,
+};
+use uuid::Uuid;
+
+fn actor(role: Role) -> Actor {
+    Actor { id: Uuid::new_v4(), role }
+}
+
+fn post_by(author: &Actor) -> PostResource {
+    PostResource {
+        author_id: author.id,
+    }
+}
+
+fn comment_by(author: &Actor, post_author: &Actor) -> CommentResource {
+    CommentResource {
+        author_id: author.id,
+        post_author_id: post_author.id,
+    }
+}
+
+#[test]
+fn test_post_author_can_update_and_delete() {
+    let author = actor(Role::User);
+    let post = post_by(&author);
+
+    for action in [Action::Update, Action::Delete] {
+        assert_eq!(PostPolicy.can(&author, action, &post), Decision::Allow(Grant::Author));
+        assert_eq!(PostPolicy.can(&actor(Role::User), action, &post), Decision::Deny);
+    }
+}
+
+#[test]
+fn test_moderators_and_admins_can_moderate_any_content() {
+    let moderator = actor(Role::Moderator);
+    let admin = actor(Role::Admin);
+
+    // Whatever role the author has, moderators and admins may edit or delete the content
+    for author_role in [Role::User, Role::Moderator, Role::Admin] {
+        let post = post_by(&actor(author_role));
+        for moderating in [&moderator, &admin] {
+            for action in [Action::Update, Action::Delete] {
+                assert_eq!(PostPolicy.can(moderating, action, &post), Decision::Allow(Grant::Moderator));
+            }
+        }
+    }
+
+    // Acting on one's own content is never recorded as moderation
+    assert_eq!(PostPolicy.can(&admin, Action::Update, &post_by(&admin)), Decision::Allow(Grant::Author));
+}
+
+#[test]
+fn test_post_author_may_delete_comments_on_their_post() {
+    let policy = CommentPolicy::new(&BoardConfig::default());
+    let post_author = actor(Role::User);
+    let commenter = actor(Role::User);
+    let comment = comment_by(&commenter, &post_author);
+
+    assert_eq!(policy.can(&commenter, Action::Update, &comment), Decision::Allow(Grant::Author));
+    assert_eq!(policy.can(&post_author, Action::Delete, &comment), Decision::Allow(Grant::PostAuthor));
+    assert_eq!(policy.can(&post_author, Action::Update, &comment), Decision::Deny);
+    assert_eq!(policy.can(&actor(Role::User), Action::Delete, &comment), Decision::Deny);
+
+    // A moderator's comment can still be removed from the post author's own thread
+    let moderator_comment = comment_by(&actor(Role::Moderator), &post_author);
+    assert_eq!(policy.can(&post_author, Action::Delete, &moderator_comment), Decision::Allow(Grant::PostAuthor));
+}
+
+#[test]
+fn test_board_can_disable_post_author_comment_deletion() {
+    let policy = CommentPolicy::new(&BoardConfig {
+        post_author_can_delete_comments: false,
+    });
+    let post_author = actor(Role::User);
+    let comment = comment_by(&actor(Role::User), &post_author);
+
+    assert_eq!(policy.can(&post_author, Action::Delete, &comment), Decision::Deny);
+    assert_eq!(policy.can(&actor(Role::Moderator), Action::Delete, &comment), Decision::Allow(Grant::Moderator));
+}
+
+#[test]
+fn test_decision_helpers() {
+    assert!(Decision::Allow(Grant::Author).is_allowed());
+    assert!(!Decision::Allow(Grant::PostAuthor).is_moderation());
+    assert!(Decision::Allow(Grant::Moderator).is_moderation());
+    assert!(!Decision::Deny.is_allowed());
+}
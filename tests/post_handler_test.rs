@@ -1,11 +1,14 @@
-use actix_web::{test, web, App};
+use actix_web::{http::{header, StatusCode}, test, web, App};
 use fake::{Fake, Faker};
 use rust_study::{
     handlers::post_handler,
+    middleware::auth_middleware::Auth,
     models::post::{CreatePostDto, UpdatePostDto},
-    services::post_service::PostService,
+    models::user::{CreateUserDto, LoginDto, LoginOutcome},
+    services::{auth_service::AuthService, post_service::PostService},
 };
 use serde_json::json;
+use serial_test::serial;
 use test_context::test_context;
 use uuid::Uuid;
 
@@ -14,30 +17,57 @@ use common::TestDb;
 
 async fn create_test_app(
     pool: sqlx::PgPool,
-) -> impl actix_web::dev::Service<actix_http::Request, Response = actix_web::dev::ServiceResponse> {
+) -> impl actix_web::dev::Service<actix_http::Request, Response = actix_web::dev::ServiceResponse, Error = actix_web::Error> {
+    let config = common::test_config();
     test::init_service(
         App::new()
+            .app_data(web::Data::from(config.clone()))
+            .app_data(web::Data::new(AuthService::new(pool.clone(), config)))
             .app_data(web::Data::new(PostService::new(pool)))
             .service(
                 web::scope("/api")
                     .service(
                         web::scope("/posts")
-                            .route("", web::post().to(post_handler::create_post))
-                            .route("", web::get().to(post_handler::get_posts))
-                            .route("/{post_id}", web::get().to(post_handler::get_post))
-                            .route("/{post_id}", web::put().to(post_handler::update_post))
-                            .route("/{post_id}", web::delete().to(post_handler::delete_post)),
+                            .route("", web::post().to(post_handler::create_post).wrap(Auth::required()))
+                            .route("", web::get().to(post_handler::get_posts).wrap(Auth::optional()))
+                            .route("/{post_id}", web::get().to(post_handler::get_post).wrap(Auth::optional()))
+                            .route("/{post_id}", web::put().to(post_handler::update_post).wrap(Auth::required()))
+                            .route("/{post_id}", web::delete().to(post_handler::delete_post).wrap(Auth::required())),
                     ),
             ),
     )
     .await
 }
 
+// 테스트 사용자를 생성하고 (사용자 ID, Authorization 헤더 값)을 반환합니다.
+async fn login_test_user(pool: &sqlx::PgPool) -> (Uuid, String) {
+    let service = AuthService::new(pool.clone(), common::test_config());
+    let dto = CreateUserDto {
+        username: Faker.fake::<String>(),
+        email: format!("{}@example.com", Uuid::new_v4()),
+        password: "password123".to_string(),
+    };
+
+    let user = service.register(dto.clone()).await.unwrap();
+    common::verify_user_email(pool, user.id).await;
+    let auth_response = service
+        .login(LoginDto {
+            email: dto.email,
+            password: dto.password,
+        })
+        .await
+        .and_then(LoginOutcome::tokens)
+        .unwrap();
+
+    (user.id, format!("Bearer {}", auth_response.token))
+}
+
 #[test_context(TestDb)]
 #[actix_web::test]
+#[serial]
 async fn test_create_post_handler(ctx: &TestDb) {
     let app = create_test_app(ctx.pool.clone()).await;
-    let author_id = Uuid::new_v4();
+    let (_, token) = login_test_user(&ctx.pool).await;
 
     let dto = CreatePostDto {
         title: Faker.fake::<String>(),
@@ -45,7 +75,8 @@ async fn test_create_post_handler(ctx: &TestDb) {
     };
 
     let req = test::TestRequest::post()
-        .uri(&format!("/api/posts?author_id={}", author_id))
+        .uri("/api/posts")
+        .insert_header((header::AUTHORIZATION, token))
         .set_json(&dto)
         .to_request();
 
@@ -55,13 +86,17 @@ async fn test_create_post_handler(ctx: &TestDb) {
     let body: serde_json::Value = test::read_body_json(resp).await;
     assert_eq!(body["title"], json!(dto.title));
     assert_eq!(body["content"], json!(dto.content));
+    assert!(body["author"]["username"].is_string());
+    assert_eq!(body["author"]["deleted"], json!(false));
+    assert!(body.get("author_id").is_none());
 }
 
 #[test_context(TestDb)]
 #[actix_web::test]
+#[serial]
 async fn test_get_post_handler(ctx: &TestDb) {
     let app = create_test_app(ctx.pool.clone()).await;
-    let author_id = Uuid::new_v4();
+    let (author_id, token) = login_test_user(&ctx.pool).await;
 
     let title: String = Faker.fake();
     let content: String = Faker.fake();
@@ -69,6 +104,7 @@ async fn test_get_post_handler(ctx: &TestDb) {
 
     let req = test::TestRequest::get()
         .uri(&format!("/api/posts/{}", post_id))
+        .insert_header((header::AUTHORIZATION, token))
         .to_request();
 
     let resp = test::call_service(&app, req).await;
@@ -81,9 +117,10 @@ async fn test_get_post_handler(ctx: &TestDb) {
 
 #[test_context(TestDb)]
 #[actix_web::test]
+#[serial]
 async fn test_get_posts_handler(ctx: &TestDb) {
     let app = create_test_app(ctx.pool.clone()).await;
-    let author_id = Uuid::new_v4();
+    let (author_id, token) = login_test_user(&ctx.pool).await;
 
     // Create 5 test posts
     for _ in 0..5 {
@@ -94,20 +131,57 @@ async fn test_get_posts_handler(ctx: &TestDb) {
 
     let req = test::TestRequest::get()
         .uri("/api/posts?page=1&per_page=10")
+        .insert_header((header::AUTHORIZATION, token))
         .to_request();
 
     let resp = test::call_service(&app, req).await;
     assert!(resp.status().is_success());
 
-    let body: Vec<serde_json::Value> = test::read_body_json(resp).await;
-    assert_eq!(body.len(), 5);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["items"].as_array().unwrap().len(), 5);
+    assert_eq!(body["total"], json!(5));
+    assert_eq!(body["page"], json!(1));
+    assert_eq!(body["per_page"], json!(10));
+    assert_eq!(body["total_pages"], json!(1));
 }
 
 #[test_context(TestDb)]
 #[actix_web::test]
+#[serial]
+async fn test_get_posts_handler_invalid_page(ctx: &TestDb) {
+    let app = create_test_app(ctx.pool.clone()).await;
+    let (_, token) = login_test_user(&ctx.pool).await;
+
+    let req = test::TestRequest::get()
+        .uri("/api/posts?page=-1&per_page=10")
+        .insert_header((header::AUTHORIZATION, token.clone()))
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), actix_web::http::StatusCode::UNPROCESSABLE_ENTITY);
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["fields"][0]["field"], json!("page"));
+
+    // per_page above the configured maximum is capped
+    let req = test::TestRequest::get()
+        .uri("/api/posts?page=1&per_page=100000")
+        .insert_header((header::AUTHORIZATION, token))
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["per_page"], json!(100));
+}
+
+#[test_context(TestDb)]
+#[actix_web::test]
+#[serial]
 async fn test_update_post_handler(ctx: &TestDb) {
     let app = create_test_app(ctx.pool.clone()).await;
-    let author_id = Uuid::new_v4();
+    let (author_id, token) = login_test_user(&ctx.pool).await;
 
     let title: String = Faker.fake();
     let content: String = Faker.fake();
@@ -121,7 +195,8 @@ async fn test_update_post_handler(ctx: &TestDb) {
     };
 
     let req = test::TestRequest::put()
-        .uri(&format!("/api/posts/{}?author_id={}", post_id, author_id))
+        .uri(&format!("/api/posts/{}", post_id))
+        .insert_header((header::AUTHORIZATION, token))
         .set_json(&dto)
         .to_request();
 
@@ -135,16 +210,18 @@ async fn test_update_post_handler(ctx: &TestDb) {
 
 #[test_context(TestDb)]
 #[actix_web::test]
+#[serial]
 async fn test_delete_post_handler(ctx: &TestDb) {
     let app = create_test_app(ctx.pool.clone()).await;
-    let author_id = Uuid::new_v4();
+    let (author_id, token) = login_test_user(&ctx.pool).await;
 
     let title: String = Faker.fake();
     let content: String = Faker.fake();
     let post_id = common::create_test_post(&ctx.pool, &title, &content, author_id).await;
 
     let req = test::TestRequest::delete()
-        .uri(&format!("/api/posts/{}?author_id={}", post_id, author_id))
+        .uri(&format!("/api/posts/{}", post_id))
+        .insert_header((header::AUTHORIZATION, token.clone()))
         .to_request();
 
     let resp = test::call_service(&app, req).await;
@@ -153,8 +230,150 @@ async fn test_delete_post_handler(ctx: &TestDb) {
     // Verify post is deleted
     let req = test::TestRequest::get()
         .uri(&format!("/api/posts/{}", post_id))
+        .insert_header((header::AUTHORIZATION, token))
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), actix_web::http::StatusCode::NOT_FOUND);
+}
+
+#[test_context(TestDb)]
+#[actix_web::test]
+#[serial]
+async fn test_update_post_handler_forbidden_vs_not_found(ctx: &TestDb) {
+    let app = create_test_app(ctx.pool.clone()).await;
+    let (author_id, _) = login_test_user(&ctx.pool).await;
+    let (_, other_token) = login_test_user(&ctx.pool).await;
+
+    let title: String = Faker.fake();
+    let content: String = Faker.fake();
+    let post_id = common::create_test_post(&ctx.pool, &title, &content, author_id).await;
+
+    let dto = UpdatePostDto {
+        title: Some(Faker.fake::<String>()),
+        content: None,
+    };
+
+    // Another user's post
+    let req = test::TestRequest::put()
+        .uri(&format!("/api/posts/{}", post_id))
+        .insert_header((header::AUTHORIZATION, other_token.clone()))
+        .set_json(&dto)
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), actix_web::http::StatusCode::FORBIDDEN);
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["code"], json!("FORBIDDEN"));
+
+    // Missing post
+    let req = test::TestRequest::delete()
+        .uri(&format!("/api/posts/{}", Uuid::new_v4()))
+        .insert_header((header::AUTHORIZATION, other_token))
         .to_request();
 
     let resp = test::call_service(&app, req).await;
     assert_eq!(resp.status(), actix_web::http::StatusCode::NOT_FOUND);
 }
+
+#[test_context(TestDb)]
+#[actix_web::test]
+#[serial]
+async fn test_get_posts_handler_cursor(ctx: &TestDb) {
+    let app = create_test_app(ctx.pool.clone()).await;
+    let (author_id, token) = login_test_user(&ctx.pool).await;
+
+    // Create 3 test posts
+    for _ in 0..3 {
+        let title: String = Faker.fake();
+        let content: String = Faker.fake();
+        common::create_test_post(&ctx.pool, &title, &content, author_id).await;
+    }
+
+    let req = test::TestRequest::get()
+        .uri("/api/posts?per_page=2")
+        .insert_header((header::AUTHORIZATION, token.clone()))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let cursor = body["next_cursor"].as_str().unwrap().to_string();
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/posts?after={}&per_page=2", cursor))
+        .insert_header((header::AUTHORIZATION, token.clone()))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["items"].as_array().unwrap().len(), 1);
+    assert_eq!(body["next_cursor"], json!(null));
+    assert!(body["prev_cursor"].is_string());
+
+    // after and before together are rejected
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/posts?after={0}&before={0}", cursor))
+        .insert_header((header::AUTHORIZATION, token))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), actix_web::http::StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[test_context(TestDb)]
+#[actix_web::test]
+#[serial]
+async fn test_create_post_handler_validation(ctx: &TestDb) {
+    let app = create_test_app(ctx.pool.clone()).await;
+    let (_, token) = login_test_user(&ctx.pool).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/posts")
+        .insert_header((header::AUTHORIZATION, token))
+        .set_json(json!({ "title": "t".repeat(256), "content": "" }))
+        .to_request();
+
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), actix_web::http::StatusCode::UNPROCESSABLE_ENTITY);
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["code"], json!("VALIDATION_FAILED"));
+    assert_eq!(body["fields"][0]["field"], json!("content"));
+    assert_eq!(body["fields"][1]["field"], json!("title"));
+}
+
+#[test_context(TestDb)]
+#[actix_web::test]
+#[serial]
+async fn test_posts_are_readable_without_login(ctx: &TestDb) {
+    let app = create_test_app(ctx.pool.clone()).await;
+    let (author_id, _) = login_test_user(&ctx.pool).await;
+    let post_id = common::create_test_post(&ctx.pool, "Public post", "Anyone can read this", author_id).await;
+
+    let req = test::TestRequest::get().uri("/api/posts").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["total"], json!(1));
+
+    let req = test::TestRequest::get().uri(&format!("/api/posts/{}", post_id)).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["title"], json!("Public post"));
+
+    // Writing still requires a login
+    let req = test::TestRequest::post()
+        .uri("/api/posts")
+        .set_json(json!({ "title": "Anonymous", "content": "Should be rejected" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::UNAUTHORIZED);
+    let req = test::TestRequest::delete().uri(&format!("/api/posts/{}", post_id)).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::UNAUTHORIZED);
+
+    // A bad token on a public route is rejected rather than treated as anonymous
+    let req = test::TestRequest::get()
+        .uri("/api/posts")
+        .insert_header((header::AUTHORIZATION, "Bearer invalid.token.here"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::UNAUTHORIZED);
+}
@@ -1,7 +1,10 @@
 use crate::common::TestDb;
 use fake::{Fake, Faker};
+use rust_study::errors::AppError;
+use rust_study::models::pagination::{Cursor, CursorParams, PageParams};
 use rust_study::models::post::{CreatePostDto, UpdatePostDto};
-use rust_study::services::post_service::PostService;
+use rust_study::models::user::{Actor, Role, DELETED_USERNAME};
+use rust_study::services::{post_service::PostService, MutationOutcome};
 use serial_test::serial;
 use test_context::test_context;
 use uuid::Uuid;
@@ -13,8 +16,8 @@ mod common;
 #[serial]
 async fn test_create_post(ctx: &TestDb) {
     let service = PostService::new(ctx.pool.clone());
-    let author_id = Uuid::new_v4();
-    
+    let author_id = common::create_test_user(&ctx.pool, "author", "author@example.com", "hash").await;
+
     let dto = CreatePostDto {
         title: Faker.fake::<String>(),
         content: Faker.fake::<String>(),
@@ -24,7 +27,7 @@ async fn test_create_post(ctx: &TestDb) {
 
     assert_eq!(post.title, dto.title);
     assert_eq!(post.content, dto.content);
-    assert_eq!(post.author_id, author_id);
+    assert_eq!(post.author.id, author_id);
 }
 
 #[test_context(TestDb)]
@@ -43,7 +46,7 @@ async fn test_get_post(ctx: &TestDb) {
     assert_eq!(post.id, post_id);
     assert_eq!(post.title, title);
     assert_eq!(post.content, content);
-    assert_eq!(post.author_id, author_id);
+    assert_eq!(post.author.id, author_id);
 }
 
 #[test_context(TestDb)]
@@ -61,12 +64,15 @@ async fn test_get_posts_pagination(ctx: &TestDb) {
     }
 
     // Test first page (10 posts)
-    let posts = service.get_posts(1, 10).await.unwrap();
-    assert_eq!(posts.len(), 10);
+    let posts = service.get_posts(PageParams::new(1, 10)).await.unwrap();
+    assert_eq!(posts.items.len(), 10);
+    assert_eq!(posts.total, 15);
+    assert_eq!(posts.total_pages, 2);
 
     // Test second page (5 posts)
-    let posts = service.get_posts(2, 10).await.unwrap();
-    assert_eq!(posts.len(), 5);
+    let posts = service.get_posts(PageParams::new(2, 10)).await.unwrap();
+    assert_eq!(posts.items.len(), 5);
+    assert_eq!(posts.page, 2);
 }
 
 #[test_context(TestDb)]
@@ -87,16 +93,15 @@ async fn test_update_post(ctx: &TestDb) {
         content: Some(new_content.clone()),
     };
 
-    let updated_post = service
-        .update_post(post_id, dto, author_id)
-        .await
-        .unwrap()
-        .unwrap();
+    let updated_post = match service.update_post(post_id, dto, &common::user_actor(author_id)).await.unwrap() {
+        MutationOutcome::Done(post) => post,
+        other => panic!("expected Done, got {:?}", other),
+    };
 
     assert_eq!(updated_post.id, post_id);
     assert_eq!(updated_post.title, new_title);
     assert_eq!(updated_post.content, new_content);
-    assert_eq!(updated_post.author_id, author_id);
+    assert_eq!(updated_post.author.id, author_id);
 }
 
 #[test_context(TestDb)]
@@ -111,8 +116,8 @@ async fn test_delete_post(ctx: &TestDb) {
     let post_id = common::create_test_post(&ctx.pool, &title, &content, author_id).await;
 
     // Delete the post
-    let result = service.delete_post(post_id, author_id).await.unwrap();
-    assert!(result);
+    let result = service.delete_post(post_id, &common::user_actor(author_id)).await.unwrap();
+    assert!(matches!(result, MutationOutcome::Done(())));
 
     // Verify post is deleted
     let post = service.get_post(post_id).await.unwrap();
@@ -132,10 +137,225 @@ async fn test_delete_post_wrong_author(ctx: &TestDb) {
     let post_id = common::create_test_post(&ctx.pool, &title, &content, author_id).await;
 
     // Try to delete with wrong author
-    let result = service.delete_post(post_id, wrong_author_id).await.unwrap();
-    assert!(!result);
+    let result = service.delete_post(post_id, &common::user_actor(wrong_author_id)).await.unwrap();
+    assert!(matches!(result, MutationOutcome::Forbidden));
 
     // Verify post still exists
     let post = service.get_post(post_id).await.unwrap();
     assert!(post.is_some());
 }
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_update_post_wrong_author(ctx: &TestDb) {
+    let service = PostService::new(ctx.pool.clone());
+    let author_id = Uuid::new_v4();
+    let wrong_author_id = Uuid::new_v4();
+
+    let title: String = Faker.fake();
+    let content: String = Faker.fake();
+    let post_id = common::create_test_post(&ctx.pool, &title, &content, author_id).await;
+
+    let dto = UpdatePostDto {
+        title: Some(Faker.fake::<String>()),
+        content: None,
+    };
+
+    let result = service.update_post(post_id, dto, &common::user_actor(wrong_author_id)).await.unwrap();
+    assert!(matches!(result, MutationOutcome::Forbidden));
+
+    // Verify post is unchanged
+    let post = service.get_post(post_id).await.unwrap().unwrap();
+    assert_eq!(post.title, title);
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_update_and_delete_missing_post(ctx: &TestDb) {
+    let service = PostService::new(ctx.pool.clone());
+    let author_id = Uuid::new_v4();
+    let missing_post_id = Uuid::new_v4();
+
+    let dto = UpdatePostDto {
+        title: Some(Faker.fake::<String>()),
+        content: None,
+    };
+
+    let result = service.update_post(missing_post_id, dto, &common::user_actor(author_id)).await.unwrap();
+    assert!(matches!(result, MutationOutcome::NotFound));
+
+    let result = service.delete_post(missing_post_id, &common::user_actor(author_id)).await.unwrap();
+    assert!(matches!(result, MutationOutcome::NotFound));
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_get_posts_by_cursor(ctx: &TestDb) {
+    let service = PostService::new(ctx.pool.clone());
+    let author_id = Uuid::new_v4();
+
+    // Create 15 test posts
+    for _ in 0..15 {
+        let title: String = Faker.fake();
+        let content: String = Faker.fake();
+        common::create_test_post(&ctx.pool, &title, &content, author_id).await;
+    }
+
+    // First page in offset mode exposes a cursor for the next page
+    let first = service.get_posts(PageParams::new(1, 10)).await.unwrap();
+    let next_cursor = Cursor::decode(first.next_cursor.as_deref().unwrap()).unwrap();
+
+    // A post created while scrolling must not shift the next page
+    common::create_test_post(&ctx.pool, "new", "new", author_id).await;
+
+    let second = service
+        .get_posts_by_cursor(CursorParams::after(next_cursor, 10))
+        .await
+        .unwrap();
+    assert_eq!(second.items.len(), 5);
+    assert!(second.next_cursor.is_none());
+    for post in &second.items {
+        assert!(first.items.iter().all(|p| p.id != post.id));
+    }
+
+    // Going back from the second page returns the tail of the first page
+    let prev_cursor = Cursor::decode(second.prev_cursor.as_deref().unwrap()).unwrap();
+    let back = service
+        .get_posts_by_cursor(CursorParams::before(prev_cursor, 10))
+        .await
+        .unwrap();
+    let back_ids: Vec<_> = back.items.iter().map(|p| p.id).collect();
+    let first_ids: Vec<_> = first.items.iter().map(|p| p.id).collect();
+    assert_eq!(back_ids, first_ids);
+    // The newly created post is still ahead of the first page
+    assert!(back.prev_cursor.is_some());
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_post_author_summary(ctx: &TestDb) {
+    let service = PostService::new(ctx.pool.clone());
+    let author_id = common::create_test_user(&ctx.pool, "alice", "alice@example.com", "hash").await;
+    let deleted_author_id = Uuid::new_v4();
+
+    let post_id = common::create_test_post(&ctx.pool, "title", "content", author_id).await;
+    common::create_test_post(&ctx.pool, "orphan", "content", deleted_author_id).await;
+
+    let post = service.get_post(post_id).await.unwrap().unwrap();
+    assert_eq!(post.author.id, author_id);
+    assert_eq!(post.author.username, "alice");
+    assert!(!post.author.deleted);
+
+    // Posts whose author account no longer exists are still listed
+    let posts = service.get_posts(PageParams::new(1, 10)).await.unwrap();
+    assert_eq!(posts.items.len(), 2);
+    let orphan = posts.items.iter().find(|p| p.author.id == deleted_author_id).unwrap();
+    assert!(orphan.author.deleted);
+    assert_eq!(orphan.author.username, DELETED_USERNAME);
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_post_comment_count(ctx: &TestDb) {
+    let service = PostService::new(ctx.pool.clone());
+    let author_id = Uuid::new_v4();
+    let post_id = common::create_test_post(&ctx.pool, "title", "content", author_id).await;
+
+    let root_id = common::create_test_comment(&ctx.pool, "root", post_id, author_id, None).await;
+    common::create_test_comment(&ctx.pool, "reply", post_id, author_id, Some(root_id)).await;
+    common::create_test_comment(&ctx.pool, "other", post_id, author_id, None).await;
+
+    let post = service.get_post(post_id).await.unwrap().unwrap();
+    assert_eq!(post.comment_count, 3);
+
+    // Deleting the root comment also removes its reply via ON DELETE CASCADE
+    sqlx::query!("DELETE FROM comments WHERE id = $1", root_id)
+        .execute(&ctx.pool)
+        .await
+        .unwrap();
+
+    let posts = service.get_posts(PageParams::new(1, 10)).await.unwrap();
+    assert_eq!(posts.items[0].comment_count, 1);
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_moderator_can_edit_and_delete_any_post(ctx: &TestDb) {
+    let service = PostService::new(ctx.pool.clone());
+    let author_id = Uuid::new_v4();
+    let moderator_id = common::create_test_user(&ctx.pool, "moderator", "moderator@example.com", "hash").await;
+    let moderator = Actor { id: moderator_id, role: Role::Moderator };
+    let post_id = common::create_test_post(&ctx.pool, "title", "abusive content", author_id).await;
+
+    let dto = UpdatePostDto {
+        title: None,
+        content: Some("[removed by moderator]".to_string()),
+    };
+    let post = match service.update_post(post_id, dto, &moderator).await.unwrap() {
+        MutationOutcome::Done(post) => post,
+        other => panic!("expected Done, got {:?}", other),
+    };
+    assert_eq!(post.content, "[removed by moderator]");
+    assert_eq!(post.author.id, author_id);
+
+    let result = service.delete_post(post_id, &moderator).await.unwrap();
+    assert!(matches!(result, MutationOutcome::Done(())));
+
+    let actions = sqlx::query!(
+        r#"SELECT action::TEXT AS "action!", target_author_id FROM moderator_actions WHERE target_id = $1 ORDER BY created_at"#,
+        post_id
+    )
+    .fetch_all(&ctx.pool)
+    .await
+    .unwrap();
+    let recorded: Vec<_> = actions.iter().map(|a| a.action.as_str()).collect();
+    assert_eq!(recorded, ["update_post", "delete_post"]);
+    assert!(actions.iter().all(|a| a.target_author_id == author_id));
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_author_edits_are_not_recorded_as_moderation(ctx: &TestDb) {
+    let service = PostService::new(ctx.pool.clone());
+    let moderator_id = common::create_test_user(&ctx.pool, "moderator", "moderator@example.com", "hash").await;
+    let moderator = Actor { id: moderator_id, role: Role::Moderator };
+    let post_id = common::create_test_post(&ctx.pool, "title", "content", moderator.id).await;
+
+    let result = service.delete_post(post_id, &moderator).await.unwrap();
+    assert!(matches!(result, MutationOutcome::Done(())));
+
+    let count = sqlx::query_scalar!(r#"SELECT COUNT(*) AS "count!" FROM moderator_actions"#)
+        .fetch_one(&ctx.pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 0);
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_create_post_requires_verified_email(ctx: &TestDb) {
+    let service = PostService::new(ctx.pool.clone());
+    let author_id = common::create_test_user(&ctx.pool, "author", "author@example.com", "hash").await;
+    sqlx::query!("UPDATE users SET email_verified_at = NULL WHERE id = $1", author_id)
+        .execute(&ctx.pool)
+        .await
+        .unwrap();
+
+    let dto = CreatePostDto {
+        title: Faker.fake::<String>(),
+        content: Faker.fake::<String>(),
+    };
+    let result = service.create_post(dto.clone(), author_id).await;
+    assert!(matches!(result, Err(AppError::Forbidden(_))));
+
+    common::verify_user_email(&ctx.pool, author_id).await;
+    assert!(service.create_post(dto, author_id).await.is_ok());
+}
@@ -0,0 +1,57 @@
+use rust_study::services::session_cache::{SessionCache, SessionState};
+use std::collections::HashSet;
+use std::time::Duration;
+use uuid::Uuid;
+
+fn state(token_version: i32) -> SessionState {
+    SessionState {
+        token_version,
+        revoked_jtis: HashSet::new(),
+    }
+}
+
+#[test]
+fn test_insert_and_invalidate() {
+    let cache = SessionCache::new(Duration::from_secs(60));
+    let user_id = Uuid::new_v4();
+    assert!(cache.get(user_id).is_none());
+
+    cache.insert(user_id, state(0), cache.generation(user_id));
+    assert_eq!(cache.get(user_id).unwrap().token_version, 0);
+
+    cache.invalidate(user_id);
+    assert!(cache.get(user_id).is_none());
+
+    cache.insert(user_id, state(1), cache.generation(user_id));
+    assert_eq!(cache.get(user_id).unwrap().token_version, 1);
+}
+
+#[test]
+fn test_state_loaded_before_invalidate_is_not_cached() {
+    let cache = SessionCache::new(Duration::from_secs(60));
+    let user_id = Uuid::new_v4();
+
+    // A request reads the pre-logout state from the database...
+    let generation = cache.generation(user_id);
+    // ...a logout for the same user finishes meanwhile...
+    cache.invalidate(user_id);
+    // ...and the request's insert must not bring the old state back
+    cache.insert(user_id, state(0), generation);
+    assert!(cache.get(user_id).is_none());
+
+    // The same applies when the user already had a cached entry
+    cache.insert(user_id, state(1), cache.generation(user_id));
+    let generation = cache.generation(user_id);
+    cache.invalidate(user_id);
+    cache.insert(user_id, state(1), generation);
+    assert!(cache.get(user_id).is_none());
+}
+
+#[test]
+fn test_zero_ttl_disables_the_cache() {
+    let cache = SessionCache::new(Duration::ZERO);
+    let user_id = Uuid::new_v4();
+
+    cache.insert(user_id, state(0), cache.generation(user_id));
+    assert!(cache.get(user_id).is_none());
+}
@@ -0,0 +1,270 @@
+use actix_web::{http::header, http::StatusCode, test, web, App};
+use rust_study::{
+    errors::AppError,
+    handlers::user_handler,
+    mail::FileMailer,
+    middleware::auth_middleware::Auth,
+    models::user::{AuthResponse, ChangePasswordDto, CreateUserDto, LoginDto, LoginOutcome, UpdateProfileDto, User},
+    services::{auth_service::AuthService, email_verification_service::EmailVerificationService, user_service::UserService},
+};
+use serde_json::json;
+use serial_test::serial;
+use std::{path::PathBuf, sync::Arc};
+use test_context::test_context;
+use uuid::Uuid;
+
+mod common;
+use common::TestDb;
+
+const PASSWORD: &str = "password123";
+
+// 인증 메일을 테스트 디렉터리에 저장하고, AuthService와 폐기 상태 캐시를 공유하는 UserService를 만듭니다.
+fn services(pool: &sqlx::PgPool, dir: &PathBuf) -> (AuthService, UserService) {
+    let config = common::test_config();
+    let auth = AuthService::new(pool.clone(), config.clone());
+    let mailer = Arc::new(FileMailer::new(dir, &config.mail.from));
+    let verification = Arc::new(EmailVerificationService::new(pool.clone(), config.clone(), mailer));
+    let users = UserService::new(pool.clone(), config, auth.sessions(), verification);
+    (auth, users)
+}
+
+// 이메일 인증을 마친 사용자를 만들고 로그인합니다.
+async fn verified_user(auth: &AuthService, pool: &sqlx::PgPool, username: &str) -> (User, AuthResponse) {
+    let email = format!("{}@example.com", username.to_lowercase());
+    let user = auth
+        .register(CreateUserDto {
+            username: username.to_string(),
+            email: email.clone(),
+            password: PASSWORD.to_string(),
+        })
+        .await
+        .unwrap();
+    common::verify_user_email(pool, user.id).await;
+    let session = auth
+        .login(LoginDto {
+            email,
+            password: PASSWORD.to_string(),
+        })
+        .await
+        .and_then(LoginOutcome::tokens)
+        .unwrap();
+    (user, session)
+}
+
+fn sent_count(dir: &PathBuf) -> usize {
+    std::fs::read_dir(dir).map_or(0, |entries| entries.count())
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_update_profile_enforces_unique_username(ctx: &TestDb) {
+    let dir = std::env::temp_dir().join(format!("mail-test-{}", Uuid::new_v4()));
+    let (auth, users) = services(&ctx.pool, &dir);
+    let (alice, _) = verified_user(&auth, &ctx.pool, "alice").await;
+    verified_user(&auth, &ctx.pool, "bob").await;
+
+    let result = users
+        .update_profile(alice.id, UpdateProfileDto { username: Some("BOB".to_string()), email: None, current_password: None })
+        .await;
+    assert!(matches!(result, Err(AppError::Conflict(_))));
+    let result = users
+        .update_profile(alice.id, UpdateProfileDto {
+            username: None,
+            email: Some("bob@example.com".to_string()),
+            current_password: Some(PASSWORD.to_string()),
+        })
+        .await;
+    assert!(matches!(result, Err(AppError::Conflict(_))));
+
+    // Changing only the case of your own name is allowed, and keeps the email verified
+    let updated = users
+        .update_profile(alice.id, UpdateProfileDto { username: Some("Alice".to_string()), email: None, current_password: None })
+        .await
+        .unwrap();
+    assert_eq!(updated.username, "Alice");
+    assert!(updated.email_verified_at.is_some());
+    assert!(updated.updated_at > alice.updated_at);
+    assert_eq!(sent_count(&dir), 0);
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_changing_email_requires_verification(ctx: &TestDb) {
+    let dir = std::env::temp_dir().join(format!("mail-test-{}", Uuid::new_v4()));
+    let (auth, users) = services(&ctx.pool, &dir);
+    let (alice, _) = verified_user(&auth, &ctx.pool, "alice").await;
+
+    let change_email = |current_password: Option<&str>| UpdateProfileDto {
+        username: None,
+        email: Some("new-alice@example.com".to_string()),
+        current_password: current_password.map(str::to_string),
+    };
+
+    // A stolen session alone is not enough to move the account to another address
+    for current_password in [None, Some("wrong-password")] {
+        match users.update_profile(alice.id, change_email(current_password)).await {
+            Err(AppError::Validation { fields, .. }) => assert_eq!(fields[0].field, "current_password"),
+            other => panic!("expected Validation, got {:?}", other),
+        }
+    }
+    assert_eq!(users.get_user(alice.id).await.unwrap().email, "alice@example.com");
+    assert_eq!(sent_count(&dir), 0);
+
+    let updated = users.update_profile(alice.id, change_email(Some(PASSWORD))).await.unwrap();
+    assert_eq!(updated.email, "new-alice@example.com");
+    assert!(updated.email_verified_at.is_none());
+
+    // The verification email goes to the new address
+    let files: Vec<_> = std::fs::read_dir(&dir).unwrap().map(|entry| entry.unwrap().path()).collect();
+    assert_eq!(files.len(), 1);
+    let message = std::fs::read_to_string(&files[0]).unwrap();
+    assert!(message.contains("To: new-alice@example.com\r\n"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_change_password_requires_current_password(ctx: &TestDb) {
+    let dir = std::env::temp_dir().join(format!("mail-test-{}", Uuid::new_v4()));
+    let (auth, users) = services(&ctx.pool, &dir);
+    let (alice, session) = verified_user(&auth, &ctx.pool, "alice").await;
+
+    let result = users
+        .change_password(alice.id, ChangePasswordDto {
+            current_password: "wrong-password".to_string(),
+            password: "new-password456".to_string(),
+        })
+        .await;
+    match result {
+        Err(AppError::Validation { fields, .. }) => assert_eq!(fields[0].field, "current_password"),
+        other => panic!("expected Validation, got {:?}", other),
+    }
+    let result = users
+        .change_password(alice.id, ChangePasswordDto {
+            current_password: PASSWORD.to_string(),
+            password: "short".to_string(),
+        })
+        .await;
+    assert!(matches!(result, Err(AppError::Validation { .. })));
+    assert!(auth.authenticate(&session.token).await.is_ok());
+
+    users
+        .change_password(alice.id, ChangePasswordDto {
+            current_password: PASSWORD.to_string(),
+            password: "new-password456".to_string(),
+        })
+        .await
+        .unwrap();
+
+    // Every session issued with the old password stops working right away
+    assert!(auth.authenticate(&session.token).await.is_err());
+    assert!(auth.refresh(&session.refresh_token).await.is_err());
+    let login = |password: &str| LoginDto {
+        email: "alice@example.com".to_string(),
+        password: password.to_string(),
+    };
+    assert!(auth.login(login(PASSWORD)).await.is_err());
+    assert!(auth.login(login("new-password456")).await.is_ok());
+}
+
+#[test_context(TestDb)]
+#[tokio::test]
+#[serial]
+async fn test_change_password_guesses_share_the_login_lockout(ctx: &TestDb) {
+    let dir = std::env::temp_dir().join(format!("mail-test-{}", Uuid::new_v4()));
+    let mut config = (*common::test_config()).clone();
+    config.security.login_account_free_attempts = 0;
+    config.security.login_account_lockout_threshold = 3;
+    config.security.login_backoff_base_secs = 0;
+    let config = Arc::new(config);
+    let auth = AuthService::new(ctx.pool.clone(), config.clone());
+    let mailer = Arc::new(FileMailer::new(&dir, &config.mail.from));
+    let verification = Arc::new(EmailVerificationService::new(ctx.pool.clone(), config.clone(), mailer));
+    let users = UserService::new(ctx.pool.clone(), config, auth.sessions(), verification);
+    let (alice, _) = verified_user(&auth, &ctx.pool, "alice").await;
+
+    let change = |current_password: &str| ChangePasswordDto {
+        current_password: current_password.to_string(),
+        password: "new-password456".to_string(),
+    };
+    for _ in 0..3 {
+        assert!(matches!(
+            users.change_password(alice.id, change("wrong-password")).await,
+            Err(AppError::Validation { .. })
+        ));
+    }
+
+    // Once the account is locked, even the right password is not checked, here or at login
+    assert!(matches!(
+        users.change_password(alice.id, change(PASSWORD)).await,
+        Err(AppError::RateLimited { .. })
+    ));
+    let login = LoginDto {
+        email: "alice@example.com".to_string(),
+        password: PASSWORD.to_string(),
+    };
+    assert!(matches!(auth.login(login).await, Err(AppError::RateLimited { .. })));
+}
+
+#[test_context(TestDb)]
+#[actix_web::test]
+#[serial]
+async fn test_me_endpoints(ctx: &TestDb) {
+    let dir = std::env::temp_dir().join(format!("mail-test-{}", Uuid::new_v4()));
+    let (auth, users) = services(&ctx.pool, &dir);
+    let (_, session) = verified_user(&auth, &ctx.pool, "alice").await;
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::from(common::test_config()))
+            .app_data(web::Data::new(auth))
+            .app_data(web::Data::new(users))
+            .service(
+                web::scope("/api/users/me")
+                    .wrap(Auth::required())
+                    .route("", web::get().to(user_handler::get_me))
+                    .route("", web::patch().to(user_handler::update_me))
+                    .route("/password", web::post().to(user_handler::change_password)),
+            ),
+    )
+    .await;
+    let bearer = (header::AUTHORIZATION, format!("Bearer {}", session.token));
+
+    let req = test::TestRequest::get().uri("/api/users/me").to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::UNAUTHORIZED);
+
+    let req = test::TestRequest::get().uri("/api/users/me").insert_header(bearer.clone()).to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["username"], "alice");
+    assert!(body.get("password_hash").is_none());
+
+    let req = test::TestRequest::patch()
+        .uri("/api/users/me")
+        .insert_header(bearer.clone())
+        .set_json(json!({ "username": "[deleted]" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let req = test::TestRequest::patch()
+        .uri("/api/users/me")
+        .insert_header(bearer.clone())
+        .set_json(json!({ "username": "alice_renamed" }))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["username"], "alice_renamed");
+    assert_eq!(body["email"], "alice@example.com");
+
+    let req = test::TestRequest::post()
+        .uri("/api/users/me/password")
+        .insert_header(bearer.clone())
+        .set_json(json!({ "current_password": PASSWORD, "password": "new-password456" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::NO_CONTENT);
+
+    // The token used to change the password has been revoked
+    let req = test::TestRequest::get().uri("/api/users/me").insert_header(bearer).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,110 @@
+use rust_study::config::SecurityConfig;
+use rust_study::errors::AppError;
+use rust_study::models::comment::CreateCommentDto;
+use rust_study::models::post::{CreatePostDto, UpdatePostDto};
+use rust_study::models::user::{CreateUserDto, UpdateProfileDto};
+use rust_study::models::validation::validate_password;
+use validator::Validate;
+
+fn field_names(err: AppError) -> Vec<String> {
+    match err {
+        AppError::Validation { fields, .. } => fields.into_iter().map(|f| f.field).collect(),
+        other => panic!("expected validation error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_create_post_dto_rules() {
+    let valid = CreatePostDto {
+        title: "Hello".to_string(),
+        content: "World".to_string(),
+    };
+    assert!(valid.validate().is_ok());
+
+    let invalid = CreatePostDto {
+        title: "   ".to_string(),
+        content: "x".repeat(50_001),
+    };
+    let err = AppError::from(invalid.validate().unwrap_err());
+    assert_eq!(field_names(err), vec!["content", "title"]);
+
+    let too_long = CreatePostDto {
+        title: "t".repeat(256),
+        content: "content".to_string(),
+    };
+    assert!(too_long.validate().is_err());
+}
+
+#[test]
+fn test_update_post_dto_skips_missing_fields() {
+    let dto = UpdatePostDto {
+        title: None,
+        content: Some("new content".to_string()),
+    };
+    assert!(dto.validate().is_ok());
+
+    let dto = UpdatePostDto {
+        title: Some(String::new()),
+        content: None,
+    };
+    assert!(dto.validate().is_err());
+}
+
+#[test]
+fn test_create_comment_dto_rules() {
+    let dto = CreateCommentDto {
+        content: "x".repeat(10_001),
+        parent_id: None,
+    };
+    assert!(dto.validate().is_err());
+}
+
+#[test]
+fn test_create_user_dto_rules() {
+    let dto = CreateUserDto {
+        username: "ab".to_string(),
+        email: "not-an-email".to_string(),
+        password: "password123".to_string(),
+    };
+    let err = AppError::from(dto.validate().unwrap_err());
+    assert_eq!(field_names(err), vec!["email", "username"]);
+}
+
+#[test]
+fn test_reserved_and_optional_profile_fields() {
+    // The placeholder shown for deleted authors cannot be taken as a username
+    let dto = CreateUserDto {
+        username: "[Deleted]".to_string(),
+        email: "someone@example.com".to_string(),
+        password: "password123".to_string(),
+    };
+    let err = AppError::from(dto.validate().unwrap_err());
+    assert_eq!(field_names(err), vec!["username"]);
+
+    assert!(UpdateProfileDto::default().validate().is_ok());
+    let dto = UpdateProfileDto {
+        username: Some("[deleted]".to_string()),
+        email: Some("not-an-email".to_string()),
+        current_password: None,
+    };
+    let err = AppError::from(dto.validate().unwrap_err());
+    assert_eq!(field_names(err), vec!["email", "username"]);
+}
+
+#[test]
+fn test_password_strength() {
+    let config = SecurityConfig::default();
+    assert!(validate_password("password123", &config).is_ok());
+
+    // Too short and no digit: both problems are reported
+    let err = validate_password("abc", &config).unwrap_err();
+    assert_eq!(field_names(err).len(), 2);
+
+    let strict = SecurityConfig {
+        password_require_uppercase: true,
+        password_require_symbol: true,
+        ..SecurityConfig::default()
+    };
+    assert!(validate_password("password123", &strict).is_err());
+    assert!(validate_password("Password123!", &strict).is_ok());
+}